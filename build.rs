@@ -0,0 +1,96 @@
+//! build.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The build script injects the build info reported by the `version` RTT command (see build_info.rs) as compile-time
+//! environment variables:
+//!
+//! 1. HSV_GIT_HASH: the short hash of the checked out commit, suffixed with `-dirty` for uncommitted changes, or
+//!    `unknown` outside a git checkout
+//! 2. HSV_BUILD_TIME: the UTC build time as `YYYY-MM-DDTHH:MM:SSZ`, taken from SOURCE_DATE_EPOCH when set so a
+//!    reproducible build stays reproducible
+//!
+//! The script reruns when the git state changes, and when a source file or the manifest changes, so the `-dirty`
+//! marker follows uncommitted edits. The build time is therefore that of the build after the last commit, checkout, or
+//! source edit rather than of every incremental rebuild.
+
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Constants
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// fn git() runs git with args, returning its trimmed output or None if it could not be run or failed
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8(output.stdout).ok()?.trim().into())
+}
+
+/// fn git_hash() returns the short hash of HEAD, suffixed with `-dirty` for uncommitted changes to tracked files
+fn git_hash() -> Option<String> {
+    let hash = git(&["rev-parse", "--short=8", "HEAD"])?;
+    let dirty =
+        git(&["status", "--porcelain", "--untracked-files=no"]).is_some_and(|s| !s.is_empty());
+    Some(if dirty { hash + "-dirty" } else { hash })
+}
+
+/// fn utc_timestamp() formats seconds since the UNIX epoch as `YYYY-MM-DDTHH:MM:SSZ` (the days to civil date
+/// conversion from Howard Hinnant's date algorithms)
+fn utc_timestamp(epoch_seconds: u64) -> String {
+    let days = (epoch_seconds / SECONDS_PER_DAY) as i64;
+    let second_of_day = epoch_seconds % SECONDS_PER_DAY;
+
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        second_of_day / 3600,
+        second_of_day / 60 % 60,
+        second_of_day % 60
+    )
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/index");
+    println!("cargo:rerun-if-changed=.git/refs");
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+
+    let hash = git_hash().unwrap_or_else(|| "unknown".into());
+    let epoch_seconds = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs())
+        });
+
+    println!("cargo:rustc-env=HSV_GIT_HASH={}", hash);
+    println!(
+        "cargo:rustc-env=HSV_BUILD_TIME={}",
+        utc_timestamp(epoch_seconds)
+    );
+}
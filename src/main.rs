@@ -15,90 +15,217 @@
 //! 1. Red LED connected to P0_10 (e08)
 //! 2. Green LED connected to P0_09 (e09)
 //! 3. Blue LED connected to P1_02 (e16)
-//! 4. Pot output connected to P0_04 (e16)
+//! 4. Pot output connected to P0_04 (e02)
+//! 5. Optional: polarity jumper from P0_12 (e12) to GND when a common-cathode RGB LED is used
+//! 6. Optional: photoresistor voltage divider output connected to P0_03 (e01) for the ambient light mode
+//! 7. Optional: UART daisy-chain, this board's TX on P0_13 (e15) to the next board's RX on P0_01 (e14), grounds joined
 //!
-//! Note: the adc is sampled at ~40usecs and is averaged to a 100msec refresh rate. Most interactions are handled via
-//! interrupts while the main event loop accumulates and averages the pot ADC value.
+//! The analog edge pins e00, e01, and e02 can be re-assigned at build time, e.g. to give an axis its own pot (see
+//! adc_channels.rs).
+//!
+//! Note: the adc is sampled at ~40usecs and is averaged to a 100msec refresh rate (optionally low-pass filtered across
+//! refreshes, see adc_filter.rs). Most interactions are handled via interrupts while the main event loop accumulates
+//! and averages the pot ADC value.
 //!
 //! The RGB physical color is controled by a custom-made, Timer-based pulse width modulation (PWM) of each RGB pin voltage
+//! (or, with the `ppi-pwm` cargo feature, by TIMER2 compare events routed through PPI to GPIOTE pin tasks, or, with the
+//! `hw-pwm` cargo feature, by the PWM0 peripheral)
+//!
+//! LED polarity detection: the LED wiring (common-anode vs common-cathode) cannot be reliably sensed through the LED
+//! pins themselves. Reading an LED pin back as an input only sees the LED's forward-voltage drop (roughly 1.8V-3V
+//! depending upon the die) pulled against the nRF's ~13k internal pull resistor, which lands in the undefined region
+//! of the GPIO input thresholds for some colors. Instead, a configuration jumper on P0_12 (e12) is read at boot with
+//! the internal pull-up enabled: left open (high) selects the default common-anode wiring, tied to GND (low) selects
+//! common-cathode.
+//!
+//! Panics: the panic handler prints the panic message on RTT like panic-rtt-target, then stops the RGB PWM and latches
+//! the LED at the distinct PANIC_COLOR with a blocking software PWM loop, so a panic is obvious even without a debugger
+//! attached. A panic before the LED pins are set up (e.g. a rejected AppConfig) leaves the LED dark.
 
 #![no_std]
 #![no_main]
 
 mod utils;
 
-use panic_rtt_target as _;
-use rtt_target::rtt_init_print;
-//use rtt_target::rprintln;
 use cortex_m_rt::entry;
+#[cfg(feature = "display")]
+use microbit::display::nonblocking::Display;
 use microbit::{
     board::Board,
-    display::nonblocking::Display,
     hal::{
-        Timer,
+        Rng, Timer,
         gpio::{
-            Floating, Input, Level, Output, PushPull,
-            p0::{P0_04, P0_09, P0_10},
+            Floating, Input, Level, Output, Pin, PullUp, PushPull,
+            p0::{P0_01, P0_09, P0_10, P0_12, P0_13},
             p1::P1_02,
         },
         gpiote::Gpiote,
         saadc,
         saadc::{Saadc, SaadcConfig},
+        uarte,
     },
-    pac::{Interrupt, NVIC, TIMER0, TIMER1, TIMER2, TIMER3, interrupt},
+    pac::{Interrupt, NVIC, TIMER0, TIMER1, TIMER2, TIMER3, TIMER4, interrupt},
 };
+use rtt_target::{ChannelMode, rprintln, rtt_init, set_print_channel, with_terminal_channel};
 
+use core::fmt::Write as _;
+use core::panic::PanicInfo;
 use core::sync::atomic::{AtomicBool, AtomicU32, Ordering::SeqCst};
 
-use crate::utils::color_control::{ColorControler, STARTING_HSV};
-use crate::utils::hsv_display::{HSVDisplay, HSVPage};
+use crate::utils::adc_channels::{
+    ADC_CHANNELS, ADC_PIN_COUNT, AdcInput, AdcPins, LIGHT_CHANNEL, POT_CHANNEL, has_axis_pot,
+};
+use crate::utils::adc_filter::{ADC_MEDIAN_WINDOW, AdcFilter, MedianFilter};
+use crate::utils::adc_scaling::{
+    ADC_CORRECTION_LUT, MAX_ADC_VALUE, MAX_CENTER_SNAP, adc_to_percentage, average_samples,
+    center_snap, preprocess_sample,
+};
+use crate::utils::ambient::{AmbientBounds, AmbientLight};
+use crate::utils::app_config::AppConfigBuilder;
+use crate::utils::arbitration::{
+    AXIS_COUNT, ArbitrationPolicy, AxisArbiter, POT_ACTIVITY_THRESHOLD,
+};
+use crate::utils::auto_balance::{
+    BALANCE_DRIVE_DUTY, BALANCE_SAMPLES, BALANCE_SETTLE_MS, balance_factors,
+};
+use crate::utils::build_info::BUILD_INFO;
+use crate::utils::button_map::{Button, PageTurn, SWAP_BUTTONS, button_for, page_turn};
+use crate::utils::clock::{Clock, TimerClock};
+use crate::utils::color_control::{ColorControler, Polarity, PotWrite, STARTING_HSV};
+use crate::utils::color_match::ColorMatch;
+use crate::utils::color_rng::{ColorRng, FIXED_RNG_SEED};
+use crate::utils::color_space::ColorSpace;
+use crate::utils::color_watch::{ColorSource, ColorWatch};
+use crate::utils::commit_debounce::CommitDebouncer;
+use crate::utils::config::{Config, ConfigError, DEFAULT_CONFIG};
+use crate::utils::debounce::{Debouncer, PressAction, press_action};
+use crate::utils::demo_reel::{DEMO_DWELL_MS, DemoReel};
+use crate::utils::display_sleep::DisplaySleep;
+use crate::utils::effects::Effect;
+use crate::utils::fade::HueDirection;
+use crate::utils::hsv_display::{AxisState, HSVDisplay, HSVPage};
+use crate::utils::hsv_rgb_convert::{Rgb, normalize_hue};
+use crate::utils::hue_rate::DEFAULT_HUE_RATE_CAP;
+use crate::utils::hue_scroll::{HueScroll, ScrollUpdate, pinned_extreme};
+use crate::utils::inactivity::{INACTIVITY_FADE_MS, Inactivity};
+use crate::utils::irq_stats::{Irq, IrqCounters, rate_per_second};
+use crate::utils::latency::LatencyProbe;
+use crate::utils::midi_cc::{MAX_CC_NUMBER, MIDI_CHANNEL_COUNT, MidiCc};
+use crate::utils::mode::Mode;
+use crate::utils::persist::HsvSaver;
+use crate::utils::pot_mapping::PotMapper;
+use crate::utils::power_toggle::{POWER_FADE_MS, PowerAction, PowerToggle};
+use crate::utils::rtt_command::{
+    self, Command, CommandHistory, CommandReader, CssColor, HsvLiteral,
+};
+use crate::utils::scheduler::Scheduler;
+use crate::utils::serial_control::{SerialCommand, SerialControl};
+use crate::utils::slideshow::{Slideshow, dwell_from_pot};
+use crate::utils::standby::{Standby, StandbyUpdate, standby_due};
+use crate::utils::storage::{ConfigStore, LoadError};
+use crate::utils::timers::TIMER_TICKS_PER_MS;
+use crate::utils::uart_link::UartLink;
+use crate::utils::value_floor::ValueFloor;
 use critical_section_lock_mut::LockMut;
+use embedded_hal::digital::InputPin;
+#[cfg(feature = "ppi-pwm")]
+use microbit::hal::ppi;
 
 /// Type definitions - the top 4 definitions are used in color_control.rs while
-/// the last (PotType) is referenced here just for convience in assigning the hardware
+/// the rest are referenced here just for convience in assigning the hardware
 type RedPinType = P0_10<Output<PushPull>>; //e08
 type GreenPinType = P0_09<Output<PushPull>>; //e09
 type BluePinType = P1_02<Output<PushPull>>; //e16
+#[cfg(not(feature = "hw-pwm"))]
 type ColorTimer = Timer<TIMER2>;
-type PotType = P0_04<Input<Floating>>; //e02
+type PolarityJumperType = P0_12<Input<PullUp>>; //e12
+type ButtonPinType = Pin<Input<Floating>>; // A and B buttons
+type LinkTxPinType = P0_13<Output<PushPull>>; //e15
+type LinkRxPinType = P0_01<Input<Floating>>; //e14
 
 /// Globals Constants
-const DEBOUNCE_TIME: u32 = 100 * 1_000_000 / 1000; // 100ms at 1MHz count rate.
-const MAX_ADC_VALUE: i16 = (1_i16 << 14) - 1_i16; // max value of the ADC output
-const MAX_ADC_THRESHOLD: f32 = MAX_ADC_VALUE as f32 * 0.98; // 16,053; clamp upper ADC bound slightly below max (98%)
-const MIN_ADC_THRESHOLD: f32 = 10f32; // clamp lower ADC bound to 10
-const REFRESH_RATE_MS: u32 = 100; // update rate of the ADC
-const TIMER_TICKS_PER_MS: u32 = 1_000_000u32 / 1000; // TIMER peripheral clock rate in msecs
-const REFRESH_RATE_TICKS: u32 = TIMER_TICKS_PER_MS * REFRESH_RATE_MS; // 100ms in TIMER clock ticks
+const ADC_ERROR_CODE: u8 = 2; // matrix flash code shown when an ADC read fails
+const FLASH_ERROR_CODE: u8 = 3; // matrix flash code shown when the config could not be loaded from or saved to flash
+const CONFIG_CORRUPT_CODE: u8 = 4; // matrix flash code shown when the saved config failed its CRC check at boot
+const HOUSEKEEPING_PERIOD_MS: u32 = 100; // how often the scheduled config commit, matrix blanking, and color save checks run
+const LATENCY_CHANGE_THRESHOLD: f32 = 0.02; // pot changes larger than 2% of full scale are timed from apply to render
+const PANIC_COLOR: Rgb = Rgb {
+    r: 0.6,
+    g: 0.0,
+    b: 0.0,
+}; // deep red, latched on the LED by the panic handler
+const PANIC_PWM_STEPS: u32 = 100; // resolution of the panic handler's software PWM
+const PANIC_STEP_CYCLES: u32 = 6_400; // 100us per panic PWM step at the 64MHz core clock, i.e. 10ms frames
 
 // Global Mutexes for interupt handlers
 static GPIOTE_PERIPHERAL: LockMut<Gpiote> = LockMut::new(); // GPIOTE for button presses
-static DEBOUNCE_TIMER: LockMut<Timer<TIMER1>> = LockMut::new(); // Debounce TIMER to protect button presses
+static BUTTONS: LockMut<[ButtonPinType; 2]> = LockMut::new(); // A (index 0) and B (index 1) button pins, for reading the held state
+static DEBOUNCER: LockMut<Debouncer<TimerClock<TIMER1>>> = LockMut::new(); // Debounce TIMER to protect button presses
 static ADC_ACC_TIMER: LockMut<Timer<TIMER3>> = LockMut::new(); // ADC accumulator timer - indicates when to stop co-adding and to average
 static DISPLAY: LockMut<HSVDisplay<TIMER0>> = LockMut::new(); // non-blocking display update timer
 static COLOR_CONTROLER: LockMut<ColorControler> = LockMut::new(); // set the RGB pin states based upon the HSV parameter and ADC result
-static ADC_ACCUMULATOR_VALUE: AtomicU32 = AtomicU32::new(0); // ADC co-adding sum: can accumulate max adc value for more than 5 seconds at 20us sample rate before overflow
+static REFRESH_TICKS: AtomicU32 = AtomicU32::new(0); // ADC averaging (refresh) period in TIMER clock ticks, from the AppConfig
 static ADC_READY_READ: AtomicBool = AtomicBool::new(false); // indicator to main loop that ADC is ready to be averaged and update HSV
+static SYSTEM_CLOCK: LockMut<TimerClock<TIMER4>> = LockMut::new(); // free-running 1MHz timestamp counter, wraps every ~71 minutes
+static FRAME_START_US: AtomicU32 = AtomicU32::new(0); // SYSTEM_CLOCK timestamp of the most recently started PWM color frame
+static BUTTON_ACTIVITY: AtomicBool = AtomicBool::new(false); // set by the GPIOTE handler on every debounced button press, for the inactivity timer
+static POWERED_OFF: AtomicBool = AtomicBool::new(false); // set while the soft power is off, so the GPIOTE handler ignores presses
+static INPUT_READY: AtomicBool = AtomicBool::new(false); // set once the boot has finished, the GPIOTE handler ignores presses before
+static PWM_INTERRUPTS: AtomicU32 = AtomicU32::new(0); // PWM interrupts handled since the last `pwm` report
+static PWM_BUSY_US: AtomicU32 = AtomicU32::new(0); // usecs spent in the PWM handler since the last `pwm` report
+static IRQ_COUNTERS: IrqCounters = IrqCounters::new(); // runs of each interrupt handler since the last `irqs` report
+static POLARITY_JUMPER_LOW: AtomicBool = AtomicBool::new(false); // polarity jumper state read at boot, for the panic handler which can't borrow the ColorControler
 
 /// TIMER0 Interupt handler (nrf52833 Peripheral Vecotr Table Entry #8)
 ///
 /// Handles the Non-Blocking Display Timer interrupt. This timeout is set internally by the display::nonblocking::Display module.
 /// HSVDisplay<T>::display() fn is a simple wrapper around the display::nonblocking::Display::handle_display_event fn.
+/// Whether this interrupt is a row switch (CC[0]) rather than a greyscale step (CC[1]) is peeked first, so HSVDisplay
+/// can swap in a newly rendered glyph at a frame boundary. Without the `display` feature TIMER0 is not used.
+#[cfg(feature = "display")]
 #[interrupt]
 fn TIMER0() {
+    IRQ_COUNTERS.record(Irq::Timer0);
+    // SAFETY: a read-only peek at an event register; the Display itself still checks and clears it below
+    let row_switched = unsafe { (*TIMER0::ptr()).events_compare[0].read().bits() != 0 };
     DISPLAY.with_lock(|display| {
-        display.handle_display_event();
+        display.handle_display_event(row_switched);
     });
 }
 
 /// TIMER2 Interupt handler (nrf52833 Peripheral Vecotr Table Entry #10)
 ///
-/// Handles the ColorControler timer interrupt which changes the RGB LED color at the 100ms refresh rate
+/// Handles the ColorControler timer interrupt which changes the RGB LED color at the 100ms refresh rate.
+#[cfg(not(feature = "hw-pwm"))]
 #[interrupt]
 fn TIMER2() {
+    pwm_interrupt();
+}
+
+/// PWM0 Interupt handler (nrf52833 Peripheral Vecotr Table Entry #28)
+///
+/// Handles the ColorControler frame interrupt of the `hw-pwm` feature, raised at the end of every PWM0 sequence.
+#[cfg(feature = "hw-pwm")]
+#[interrupt]
+fn PWM0() {
+    pwm_interrupt();
+}
+
+/// fn pwm_interrupt() is the body of the RGB PWM interrupt handler, whichever peripheral raises it: it has the
+/// ColorControler render. When render() begins a new color frame, the frame start is timestamped for the latency
+/// measurement. The number of interrupts and the time spent handling them are recorded for the `pwm` CPU load report.
+fn pwm_interrupt() {
+    IRQ_COUNTERS.record(Irq::Pwm);
+    let start = now_us();
     COLOR_CONTROLER.with_lock(|color_controler| {
+        let frames = color_controler.frames_rendered();
         color_controler.render();
+        if color_controler.frames_rendered() != frames {
+            FRAME_START_US.store(now_us(), SeqCst);
+        }
     });
+    PWM_INTERRUPTS.fetch_add(1, SeqCst);
+    PWM_BUSY_US.fetch_add(now_us().wrapping_sub(start), SeqCst);
 }
 
 /// TIMER3 Interupt handler (nrf52833 Peripheral Vecotr Table Entry #26)
@@ -109,9 +236,10 @@ fn TIMER2() {
 /// final ADC result to the ColorControler instance
 #[interrupt]
 fn TIMER3() {
+    IRQ_COUNTERS.record(Irq::Timer3);
     ADC_ACC_TIMER.with_lock(|adc_acc_timer| {
         ADC_READY_READ.store(true, SeqCst);
-        adc_acc_timer.start(REFRESH_RATE_TICKS);
+        adc_acc_timer.start(REFRESH_TICKS.load(SeqCst));
     });
 }
 
@@ -119,48 +247,144 @@ fn TIMER3() {
 ///
 /// Handles interrupts originating from either the A or B btn press with anti-bouncing logic.
 /// First, this interupt handler checks that the debouncer timer has cooled down and, if so, will
-/// update the 5x5 LED matrix on the MB2 to represent the HSV setting
+/// update the 5x5 LED matrix on the MB2 to represent the HSV setting. A press while the other button
+/// is already held completes an A+B chord (handled by the main loop) and does not change the page, and a press
+/// while the matrix is blanked by the power save only re-lights the current page. While the soft power is off (see
+/// power_toggle.rs) presses are ignored, leaving the long press switching it back on to the main loop. The decision is
+/// press_action() (see debounce.rs), which also ignores any press before the boot has finished, and the direction a
+/// press rotates the page is page_turn() (see button_map.rs). The page changes under the DISPLAY lock, but the glyph is
+/// only rendered by the TIMER0 handler at the next frame boundary (see FrameSwap in hsv_display.rs), so a press never
+/// holds the lock (delaying the matrix scan) for a whole render.
 #[interrupt]
 fn GPIOTE() {
-    // check for bouncing using a 100ms timer based coolddown:
+    IRQ_COUNTERS.record(Irq::Gpiote);
+    let held = buttons_held();
+
+    // check for bouncing by comparing this press's timer capture against the last accepted press:
     let mut debounced = false;
-    DEBOUNCE_TIMER.with_lock(|debounce_timer| {
-        if debounce_timer.read() == 0 {
-            debounced = true;
-            debounce_timer.start(DEBOUNCE_TIME);
-        }
+    DEBOUNCER.with_lock(|debouncer| {
+        debounced = debouncer.press();
     });
+    // only an accepted press counts as interaction, so contact bounce can't restart the idle timers. A chord or a press
+    // while the soft power is off still counts, press_action() ignoring those only for the page
+    let input_ready = INPUT_READY.load(SeqCst);
+    if input_ready && debounced {
+        BUTTON_ACTIVITY.store(true, SeqCst);
+    }
 
     // grab a mutable reference to the Gpiote instance, determine which button sent the signal,
-    // reset the interrupt, and update the LED display HSV if debounced timer as timed out
+    // reset the interrupt, and update the LED display HSV if the press_action() decision is to act on it
     GPIOTE_PERIPHERAL.with_lock(|gpiote| {
-        if gpiote.channel0().is_event_triggered() {
-            //A button press
+        let button = if gpiote.channel0().is_event_triggered() {
             gpiote.channel0().reset_events();
-            if debounced {
-                DISPLAY.with_lock(|display| {
-                    display.left();
-                    display.render();
-                });
-            }
+            Button::A
         } else if gpiote.channel1().is_event_triggered() {
-            //B button press
             gpiote.channel1().reset_events();
-            if debounced {
-                DISPLAY.with_lock(|display| {
-                    display.right();
-                    display.render();
-                });
+            Button::B
+        } else {
+            return;
+        };
+        // the other button held makes this press the second half of an A+B chord
+        let other_held = held[button.other() as usize];
+
+        DISPLAY.with_lock(|display| {
+            match press_action(
+                input_ready,
+                debounced,
+                other_held,
+                POWERED_OFF.load(SeqCst),
+                display.is_blanked(),
+            ) {
+                PressAction::Ignore => {}
+                PressAction::Unblank => display.unblank(),
+                PressAction::TurnPage => {
+                    match page_turn(button, SWAP_BUTTONS) {
+                        PageTurn::Left => display.left(),
+                        PageTurn::Right => display.right(),
+                    }
+                    display.request_render();
+                }
             }
-        }
+        });
+    });
+}
+
+/// fn buttons_held() returns whether the [A, B] buttons are currently pressed (the buttons are active low)
+fn buttons_held() -> [bool; 2] {
+    let mut held = [false; 2];
+    BUTTONS.with_lock(|buttons| {
+        held = [buttons[0].is_low().unwrap(), buttons[1].is_low().unwrap()];
+    });
+    held
+}
+
+/// fn now_us() returns the current SYSTEM_CLOCK timestamp in usecs (one TIMER4 tick, see timers.rs). Timestamps wrap,
+/// so compare them with wrapping_sub.
+fn now_us() -> u32 {
+    let mut now = 0;
+    SYSTEM_CLOCK.with_lock(|system_clock| {
+        now = system_clock.now_ticks();
     });
+    now
+}
+
+/// fn panic() is the panic handler: with interrupts disabled for good, it prints the panic message on the RTT terminal
+/// channel (blocking so it isn't dropped), then latches the LED at PANIC_COLOR with a blocking software PWM loop
+#[inline(never)]
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    cortex_m::interrupt::disable();
+    with_terminal_channel(|term| {
+        term.set_mode(ChannelMode::BlockIfFull);
+        writeln!(term.write(0), "{}", info).ok();
+    });
+
+    // SAFETY: interrupts are disabled and this handler never returns, so nothing else touches these peripherals again.
+    // The pins and TIMER2 owned by the ColorControler (which may be mid-borrow) are taken over by writing the raw
+    // registers: TIMER2 is stopped so the ppi-pwm feature's PPI stops toggling the pins, and GPIOTE channels 2-4 are
+    // unconfigured and PWM0 disabled to hand the pins back to their GPIO output registers (all harmless without the
+    // ppi-pwm and hw-pwm features)
+    let (timer, pwm, gpiote, p0, p1) = unsafe {
+        (
+            &*TIMER2::ptr(),
+            &*microbit::pac::PWM0::ptr(),
+            &*microbit::pac::GPIOTE::ptr(),
+            &*microbit::pac::P0::ptr(),
+            &*microbit::pac::P1::ptr(),
+        )
+    };
+    timer.tasks_stop.write(|w| unsafe { w.bits(1) });
+    pwm.enable.write(|w| w.enable().disabled());
+    for channel in 2..5 {
+        gpiote.config[channel].reset();
+    }
+
+    let polarity = Polarity::from_jumper(POLARITY_JUMPER_LOW.load(SeqCst));
+    let channels = [
+        (PANIC_COLOR.r, p0, 10), // P0_10, red
+        (PANIC_COLOR.g, p0, 9),  // P0_09, green
+        (PANIC_COLOR.b, p1, 2),  // P1_02, blue
+    ];
+    loop {
+        for step in 0..PANIC_PWM_STEPS {
+            for &(level, port, pin) in &channels {
+                let on = step < (level * PANIC_PWM_STEPS as f32) as u32;
+                if polarity.pin_level(on) {
+                    port.outset.write(|w| unsafe { w.bits(1 << pin) });
+                } else {
+                    port.outclr.write(|w| unsafe { w.bits(1 << pin) });
+                }
+            }
+            cortex_m::asm::delay(PANIC_STEP_CYCLES);
+        }
+    }
 }
 
 /// fn init() is called once immediately prior to the main event loop to initialize the
 /// global MUTEX instances.
 ///  
 /// 1. initialize the 5x5 LED display to H
-/// 2. initialize the ColorControler instance physical pin states to illuminate the RGB LED
+/// 2. initialize the ColorControler physical pin states to illuminate the RGB LED
 /// 3. initialize the ADC accumulator timer
 fn init() {
     DISPLAY.with_lock(|display| {
@@ -172,7 +396,926 @@ fn init() {
     });
 
     ADC_ACC_TIMER.with_lock(|adc_acc_timer| {
-        adc_acc_timer.start(REFRESH_RATE_TICKS);
+        adc_acc_timer.start(REFRESH_TICKS.load(SeqCst));
+    });
+}
+
+/// fn reinit() brings the running peripherals back to a known-good state, e.g. when waking from sleep, without
+/// re-taking the Board or re-initializing any of the global MUTEX instances. It is idempotent, and only uses the
+/// locks, so it is safe to call from the wake path (or again at any time).
+///
+/// 1. restart the ColorControler PWM timer from the start of a fresh frame of the current color
+/// 2. restart the ADC accumulator timer for a full refresh period (samples accumulated so far are kept)
+/// 3. re-show the current page on the 5x5 LED display straight away
+fn reinit() {
+    COLOR_CONTROLER.with_lock(|color_controler| {
+        color_controler.restart();
+    });
+
+    ADC_ACC_TIMER.with_lock(|adc_acc_timer| {
+        adc_acc_timer.start(REFRESH_TICKS.load(SeqCst));
+    });
+
+    DISPLAY.with_lock(|display| {
+        display.reinit();
+    });
+}
+
+/// State owned by the main event loop which RTT commands are also allowed to inspect and change
+///
+/// 1. latency_probe: pot-change to render latency measurement
+/// 2. ambient: ambient light mode (Value follows the photoresistor light level)
+/// 3. rng: pseudo random generator for the random color jump
+/// 4. arbiter: decides whether the pot or RTT owns each of the H, S, and V axes
+/// 5. pot: most recent averaged pot percentage [0,1]
+/// 6. pot_mapper: coarse/fine mapping of the pot onto the selected axis
+/// 7. chord_held: whether the A+B chord was held at the previous refresh, to toggle fine mode once per chord
+/// 8. mode: the current operating mode
+/// 9. pwm_since: SYSTEM_CLOCK timestamp at which the PWM interrupt statistics were last reset
+/// 10. inactivity: the optional return to a default color after a period without interaction
+/// 11. config: the user settings persisted to flash (the per-axis pot input curves)
+/// 12. store: flash storage for config
+/// 13. link: the UART daisy-chain carrying the color to (and, with the `follower` feature, from) other boards
+/// 14. slideshow: the slideshow mode's fade-and-dwell cycle through the preset colors
+/// 15. history: the last few RTT commands, for replay with `!`
+/// 16. display_sleep: the optional blanking of the matrix after a period without button presses
+/// 17. color_space: whether the pages edit the H, S, and V components or the R, G, and B channels
+/// 18. irq_since: SYSTEM_CLOCK timestamp at which the interrupt counters were last reset
+/// 19. shimmer: the saturation and value swing run by the shimmer mode
+/// 20. config_commit: holds back writing a changed config to flash until it has been left alone for a while
+/// 21. balance_requested: an RTT `balance auto` waiting for the main loop, which owns the ADC, to run it
+/// 22. axis_pots: most recent averaged percentage [0,1] of each axis's own pot (see adc_channels.rs), None for an axis
+///     edited with the selected-page pot
+/// 23. center_snap: half-width of the window around the pot center which snaps to exactly 0.5, 0 for none
+/// 24. power: the soft power button (a long press of B alone), which also remembers the color while switched off
+/// 25. standby: the optional dim hue shimmer shown while the output is dark and untouched
+/// 26. demo: the demo reel stepping through the modes (a long press of A alone), stopped by any manual input
+/// 27. watch: the optional logging of every base color change with the code path behind it
+/// 28. hsv_saver: holds back saving the color to flash (see persist.rs) until the pots have been left alone for a while
+/// 29. serial: the line protocol setting the color from a host PC over the USB serial port (see serial_control.rs)
+/// 30. pot_filter: the optional IIR low-pass smoothing the pot percentage of the selected page (see adc_filter.rs)
+/// 31. hue_scroll: the optional scrolling of the hue while the pot is pinned at an end of its travel on the Hue page
+struct LoopState {
+    latency_probe: LatencyProbe,
+    ambient: AmbientLight,
+    rng: ColorRng,
+    arbiter: AxisArbiter,
+    pot: f32,
+    pot_mapper: PotMapper,
+    chord_held: bool,
+    mode: Mode,
+    pwm_since: u32,
+    inactivity: Inactivity,
+    config: Config,
+    store: ConfigStore,
+    link: UartLink,
+    slideshow: Slideshow,
+    history: CommandHistory,
+    display_sleep: DisplaySleep,
+    color_space: ColorSpace,
+    irq_since: u32,
+    shimmer: Option<Effect>,
+    config_commit: CommitDebouncer<Config>,
+    balance_requested: bool,
+    axis_pots: [Option<f32>; AXIS_COUNT],
+    center_snap: f32,
+    power: PowerToggle,
+    standby: Standby,
+    demo: DemoReel,
+    watch: ColorWatch,
+    hsv_saver: HsvSaver,
+    serial: SerialControl,
+    pot_filter: AdcFilter,
+    hue_scroll: HueScroll,
+}
+
+/// fn editing_color_space() returns whether the pages currently edit the HSV components or the RGB channels. The shade
+/// mode always edits S and V
+fn editing_color_space(state: &LoopState) -> ColorSpace {
+    if state.mode == Mode::Shade {
+        ColorSpace::Hsv
+    } else {
+        state.color_space
+    }
+}
+
+/// fn pot_for_axis() returns the reading of the pot which edits axis (indexed by HSVPage): its own pot if it has one,
+/// otherwise the selected-page pot. RTT holds record it, so the hold ends once that pot is turned
+fn pot_for_axis(state: &LoopState, axis: usize) -> f32 {
+    state.axis_pots[axis].unwrap_or(state.pot)
+}
+
+/// fn axis_states() returns who writes each axis (indexed by HSVPage) at now_us, for the page glyph: an axis awaits a
+/// Pickup while the selected-page pot has to pick up its value (only the selected page, and only without a pot of its
+/// own) or while its RTT hold has run out and waits for the pot to move, is Locked while RTT holds it, and is otherwise
+/// Free
+fn axis_states(state: &LoopState, page: HSVPage, now_us: u32) -> [AxisState; AXIS_COUNT] {
+    let mut states = [AxisState::Free; AXIS_COUNT];
+    for (axis, axis_state) in states.iter_mut().enumerate() {
+        let pot_pickup =
+            axis == page as usize && !has_axis_pot(page) && state.pot_mapper.awaiting_pickup();
+        *axis_state = if pot_pickup || state.arbiter.awaiting_pot(axis, now_us) {
+            AxisState::Pickup
+        } else if state.arbiter.is_held(axis) {
+            AxisState::Locked
+        } else {
+            AxisState::Free
+        };
+    }
+    states
+}
+
+/// fn handle_serial() applies a command received over the USB serial port (see serial_control.rs) and replies to it.
+/// The hue is refused in the shade mode, which locks it
+fn handle_serial(state: &mut LoopState, command: SerialCommand) {
+    let now = now_us();
+    state.inactivity.touch(now);
+    stop_demo(state);
+    match command {
+        SerialCommand::Get => {
+            let mut hsv = STARTING_HSV;
+            COLOR_CONTROLER.with_lock(|color_controler| {
+                hsv = color_controler.current_hsv();
+            });
+            state
+                .serial
+                .reply(format_args!("HSV {} {} {}", hsv.h, hsv.s, hsv.v));
+        }
+        SerialCommand::Set {
+            page: HSVPage::H, ..
+        } if state.mode == Mode::Shade => {
+            state
+                .serial
+                .reply(format_args!("ERR hue is locked in the shade mode"));
+        }
+        SerialCommand::Set { page, value } => {
+            COLOR_CONTROLER.with_lock(|color_controler| match page {
+                HSVPage::H => color_controler.update_hue(value, ColorSource::Serial),
+                HSVPage::S => color_controler.update_sat(value, ColorSource::Serial),
+                HSVPage::V => color_controler.update_value(value, ColorSource::Serial),
+            });
+            state
+                .arbiter
+                .rtt_set(page as usize, now, pot_for_axis(state, page as usize));
+            state.serial.reply(format_args!("OK"));
+        }
+    }
+}
+
+/// fn set_mode() switches the operating mode: any running effects and the ambient light mode are stopped, then the
+/// effect (or ambient light mode, or calibration display, or hue lock) belonging to the new mode is started. The
+/// shimmer mode runs the (RTT tunable) LoopState shimmer effect.
+fn set_mode(state: &mut LoopState, mode: Mode) {
+    COLOR_CONTROLER.with_lock(|color_controler| {
+        color_controler.clear_effects();
+        color_controler.set_value_scale(1.0);
+        color_controler.set_hue_lock(mode == Mode::Shade);
+        let effect = if mode == Mode::Shimmer {
+            state.shimmer
+        } else {
+            mode.effect()
+        };
+        if let Some(effect) = effect {
+            color_controler.push_effect(effect).ok();
+        }
+    });
+    state.ambient.set_enabled(mode == Mode::Ambient);
+    state.slideshow.set_slideshow(
+        mode == Mode::Slideshow,
+        state.slideshow.dwell_ms(),
+        state.slideshow.fade_ms(),
+    );
+    DISPLAY.with_lock(|display| {
+        display.set_calibration(mode == Mode::Calibration);
+        display.set_hue_locked(mode == Mode::Shade);
+        display.render();
+    });
+    state.mode = mode;
+    rprintln!("mode: {} - {}", mode.name(), mode.description());
+}
+
+/// fn start_demo() starts (or restarts) the demo reel at now, switching to its first mode (see demo_reel.rs)
+fn start_demo(state: &mut LoopState, now: u32) {
+    let mode = state.demo.start(state.mode, now);
+    rprintln!(
+        "demo: {}s per mode, any input stops it",
+        DEMO_DWELL_MS / 1000
+    );
+    set_mode(state, mode);
+}
+
+/// fn stop_demo() stops a running demo reel, returning to the mode in use before it started
+fn stop_demo(state: &mut LoopState) {
+    if let Some(mode) = state.demo.stop() {
+        rprintln!("demo: stopped");
+        set_mode(state, mode);
+    }
+}
+
+/// fn save_config() persists the LoopState config to flash, reporting a failure over RTT and on the matrix
+fn save_config(state: &mut LoopState) {
+    match state.store.save(&state.config) {
+        Ok(()) => rprintln!("config saved"),
+        Err(e) => {
+            rprintln!("config: flash write failed: {}", e);
+            DISPLAY.with_lock(|display| display.flash_code(FLASH_ERROR_CODE));
+        }
+    }
+}
+
+/// fn commit_config_task() is a scheduled task saving a changed config to flash once it has been left alone for the
+/// commit delay, so a burst of changes costs a single page erase
+fn commit_config_task(state: &mut LoopState, now: u32) {
+    if state.config_commit.poll(now).is_some() {
+        save_config(state);
+    }
+}
+
+/// fn save_color_task() is a scheduled task saving the color to flash once the pots have settled, so it is restored at
+/// the next boot even without a soft power-off (see persist.rs)
+fn save_color_task(state: &mut LoopState, now: u32) {
+    if !state.hsv_saver.poll(now) {
+        return;
+    }
+    let mut hsv = STARTING_HSV;
+    COLOR_CONTROLER.with_lock(|color_controler| {
+        hsv = color_controler.current_hsv();
+    });
+    if let Err(e) = state.store.save_hsv(&hsv) {
+        rprintln!("color: flash write failed: {}", e);
+        DISPLAY.with_lock(|display| display.flash_code(FLASH_ERROR_CODE));
+    }
+}
+
+/// fn display_sleep_task() is a scheduled task blanking the matrix after a period without button presses (a press
+/// re-lights it in the GPIOTE handler)
+fn display_sleep_task(state: &mut LoopState, now: u32) {
+    if state.display_sleep.poll(now) {
+        DISPLAY.with_lock(|display| display.blank());
+    }
+}
+
+/// fn measure_light() averages BALANCE_SAMPLES readings of the light sensor at channel into a fraction [0,1] of full
+/// scale, None if a read failed
+fn measure_light(adc: &mut Saadc, adc_pins: &mut AdcPins, channel: usize) -> Option<f32> {
+    let mut sum = 0.0;
+    for _ in 0..BALANCE_SAMPLES {
+        let raw_light = adc_pins.read(adc, channel).ok()?;
+        sum += preprocess_sample(raw_light) as f32 / MAX_ADC_VALUE as f32;
+    }
+    Some(sum / BALANCE_SAMPLES as f32)
+}
+
+/// fn auto_calibrate_balance() runs the closed-loop channel balance calibration (see auto_balance.rs) with the light
+/// sensor (see adc_channels.rs) facing the LED. The LED is driven dark and then at BALANCE_DRIVE_DUTY on each channel alone, the light
+/// measured once it has settled, and the resulting factors applied. This blocks the main loop for ~4 x
+/// BALANCE_SETTLE_MS while the interrupts keep the LED and matrix running. On a failed read or an unseen channel the
+/// balance is left unchanged.
+fn auto_calibrate_balance(adc: &mut Saadc, adc_pins: &mut AdcPins) {
+    let Some(channel) = LIGHT_CHANNEL else {
+        rprintln!("balance: no light sensor in ADC_CHANNELS");
+        return;
+    };
+    let drive = |r, g, b| Rgb { r, g, b };
+    let drives = [
+        drive(0.0, 0.0, 0.0),
+        drive(BALANCE_DRIVE_DUTY, 0.0, 0.0),
+        drive(0.0, BALANCE_DRIVE_DUTY, 0.0),
+        drive(0.0, 0.0, BALANCE_DRIVE_DUTY),
+    ];
+    let mut levels = [0.0; 4];
+    let mut measured = true;
+    for (rgb, level) in drives.into_iter().zip(levels.iter_mut()) {
+        COLOR_CONTROLER.with_lock(|color_controler| color_controler.set_drive_override(Some(rgb)));
+        let start = now_us();
+        while now_us().wrapping_sub(start) < BALANCE_SETTLE_MS * 1000 {
+            core::hint::spin_loop();
+        }
+        match measure_light(adc, adc_pins, channel) {
+            Some(light) => *level = light,
+            None => {
+                measured = false;
+                break;
+            }
+        }
+    }
+    COLOR_CONTROLER.with_lock(|color_controler| color_controler.set_drive_override(None));
+
+    if !measured {
+        rprintln!("balance: light sensor read failed, balance unchanged");
+        DISPLAY.with_lock(|display| display.flash_code(ADC_ERROR_CODE));
+        return;
+    }
+    let [dark, r_light, g_light, b_light] = levels;
+    match balance_factors(dark, [r_light, g_light, b_light]) {
+        Some(factors) => {
+            COLOR_CONTROLER.with_lock(|color_controler| {
+                for (channel, factor) in factors.into_iter().enumerate() {
+                    color_controler.set_channel_balance(channel, factor);
+                }
+            });
+            rprintln!(
+                "balance: r {:.3} g {:.3} b {:.3}",
+                factors[0],
+                factors[1],
+                factors[2]
+            );
+        }
+        None => rprintln!(
+            "balance: a channel was too dim to measure (is the sensor facing the LED?), balance unchanged"
+        ),
+    }
+}
+
+/// fn handle_command() is called by the main event loop for every complete line received on the RTT down channel.
+///
+/// 1. Export/Css: print the current color as a ready-to-paste Hsv literal (with its RGB hex code), or as CSS colors
+/// 2. Breathe/Drift/Strobe: push the matching Effect onto the ColorControler effect stack
+/// 3. ClearEffects: remove all running effects
+/// 4. Latency: print the pot-change to render latency statistics and start a new measurement run
+/// 5. Ambient/AmbientBounds: toggle the ambient light mode and adjust its light-to-Value mapping
+/// 6. Random/Seed: jump to a random hue and saturation (keeping Value), or re-seed the random sequence
+/// 7. Hold/Lock/Release: choose how long RTT-set axes are protected from the pot, or hand them all back to the pot
+/// 8. Modes/SetMode/NextMode: print the mode menu, or switch mode
+/// 9. PwmStats: print the PWM (TIMER2, or PWM0 with hw-pwm) interrupt rate and CPU load since the last report, then start a new report
+/// 10. Idle/IdleDefault: enable (with a timeout) or disable the return to the default color after inactivity, or make
+///     the current color the default
+/// 11. Set: set one axis to a typed value (RTT then holds that axis), warning if the value had to be clamped
+/// 12. Curve: change one axis's pot input curve, saving the config to flash once it has been left alone
+/// 13. Pfm: set the minimum on-pulse (of one or every channel) below which a dim channel switches from PWM to pulse
+///     frequency modulation
+/// 14. Debounce: set the button debounce cooldown
+/// 15. Slideshow: set the slideshow dwell and fade times, then switch to the slideshow mode
+/// 16. Contrast: switch the matrix between the standard and the high-contrast glyph set
+/// 17. History: print the remembered commands, numbered for replay with `!n`
+/// 18. Blank: enable (with a timeout) or disable blanking the matrix after a period without button presses
+/// 19. Fade: fade to a typed color, the hue taking the requested way around the color wheel (RTT then holds every axis)
+/// 20. Transition: set the animation used when the matrix changes page
+/// 21. Pages: switch the editing pages between HSV components and RGB channels, and the matrix between their glyphs
+/// 22. Reinit: run the wake-from-sleep recovery (see reinit())
+/// 23. IrqRates: print each interrupt's rate per second since the last report, then start a new report
+/// 24. Primaries: calibrate (or stop calibrating) the rendered hue for an LED with off-spec primaries
+/// 25. Shimmer: set the saturation and value swing periods and depths (clamped to [0,1]), then switch to the shimmer mode
+/// 26. Swatch: switch the matrix between the page glyph and a swatch mirroring the LED color
+/// 27. CommitDelay: set how long a changed config must be left alone before it is saved to flash
+/// 28. AutoBalance/ClearBalance: queue the channel balance calibration for the main loop (see auto_calibrate_balance()),
+///     or reset every channel's balance factor to 1
+/// 29. Readout: scroll the value of the selected page's axis (or channel) across the matrix, as a percentage
+/// 30. Overview: switch the matrix between the page glyph and bar graphs of all three axes, showing the current color
+///     right away
+/// 31. Detent: set the half-width of the pot's center snap window (clamped to [0, MAX_CENTER_SNAP])
+/// 32. Gamma: switch the matrix greyscale between the perceptual gamma curve and the raw levels
+/// 33. Version: print the build info (see build_info.rs) to identify the flashed firmware
+/// 34. ScanRate: change the matrix scan rate, reporting the rate actually applied
+/// 35. Standby/StandbyLook: enable or disable the standby shimmer, or set its look (enabling it)
+/// 36. Match: apply the RGB color as HSV (keeping the locked hue in the shade mode), holding it from the pot like
+///     Set, and report the achievable color (see color_match.rs)
+/// 37. PwmPause: pause or resume the PWM, reporting when it already was
+/// 38. HueRateCap: set the hue rate cap of the effects and the standby shimmer, reporting the cap applied
+/// 39. WhitePoint: turn the color into a white of the color temperature (held from the pot like Set, and reporting the
+///     Kelvin applied), or drop the white point tint
+/// 40. Midi: switch the UART link to MIDI control changes (rejecting an invalid channel or controller number), or
+///     back to the daisy-chain packets
+/// 41. Posterize: turn the posterize effect on with the given number of levels, or off
+/// 42. ValueFloor: set the policy for the bottom of the Value axis, taking effect at the next frame or pot reading
+/// 43. Demo: start the demo reel (any command, this one included, first stops a running reel)
+/// 44. Watch: start or stop logging the base color changes with their source
+/// 45. LedGamma: set the exponent of the LED gamma curve, reporting the exponent applied
+/// 46. Blend: set the number of frames the LED color changes are smoothed over, 0 ms keeping them instant
+/// 47. BadAssignment: report an `h=`/`s=`/`v=` assignment whose value was not a number in [0,1], leaving the color be
+/// 48. ColorCode: switch the matrix between the page glyph and the color code of the current color, showing it right
+///     away
+/// 49. Smooth: switch the pot between the block average and the IIR low-pass, reporting the alpha applied
+/// 50. AutoScroll: turn the hue auto-scroll of a pinned pot on or off, reporting the period applied
+fn handle_command(command: Command, state: &mut LoopState) {
+    let effect = match command {
+        Command::Export | Command::Css => {
+            let mut hsv = STARTING_HSV;
+            COLOR_CONTROLER.with_lock(|color_controler| {
+                hsv = color_controler.current_hsv();
+            });
+            if let Command::Export = command {
+                rprintln!("{}", HsvLiteral(hsv));
+            } else {
+                rprintln!("{}", CssColor(hsv));
+            }
+            return;
+        }
+        Command::ClearEffects => {
+            COLOR_CONTROLER.with_lock(|color_controler| color_controler.clear_effects());
+            return;
+        }
+        Command::Latency => {
+            match state.latency_probe.stats() {
+                Some((last, min, max, count)) => rprintln!(
+                    "latency: last {}us, min {}us, max {}us over {} changes",
+                    last,
+                    min,
+                    max,
+                    count
+                ),
+                None => rprintln!("latency: no pot changes measured yet"),
+            }
+            state.latency_probe.reset();
+            return;
+        }
+        Command::Shimmer {
+            sat_period_ms,
+            sat_depth,
+            value_period_ms,
+            value_depth,
+        } => {
+            state.shimmer = Some(Effect::ShadeCycle {
+                sat_period_frames: ColorControler::ms_to_frames(sat_period_ms),
+                sat_depth: sat_depth.clamp(0.0, 1.0),
+                value_period_frames: ColorControler::ms_to_frames(value_period_ms),
+                value_depth: value_depth.clamp(0.0, 1.0),
+            });
+            set_mode(state, Mode::Shimmer);
+            return;
+        }
+        Command::Slideshow { dwell_ms, fade_ms } => {
+            state.slideshow.set_slideshow(true, dwell_ms, fade_ms);
+            set_mode(state, Mode::Slideshow);
+            return;
+        }
+        Command::Fade {
+            color,
+            ms,
+            direction,
+        } => {
+            COLOR_CONTROLER.with_lock(|color_controler| {
+                color_controler.fade_to(
+                    color,
+                    ColorControler::ms_to_frames(ms),
+                    direction,
+                    ColorSource::Rtt,
+                );
+            });
+            let now = now_us();
+            for axis in 0..AXIS_COUNT {
+                state.arbiter.rtt_set(axis, now, pot_for_axis(state, axis));
+            }
+            return;
+        }
+        Command::Blank { timeout_ms } => {
+            state.display_sleep.set_timeout(timeout_ms, now_us());
+            DISPLAY.with_lock(|display| display.unblank());
+            match state.display_sleep.timeout_ms() {
+                Some(ms) => rprintln!(
+                    "blank: matrix off after {}s without a button press",
+                    ms / 1000
+                ),
+                None => rprintln!("blank: off"),
+            }
+            return;
+        }
+        Command::Standby(enabled) => {
+            state.standby.set_enabled(enabled);
+            rprintln!("standby: {}", if enabled { "on" } else { "off" });
+            return;
+        }
+        Command::StandbyLook {
+            brightness,
+            period_ms,
+        } => {
+            state.standby.set_look(brightness, period_ms);
+            state.standby.set_enabled(true);
+            let (brightness, period_ms) = state.standby.look();
+            rprintln!(
+                "standby: on, brightness {} cycling every {}ms",
+                brightness,
+                period_ms
+            );
+            return;
+        }
+        Command::ScanRate { hz } => {
+            let mut applied = None;
+            DISPLAY.with_lock(|display| applied = display.set_scan_rate(hz));
+            match applied {
+                Some(applied) => rprintln!("scan: {}Hz", applied),
+                None => rprintln!("scan: no matrix display"),
+            }
+            return;
+        }
+        Command::Version => {
+            rprintln!("{}", BUILD_INFO);
+            return;
+        }
+        Command::History => {
+            for n in 1..=state.history.len() {
+                if let Some(line) = state.history.get(n) {
+                    rprintln!("!{} {}", n, line.as_str());
+                }
+            }
+            return;
+        }
+        Command::Reinit => {
+            reinit();
+            rprintln!("reinit: PWM and ADC timers restarted, display re-shown");
+            return;
+        }
+        Command::Pages(color_space) => {
+            state.color_space = color_space;
+            DISPLAY.with_lock(|display| display.set_color_space(color_space));
+            return;
+        }
+        Command::Readout => {
+            let mut hsv = STARTING_HSV;
+            COLOR_CONTROLER.with_lock(|color_controler| hsv = color_controler.current_hsv());
+            let color_space = editing_color_space(state);
+            DISPLAY.with_lock(|display| {
+                let value = color_space.component(display.get_page(), &hsv);
+                display.render_number((value.clamp(0.0, 1.0) * 100.0 + 0.5) as u8);
+            });
+            return;
+        }
+        Command::Gamma(enabled) => {
+            DISPLAY.with_lock(|display| display.set_display_gamma(enabled));
+            return;
+        }
+        Command::Detent { window } => {
+            state.center_snap = window.clamp(0.0, MAX_CENTER_SNAP);
+            rprintln!("detent: pot snaps to center within ±{}", state.center_snap);
+            return;
+        }
+        Command::Overview(enabled) => {
+            let mut hsv = STARTING_HSV;
+            COLOR_CONTROLER.with_lock(|color_controler| hsv = color_controler.current_hsv());
+            DISPLAY.with_lock(|display| {
+                display.set_overview(enabled);
+                display.render_all_axes(&hsv);
+            });
+            return;
+        }
+        Command::AutoScroll { period_ms } => {
+            state.hue_scroll.set_period_ms(period_ms);
+            match state.hue_scroll.period_ms() {
+                Some(period_ms) => rprintln!("autoscroll: one turn every {}ms", period_ms),
+                None => rprintln!("autoscroll: off"),
+            }
+            return;
+        }
+        Command::Smooth { alpha } => {
+            state.pot_filter.set_alpha(alpha);
+            match state.pot_filter.alpha() {
+                Some(alpha) => rprintln!("smooth: pot low-pass alpha {}", alpha),
+                None => rprintln!("smooth: off, pot block averaged"),
+            }
+            return;
+        }
+        Command::ColorCode(enabled) => {
+            let mut hsv = STARTING_HSV;
+            COLOR_CONTROLER.with_lock(|color_controler| hsv = color_controler.current_hsv());
+            DISPLAY.with_lock(|display| {
+                display.set_color_code(enabled);
+                display.render_color_code(&hsv);
+            });
+            return;
+        }
+        Command::Swatch(enabled) => {
+            DISPLAY.with_lock(|display| display.set_swatch(enabled));
+            return;
+        }
+        Command::Transition(transition) => {
+            DISPLAY.with_lock(|display| display.set_page_transition(transition));
+            return;
+        }
+        Command::Contrast(high_contrast) => {
+            DISPLAY.with_lock(|display| display.set_high_contrast(high_contrast));
+            return;
+        }
+        Command::Debounce { ms } => {
+            DEBOUNCER.with_lock(|debouncer| debouncer.set_cooldown_ms(ms));
+            return;
+        }
+        Command::Pfm { channel, min_on_us } => {
+            COLOR_CONTROLER.with_lock(|color_controler| match channel {
+                Some(channel) => color_controler.set_channel_min_pulse(channel, min_on_us),
+                None => color_controler.set_min_on_pulse_us(min_on_us),
+            });
+            return;
+        }
+        Command::Curve { page, curve } => {
+            state.config.curves[page as usize] = curve;
+            state.config_commit.update(state.config, now_us());
+            return;
+        }
+        Command::CommitDelay { ms } => {
+            state.config_commit.set_delay_ms(ms);
+            rprintln!(
+                "config: saved {}ms after the last change",
+                state.config_commit.delay_ms()
+            );
+            return;
+        }
+        Command::Set {
+            page: HSVPage::H, ..
+        } if state.mode == Mode::Shade => {
+            rprintln!("hue is locked in the shade mode");
+            return;
+        }
+        Command::Set { page, value } => {
+            let mut result = Ok(());
+            COLOR_CONTROLER.with_lock(|color_controler| {
+                result = match page {
+                    HSVPage::H => color_controler.try_update_hue(value, ColorSource::Rtt),
+                    HSVPage::S => color_controler.try_update_sat(value, ColorSource::Rtt),
+                    HSVPage::V => color_controler.try_update_value(value, ColorSource::Rtt),
+                };
+            });
+            if let Err(clamped) = result {
+                rprintln!("{} is outside [0,1], clamped to {}", value, clamped);
+            }
+            state
+                .arbiter
+                .rtt_set(page as usize, now_us(), pot_for_axis(state, page as usize));
+            return;
+        }
+        Command::Random => {
+            COLOR_CONTROLER.with_lock(|color_controler| {
+                let color = state.rng.random_hsv(color_controler.current_hsv().v);
+                color_controler.update_hue(color.h, ColorSource::Rtt);
+                color_controler.update_sat(color.s, ColorSource::Rtt);
+            });
+            let now = now_us();
+            for axis in [HSVPage::H as usize, HSVPage::S as usize] {
+                state.arbiter.rtt_set(axis, now, pot_for_axis(state, axis));
+            }
+            return;
+        }
+        Command::Match(requested) => {
+            let color = Rgb::from_u8(requested).to_hsv();
+            let mut applied = color;
+            COLOR_CONTROLER.with_lock(|color_controler| {
+                // the shade mode's hue lock keeps the hue
+                color_controler.set_hsv(color, ColorSource::Rtt);
+                applied = color_controler.current_hsv();
+            });
+            let now = now_us();
+            for axis in 0..AXIS_COUNT {
+                state.arbiter.rtt_set(axis, now, pot_for_axis(state, axis));
+            }
+            if state.mode == Mode::Shade {
+                rprintln!("hue is locked in the shade mode");
+            }
+            rprintln!("{}", ColorMatch::new(requested, applied));
+            return;
+        }
+        Command::Hold { ms } => {
+            state.arbiter.set_policy(ArbitrationPolicy::HoldFor { ms });
+            return;
+        }
+        Command::Lock => {
+            state.arbiter.set_policy(ArbitrationPolicy::Lock);
+            return;
+        }
+        Command::Release => {
+            state.arbiter.release_all();
+            return;
+        }
+        Command::AutoBalance => {
+            state.balance_requested = true;
+            return;
+        }
+        Command::ClearBalance => {
+            COLOR_CONTROLER.with_lock(|color_controler| {
+                for channel in 0..3 {
+                    color_controler.set_channel_balance(channel, 1.0);
+                }
+            });
+            return;
+        }
+        Command::Midi(Some((channel, controllers))) => {
+            match MidiCc::new(channel, controllers) {
+                Some(midi) if !state.link.set_midi(Some(midi)) => {
+                    rprintln!("midi: not available in a follower build, its UARTE1 is receiving")
+                }
+                Some(_) => {
+                    rprintln!(
+                        "midi: channel {}, H/S/V on CC {}/{}/{}",
+                        channel,
+                        controllers[0],
+                        controllers[1],
+                        controllers[2]
+                    );
+                }
+                None => rprintln!(
+                    "midi: the channel must be 1-{} and the CC numbers 0-{}",
+                    MIDI_CHANNEL_COUNT,
+                    MAX_CC_NUMBER
+                ),
+            }
+            return;
+        }
+        Command::Posterize(levels) => {
+            COLOR_CONTROLER.with_lock(|color_controler| color_controler.set_posterize(levels));
+            match levels {
+                Some(levels) => rprintln!("posterize: {} levels per channel", levels.max(2)),
+                None => rprintln!("posterize: off"),
+            }
+            return;
+        }
+        Command::Watch(enabled) => {
+            state.watch.set_enabled(enabled);
+            rprintln!("watch: {}", if enabled { "on" } else { "off" });
+            return;
+        }
+        Command::Demo => {
+            start_demo(state, now_us());
+            return;
+        }
+        Command::ValueFloor(floor) => {
+            COLOR_CONTROLER.with_lock(|color_controler| color_controler.set_value_floor(floor));
+            match floor {
+                ValueFloor::Off => rprintln!("vfloor: off, the Value pot reaches off"),
+                ValueFloor::MinGlow(glow) => rprintln!("vfloor: glow, at least {} Value", glow),
+                ValueFloor::NeverOff => {
+                    rprintln!("vfloor: never, the Value pot stops short of off")
+                }
+            }
+            return;
+        }
+        Command::Midi(None) => {
+            state.link.set_midi(None);
+            rprintln!("midi: off, sending daisy-chain packets");
+            return;
+        }
+        Command::WhitePoint(Some(kelvin)) => {
+            let mut applied = kelvin;
+            COLOR_CONTROLER.with_lock(|color_controler| {
+                applied = color_controler.set_white_point_kelvin(kelvin, ColorSource::Rtt)
+            });
+            let axis = HSVPage::S as usize;
+            state
+                .arbiter
+                .rtt_set(axis, now_us(), pot_for_axis(state, axis));
+            rprintln!("white point: {}K", applied);
+            return;
+        }
+        Command::WhitePoint(None) => {
+            COLOR_CONTROLER.with_lock(|color_controler| color_controler.clear_white_point());
+            rprintln!("white point: native");
+            return;
+        }
+        Command::Primaries(hues) => {
+            let mut accepted = true;
+            COLOR_CONTROLER.with_lock(|color_controler| match hues {
+                Some([r_hue, g_hue, b_hue]) => {
+                    accepted = color_controler.set_primary_calibration(r_hue, g_hue, b_hue);
+                }
+                None => color_controler.clear_primary_calibration(),
+            });
+            if !accepted {
+                rprintln!("primaries: hues must go red -> green -> blue around the color wheel");
+            }
+            return;
+        }
+        Command::IrqRates => {
+            let now = now_us();
+            let elapsed_us = now.wrapping_sub(state.irq_since);
+            state.irq_since = now;
+            for irq in Irq::ALL {
+                let rate = rate_per_second(IRQ_COUNTERS.take(irq), elapsed_us);
+                rprintln!("irqs: {} {:.1}/s", irq.name(), rate);
+            }
+            return;
+        }
+        Command::PwmStats => {
+            let now = now_us();
+            let elapsed_us = now.wrapping_sub(state.pwm_since).max(1) as f32;
+            let interrupts = PWM_INTERRUPTS.swap(0, SeqCst) as f32;
+            let busy_us = PWM_BUSY_US.swap(0, SeqCst) as f32;
+            state.pwm_since = now;
+            rprintln!(
+                "pwm: {:.2} interrupts per frame, {:.2}% CPU",
+                interrupts * ColorControler::FRAME_DURATION_US as f32 / elapsed_us,
+                busy_us * 100.0 / elapsed_us
+            );
+            return;
+        }
+        Command::LedGamma { gamma } => {
+            let mut applied = gamma;
+            COLOR_CONTROLER.with_lock(|color_controler| applied = color_controler.set_gamma(gamma));
+            rprintln!("ledgamma: {}", applied);
+            return;
+        }
+        Command::BadAssignment { page } => {
+            let name = match page {
+                HSVPage::H => "h",
+                HSVPage::S => "s",
+                HSVPage::V => "v",
+            };
+            rprintln!("{}= needs a number in [0,1], ignored", name);
+            return;
+        }
+        Command::Blend { ms } => {
+            let frames = if ms == 0 {
+                0
+            } else {
+                ColorControler::ms_to_frames(ms)
+            };
+            COLOR_CONTROLER
+                .with_lock(|color_controler| color_controler.set_transition_frames(frames));
+            rprintln!("blend: {} frames", frames);
+            return;
+        }
+        Command::HueRateCap { turns_per_s } => {
+            let mut cap = turns_per_s;
+            COLOR_CONTROLER
+                .with_lock(|color_controler| cap = color_controler.set_hue_rate_cap(turns_per_s));
+            rprintln!("hue animations capped at {} turns per second", cap);
+            return;
+        }
+        Command::PwmPause(pause) => {
+            let mut changed = false;
+            COLOR_CONTROLER.with_lock(|color_controler| {
+                changed = if pause {
+                    color_controler.pause()
+                } else {
+                    color_controler.resume()
+                };
+            });
+            match (pause, changed) {
+                (true, true) => rprintln!("pwm paused, pins frozen until `pwm resume`"),
+                (false, true) => rprintln!("pwm resumed"),
+                (true, false) => rprintln!("pwm already paused"),
+                (false, false) => rprintln!("pwm is not paused"),
+            }
+            return;
+        }
+        Command::Idle { timeout_ms } => {
+            state.inactivity.set_timeout(timeout_ms, now_us());
+            match state.inactivity.timeout_ms() {
+                Some(ms) => rprintln!("idle: revert to the default color after {}s", ms / 1000),
+                None => rprintln!("idle: off"),
+            }
+            return;
+        }
+        Command::IdleDefault => {
+            COLOR_CONTROLER.with_lock(|color_controler| {
+                state.inactivity.set_default(color_controler.current_hsv());
+            });
+            rprintln!(
+                "idle default: {}",
+                HsvLiteral(state.inactivity.default_color())
+            );
+            return;
+        }
+        Command::Seed(seed) => {
+            state.rng.seed_rng(seed);
+            return;
+        }
+        Command::Modes => {
+            for mode in Mode::ALL {
+                let marker = if mode == state.mode { '*' } else { ' ' };
+                rprintln!("{} {:8} {}", marker, mode.name(), mode.description());
+            }
+            return;
+        }
+        Command::SetMode(mode) => {
+            set_mode(state, mode);
+            return;
+        }
+        Command::NextMode => {
+            set_mode(state, state.mode.next_mode());
+            return;
+        }
+        Command::Ambient(enabled) => {
+            state.ambient.set_enabled(enabled);
+            if !enabled {
+                COLOR_CONTROLER.with_lock(|color_controler| color_controler.set_value_scale(1.0));
+            }
+            return;
+        }
+        Command::AmbientBounds {
+            dark,
+            bright,
+            min_scale,
+        } => {
+            state.ambient.set_bounds(AmbientBounds {
+                dark,
+                bright,
+                min_scale,
+            });
+            return;
+        }
+        Command::Breathe { period_ms, depth } => Effect::Breathe {
+            period_frames: ColorControler::ms_to_frames(period_ms),
+            depth,
+        },
+        Command::Drift { period_ms } => Effect::HueDrift {
+            period_frames: ColorControler::ms_to_frames(period_ms),
+        },
+        Command::Strobe { period_ms } => Effect::Strobe {
+            period_frames: ColorControler::ms_to_frames(period_ms),
+        },
+    };
+
+    COLOR_CONTROLER.with_lock(|color_controler| {
+        if color_controler.push_effect(effect).is_err() {
+            rprintln!("effect stack full, use `clear` first");
+        }
     });
 }
 
@@ -186,35 +1329,122 @@ fn init() {
 /// 2. Setup the RGB LED pins and ColorControler struct
 /// 3. Setup the ADC sampling of the pot voltage
 /// 4. Setup the A/B Buttons with GPIOTE interrupts
-/// 5. Setup and clear the NVIC states
+/// 5. Start the display, PWM, and ADC timers (init()), discard any button press made during the boot, and only then
+///    unmask the interrupts
 /// 6. Start main event loop - accumulate pot ADC results and average when triggered, passing the averaged result
-///    to the ColorControler struct to change the rgb pin states
+///    to the ColorControler struct to change the rgb pin states, and poll the RTT down channel for commands
 #[entry]
 fn main() -> ! {
-    rtt_init_print!();
+    // RTT up channel 0 is used for rprintln! output and down channel 0 for typed commands
+    let channels = rtt_init! {
+        up: {
+            0: {
+                size: 1024,
+                name: "Terminal"
+            }
+        }
+        down: {
+            0: {
+                size: 64,
+                name: "Terminal"
+            }
+        }
+    };
+    set_print_channel(channels.up.0);
+    rprintln!("{}", BUILD_INFO);
+    let mut rtt_input = channels.down.0;
+    let mut command_reader = CommandReader::new();
+
+    // build and validate the startup tunables once. Retune the firmware in DEFAULT_APP_CONFIG (see app_config.rs): a
+    // nonsensical combination fails the build, or here stops the boot with a descriptive panic message on RTT
+    let app_config = AppConfigBuilder::new()
+        .build()
+        .unwrap_or_else(|error| panic!("config: {}", error));
+    REFRESH_TICKS.store(app_config.refresh_ms * TIMER_TICKS_PER_MS, SeqCst);
 
     let board = Board::take().unwrap();
 
-    // setup display
-    let display = Display::new(board.TIMER0, board.display_pins);
-    let mut debounce_timer = Timer::new(board.TIMER1);
-    let display = HSVDisplay::new(display);
+    // setup the free-running system clock used for timestamps
+    SYSTEM_CLOCK.init(TimerClock::new(board.TIMER4));
+
+    // setup display (or, without the `display` feature, only the page tracking)
+    #[cfg(feature = "display")]
+    let mut display = HSVDisplay::new(Display::new(board.TIMER0, board.display_pins));
+    #[cfg(not(feature = "display"))]
+    let mut display = HSVDisplay::new();
+    display.set_display_gamma(app_config.display_gamma);
     DISPLAY.init(display);
-    debounce_timer.enable_interrupt(); //setup debounce timer interupts
-    debounce_timer.reset_event();
-    DEBOUNCE_TIMER.init(debounce_timer);
+    let mut debouncer = Debouncer::new(TimerClock::new(board.TIMER1));
+    debouncer.set_cooldown_ms(app_config.debounce_ms);
+    DEBOUNCER.init(debouncer);
+
+    // load the persisted settings. A blank flash page just means nothing has been saved yet, anything else falls back
+    // to the defaults and is reported, a corrupt page (e.g. a save cut short by a reset) with its own code
+    let mut store = ConfigStore::new(board.NVMC);
+    let config = match store.load() {
+        Ok(config) => config,
+        Err(LoadError::Config(ConfigError::Blank)) => DEFAULT_CONFIG,
+        Err(LoadError::Config(ConfigError::Corrupt)) => {
+            rprintln!("config: saved config is corrupt, using defaults");
+            DISPLAY.with_lock(|display| display.flash_code(CONFIG_CORRUPT_CODE));
+            DEFAULT_CONFIG
+        }
+        Err(LoadError::Flash) => {
+            rprintln!("config: flash load failed, using defaults");
+            DISPLAY.with_lock(|display| display.flash_code(FLASH_ERROR_CODE));
+            DEFAULT_CONFIG
+        }
+    };
+    // restore the color saved before the last reset (see persist.rs), if the page holds a valid one
+    let starting_color = store.load_hsv().unwrap_or(app_config.starting_color);
 
-    // setup RGB pins
+    // read the LED polarity jumper, then setup RGB pins starting in their off state for that polarity
+    let mut polarity_jumper: PolarityJumperType = board.edge.e12.into_pullup_input();
+    let jumper_is_low = polarity_jumper.is_low().unwrap();
+    POLARITY_JUMPER_LOW.store(jumper_is_low, SeqCst);
+    let polarity = Polarity::from_jumper(jumper_is_low);
+    let off_level = || {
+        if polarity.pin_level(false) {
+            Level::High
+        } else {
+            Level::Low
+        }
+    };
+    #[cfg(not(feature = "hw-pwm"))]
     let color_timer: ColorTimer = Timer::new(board.TIMER2);
-    let red: RedPinType = board.edge.e08.into_push_pull_output(Level::High); //High means off for the LED
-    let green: GreenPinType = board.edge.e09.into_push_pull_output(Level::High); //High means off for the LED
-    let blue: BluePinType = board.edge.e16.into_push_pull_output(Level::High);
-    let color_controler: ColorControler =
-        ColorControler::new(STARTING_HSV, color_timer, red, green, blue);
+    let red: RedPinType = board.edge.e08.into_push_pull_output(off_level());
+    let green: GreenPinType = board.edge.e09.into_push_pull_output(off_level());
+    let blue: BluePinType = board.edge.e16.into_push_pull_output(off_level());
+    let gpiote = Gpiote::new(board.GPIOTE);
+    #[cfg(not(any(feature = "ppi-pwm", feature = "hw-pwm")))]
+    let mut color_controler: ColorControler =
+        ColorControler::new(starting_color, color_timer, polarity, red, green, blue);
+    #[cfg(feature = "ppi-pwm")]
+    let mut color_controler: ColorControler = ColorControler::new(
+        starting_color,
+        color_timer,
+        polarity,
+        red,
+        green,
+        blue,
+        &gpiote,
+        ppi::Parts::new(board.PPI),
+    );
+    #[cfg(feature = "hw-pwm")]
+    let mut color_controler: ColorControler =
+        ColorControler::new(starting_color, board.PWM0, polarity, red, green, blue);
+    color_controler.set_min_on_pulse_us(app_config.pfm_min_on_us);
+    for (channel, factor) in app_config.channel_balance.into_iter().enumerate() {
+        color_controler.set_channel_balance(channel, factor);
+    }
     COLOR_CONTROLER.init(color_controler);
 
-    // setup the pot A2D
-    let mut pot: PotType = board.edge.e02.into_floating_input();
+    // setup the A2D of the pots and light sensor on the analog edge pins (see adc_channels.rs)
+    let mut adc_pins = AdcPins::new(
+        board.edge.e00.into_floating_input(),
+        board.edge.e01.into_floating_input(),
+        board.edge.e02.into_floating_input(),
+    );
     let adc_config = SaadcConfig {
         time: saadc::Time::_40US,
         ..Default::default()
@@ -229,8 +1459,7 @@ fn main() -> ! {
     let a_btn = board.buttons.button_a.into_floating_input().degrade();
     let b_btn = board.buttons.button_b.into_floating_input().degrade();
 
-    //setup gpiote interupts
-    let gpiote = Gpiote::new(board.GPIOTE);
+    //setup gpiote interupts (channels 2-4 are reserved for the RGB pins by the ppi-pwm feature)
     let channel0 = gpiote.channel0(); //a_btn 
     let channel1 = gpiote.channel1(); //b_btn
     channel0.input_pin(&a_btn).hi_to_lo().enable_interrupt();
@@ -239,60 +1468,505 @@ fn main() -> ! {
     channel1.reset_events();
 
     GPIOTE_PERIPHERAL.init(gpiote);
+    BUTTONS.init([a_btn, b_btn]);
+
+    // Boot ordering: everything is initialized with the interrupts still masked, so no handler can run against a half
+    // initialized state. init() starts the display, PWM, and ADC timers, whose first interrupts simply wait pending.
+    // A button pressed at any point since its GPIOTE channel was configured has set a stale event, which is discarded
+    // (the event itself, since a pending NVIC state would be raised again while it is set, then the NVIC state) right
+    // before INPUT_READY opens press handling and the interrupts are unmasked. The timers' pending interrupts are
+    // genuine and are kept.
+    init();
+    GPIOTE_PERIPHERAL.with_lock(|gpiote| {
+        gpiote.channel0().reset_events();
+        gpiote.channel1().reset_events();
+    });
+    NVIC::unpend(Interrupt::GPIOTE);
+    INPUT_READY.store(true, SeqCst);
 
     // Set up the NVIC to handle interrupts.
     unsafe {
         NVIC::unmask(Interrupt::GPIOTE); // btns
+        #[cfg(feature = "display")]
         NVIC::unmask(Interrupt::TIMER0); // non-blockign display timer
+        #[cfg(not(feature = "hw-pwm"))]
         NVIC::unmask(Interrupt::TIMER2); // color change timer
+        #[cfg(feature = "hw-pwm")]
+        NVIC::unmask(Interrupt::PWM0); // color frame
         NVIC::unmask(Interrupt::TIMER3); // adc accumulator
     }; // allow NVIC to handle GPIOTE signals
-    //clear any currently pending GPIOTE state
-    NVIC::unpend(Interrupt::GPIOTE);
-    NVIC::unpend(Interrupt::TIMER0);
-    NVIC::unpend(Interrupt::TIMER2);
-    NVIC::unpend(Interrupt::TIMER3);
 
-    init();
+    let mut spike_filters = [MedianFilter::<ADC_MEDIAN_WINDOW>::new(); ADC_PIN_COUNT]; // per pin median of the last raw readings, dropping single-reading spikes
+    let mut adc_sums = [0u64; ADC_PIN_COUNT]; // per pin ADC co-adding sums: u64 can't overflow at any refresh period
+    let mut adc_counter: u32 = 0; //count co-adds used to accumulate adc_sums, for averaging (saturating, after >47 hours at 40us)
+    let mut last_percentage: f32 = -1.0; // previous averaged pot percentage, negative so the first average counts as a change
+    let seed = FIXED_RNG_SEED.unwrap_or_else(|| Rng::new(board.RNG).random_u32());
+
+    // setup the UART daisy-chain link
+    let link_tx: LinkTxPinType = board.pins.p0_13.into_push_pull_output(Level::High);
+    let link_rx: LinkRxPinType = board.pins.p0_01.into_floating_input();
+    let link = UartLink::new(
+        board.UARTE1,
+        uarte::Pins {
+            txd: link_tx.degrade(),
+            rxd: link_rx.degrade(),
+            cts: None,
+            rts: None,
+        },
+    );
+
+    // setup the serial control port on the USB serial (interface chip) UART
+    let serial = SerialControl::new(board.UARTE0, board.uart.into());
 
-    let mut adc_counter: u32 = 0; //count co-adds used to accumulate ADC_ACCUMULATOR_VALUE, for averaging
+    let mut state = LoopState {
+        latency_probe: LatencyProbe::new(),
+        ambient: AmbientLight::new(),
+        rng: ColorRng::new(seed),
+        arbiter: AxisArbiter::new(),
+        pot: 0.0,
+        pot_mapper: PotMapper::new(),
+        chord_held: false,
+        mode: Mode::Manual,
+        pwm_since: now_us(),
+        inactivity: Inactivity::new(app_config.starting_color),
+        config,
+        store,
+        link,
+        slideshow: Slideshow::new(),
+        history: CommandHistory::new(),
+        display_sleep: DisplaySleep::new(),
+        color_space: ColorSpace::Hsv,
+        irq_since: now_us(),
+        shimmer: Mode::Shimmer.effect(),
+        config_commit: CommitDebouncer::new(config),
+        balance_requested: false,
+        axis_pots: [None; AXIS_COUNT],
+        center_snap: 0.0,
+        power: PowerToggle::new(),
+        standby: Standby::new(),
+        demo: DemoReel::new(),
+        watch: ColorWatch::new(),
+        hsv_saver: HsvSaver::new(),
+        serial,
+        pot_filter: AdcFilter::new(),
+        hue_scroll: HueScroll::new(),
+    };
+    let now = now_us();
+    state
+        .inactivity
+        .set_timeout(app_config.idle_timeout_ms, now);
+    state
+        .display_sleep
+        .set_timeout(app_config.blank_timeout_ms, now);
+    state.ambient.set_bounds(app_config.ambient);
+    state.slideshow.set_slideshow(
+        false,
+        app_config.slideshow_dwell_ms,
+        app_config.slideshow_fade_ms,
+    );
+    set_mode(&mut state, app_config.starting_mode);
+
+    // periodic housekeeping, stepped by the event loop below
+    let mut scheduler = Scheduler::<LoopState>::new();
+    let now = now_us();
+    for task in [commit_config_task, display_sleep_task, save_color_task] {
+        scheduler.register(HOUSEKEEPING_PERIOD_MS, now, task).ok();
+    }
     loop {
-        // read raw ADC result, with non-negative bounds
-        let mut raw_value = adc.read_channel(&mut pot).unwrap();
-        if raw_value < 0 {
-            raw_value = 0;
+        // read the raw ADC result of every pot pin (see adc_channels.rs), median filtered against spikes (see
+        // adc_filter.rs) and preprocessed into [0, MAX_ADC_VALUE]. A failed read skips the whole sample and is
+        // reported on the matrix
+        let mut raw_values = [0u32; ADC_PIN_COUNT];
+        let mut read_failed = false;
+        for (channel, input) in ADC_CHANNELS.into_iter().enumerate() {
+            if let Some(AdcInput::Pot | AdcInput::AxisPot(_)) = input {
+                match adc_pins.read(&mut adc, channel) {
+                    Ok(raw_value) => {
+                        let filtered = spike_filters[channel].update(raw_value);
+                        raw_values[channel] = preprocess_sample(filtered)
+                    }
+                    Err(()) => read_failed = true,
+                }
+            }
+        }
+        if read_failed {
+            DISPLAY.with_lock(|display| display.flash_code(ADC_ERROR_CODE));
+            continue;
         }
 
-        // add ADC result to the accumulating sum
-        ADC_ACCUMULATOR_VALUE.fetch_add(raw_value as u32, SeqCst);
-        adc_counter += 1;
+        // add the ADC results to the accumulating sums
+        for (sum, raw_value) in adc_sums.iter_mut().zip(raw_values) {
+            *sum += raw_value as u64;
+        }
+        adc_counter = adc_counter.saturating_add(1);
+
+        // complete a pending latency measurement once a frame has started after the change was applied
+        state.latency_probe.observe(FRAME_START_US.load(SeqCst));
+
+        // a follower shows the color received from the previous board in the chain, holding it from the pot like an
+        // RTT set
+        #[cfg(feature = "follower")]
+        if let Some(color) = state.link.poll() {
+            COLOR_CONTROLER.with_lock(|color_controler| {
+                color_controler.set_hsv(color, ColorSource::Link);
+            });
+            let now = now_us();
+            for axis in 0..AXIS_COUNT {
+                state.arbiter.rtt_set(axis, now, pot_for_axis(&state, axis));
+            }
+            state.inactivity.touch(now);
+        }
+
+        // a host PC sets or reads the color over the USB serial port, each set component held from the pot like an
+        // RTT set
+        while let Some(command) = state.serial.poll() {
+            handle_serial(&mut state, command);
+        }
 
         // if ADC_READY_READ atomic is set, then average the ADC accumulator vale and update the ColorControler HSV
         if ADC_READY_READ.load(SeqCst) {
-            let total = ADC_ACCUMULATOR_VALUE.load(SeqCst);
-            let mut average = total as f32 / adc_counter as f32;
-            average = average.clamp(MIN_ADC_THRESHOLD, MAX_ADC_THRESHOLD);
+            let lut = ADC_CORRECTION_LUT.as_ref().map(|lut| &lut[..]);
+            let average = average_samples(adc_sums[POT_CHANNEL], adc_counter);
+            let smoothed = state.pot_filter.update(adc_to_percentage(average, lut)); //scale so [0-1]
+            let percentage = center_snap(smoothed, state.center_snap);
 
-            let percentage =
-                (average - MIN_ADC_THRESHOLD) / (MAX_ADC_THRESHOLD - MIN_ADC_THRESHOLD); //scale so [0-1]
+            // an A+B chord toggles fine mode (the first button of the chord has already rotated the page, so undo that)
+            let held = buttons_held();
+            let chord = held[0] && held[1];
+            if chord && !state.chord_held {
+                DISPLAY.with_lock(|display| {
+                    display.revert_page();
+                    display.render();
+                });
+            }
 
-            // get which HSV setting we are currently on
+            // get which HSV setting (or, in the RGB color space, channel) we are currently on, and its current value
+            let color_space = editing_color_space(&state);
             let mut display_page = HSVPage::H;
             DISPLAY.with_lock(|display| {
                 display_page = display.get_page();
             });
-
-            // update the H,S, or V value with the new ADC averaged result
-            COLOR_CONTROLER.with_lock(|color_controler| match display_page {
-                HSVPage::H => color_controler.update_hue(percentage),
-                HSVPage::S => color_controler.update_sat(percentage),
-                HSVPage::V => color_controler.update_value(percentage),
+            let mut hsv = STARTING_HSV;
+            COLOR_CONTROLER.with_lock(|color_controler| {
+                hsv = color_controler.current_hsv();
             });
+            let axis_value = color_space.component(display_page, &hsv);
+            DISPLAY.with_lock(|display| display.set_saturation(hsv.s));
+
+            // shape the pot percentage by the selected axis's input curve
+            let shaped = state.config.curves[display_page as usize].apply(percentage);
+
+            if chord && !state.chord_held {
+                let fine = !state.pot_mapper.is_fine();
+                state
+                    .pot_mapper
+                    .set_fine(fine, shaped, axis_value, display_page as usize);
+                rprintln!("fine mode {}", if fine { "on" } else { "off" });
+            }
+            state.chord_held = chord;
+
+            // any interaction restarts the inactivity timer
+            let now = now_us();
+            let button_activity = BUTTON_ACTIVITY.swap(false, SeqCst);
+            let pot_activity = (percentage - state.pot).abs() > POT_ACTIVITY_THRESHOLD;
+            if pot_activity || button_activity {
+                state.inactivity.touch(now);
+                stop_demo(&mut state);
+            }
+            // a turned pot saves the color once it has settled (see save_color_task())
+            if pot_activity {
+                state.hsv_saver.touch(now);
+            }
+
+            // restart the matrix blanking timeout on a button press (see display_sleep_task())
+            if button_activity {
+                state.display_sleep.touch(now);
+            }
+
+            // a long press of the right-turning button (B, or A with swap-buttons, see button_map.rs) alone toggles
+            // the soft power. Switching off undoes the page rotation of the press starting the long press, then runs
+            // the shutdown one step per refresh (see power_toggle.rs): the color and any pending config are saved to
+            // flash, the LED fades out, and the matrix blanks. Switching on restores the saved color and, like an RTT
+            // set, protects it from the pot until the pot is turned
+            let power_frames = ColorControler::ms_to_frames(POWER_FADE_MS);
+            let power_button = button_for(PageTurn::Right, SWAP_BUTTONS);
+            let power_held = held[power_button as usize] && !held[power_button.other() as usize];
+            match state.power.poll(power_held, now, hsv) {
+                Some(PowerAction::Persist(color)) => {
+                    POWERED_OFF.store(true, SeqCst);
+                    DISPLAY.with_lock(|display| {
+                        display.revert_page();
+                        display.render();
+                    });
+                    // both saves return only once the flash is done with them, so the fade starts on a saved state
+                    if let Err(e) = state.store.save_hsv(&color) {
+                        rprintln!("color: flash write failed: {}", e);
+                        DISPLAY.with_lock(|display| display.flash_code(FLASH_ERROR_CODE));
+                    }
+                    if state.config_commit.flush().is_some() {
+                        save_config(&mut state);
+                    }
+                    rprintln!("power: state saved");
+                }
+                Some(PowerAction::FadeOut) => COLOR_CONTROLER
+                    .with_lock(|color_controler| color_controler.fade_output(0.0, power_frames)),
+                Some(PowerAction::Sleep) => {
+                    DISPLAY.with_lock(|display| display.blank());
+                    rprintln!("power: off");
+                }
+                Some(PowerAction::On(remembered)) => {
+                    let color = state.store.load_hsv().unwrap_or(remembered);
+                    COLOR_CONTROLER.with_lock(|color_controler| {
+                        color_controler.set_hsv(color, ColorSource::Power);
+                        color_controler.fade_output(1.0, power_frames);
+                    });
+                    for axis in 0..AXIS_COUNT {
+                        state.arbiter.rtt_set(axis, now, pot_for_axis(&state, axis));
+                    }
+                    state.display_sleep.touch(now);
+                    DISPLAY.with_lock(|display| display.unblank());
+                    POWERED_OFF.store(false, SeqCst);
+                    rprintln!("power: on");
+                }
+                None => {}
+            }
+
+            // a long press of the left-turning button alone starts the demo reel (undoing the page rotation of the
+            // press starting it), which then steps to its next mode every DEMO_DWELL_MS
+            let demo_button = button_for(PageTurn::Left, SWAP_BUTTONS);
+            let demo_held = held[demo_button as usize] && !held[demo_button.other() as usize];
+            if state.demo.poll_gesture(demo_held, now) && !POWERED_OFF.load(SeqCst) {
+                DISPLAY.with_lock(|display| {
+                    display.revert_page();
+                    display.render();
+                });
+                start_demo(&mut state, now);
+            }
+            if let Some(mode) = state.demo.poll(now) {
+                set_mode(&mut state, mode);
+            }
+
+            // update the H,S, or V value with the new (curve shaped, then coarse or fine mapped) ADC averaged result,
+            // unless RTT currently holds that axis or it has a pot of its own, or the soft power is off. In the
+            // slideshow mode turning the pot sets the dwell time instead
+            let hue_axis = matches!((color_space, display_page), (ColorSpace::Hsv, HSVPage::H));
+
+            // on the Hue page a pot held pinned at an end of its travel scrolls the hue around the color wheel instead
+            // (see hue_scroll.rs). Once it leaves the end the pot has to pick the scrolled hue up before it writes
+            let may_scroll = hue_axis
+                && state.mode != Mode::Slideshow
+                && !state.pot_mapper.is_fine()
+                && !has_axis_pot(display_page)
+                && !state.power.is_off()
+                && !state.arbiter.is_held(display_page as usize);
+            let extreme = pinned_extreme(percentage).filter(|_| may_scroll);
+            let mut hue_rate_cap = DEFAULT_HUE_RATE_CAP;
+            COLOR_CONTROLER
+                .with_lock(|color_controler| hue_rate_cap = color_controler.hue_rate_cap());
+            let scroll = state.hue_scroll.poll(extreme, now, hue_rate_cap);
+            match scroll {
+                Some(ScrollUpdate::Step(turns)) => {
+                    COLOR_CONTROLER.with_lock(|color_controler| {
+                        color_controler.update_hue(normalize_hue(hsv.h + turns), ColorSource::Pot)
+                    });
+                    state.inactivity.touch(now);
+                }
+                // the scrolled hue counts as a pot movement once, when the scroll ends
+                Some(ScrollUpdate::Released) => {
+                    state.pot_mapper.await_pickup();
+                    state.hsv_saver.touch(now);
+                }
+                None => {}
+            }
+
+            let mapped = state
+                .pot_mapper
+                .map(shaped, axis_value, display_page as usize, hue_axis);
+            if state.mode == Mode::Slideshow {
+                if (percentage - state.pot).abs() > POT_ACTIVITY_THRESHOLD {
+                    state.slideshow.set_dwell_ms(dwell_from_pot(percentage));
+                    rprintln!("slideshow: dwell {}ms", state.slideshow.dwell_ms());
+                }
+            } else if let Some(value) = mapped
+                && !matches!(scroll, Some(ScrollUpdate::Step(_)))
+                && !has_axis_pot(display_page)
+                && !state.power.is_off()
+                && state
+                    .arbiter
+                    .pot_may_write(display_page as usize, now, percentage)
+            {
+                // hue is wrapped into [0,1) as the very last step of its mapping, so the seam of the color wheel
+                // (e.g. exactly 1.0) is stored canonically. An RGB channel is applied to the stored Hsv color by
+                // converting it to RGB and back, which PotWrite does before the lock is taken (see color_control.rs)
+                let write = PotWrite::new(color_space, display_page, hsv, value);
+                COLOR_CONTROLER.with_lock(|color_controler| color_controler.apply_pot(write));
+
+                // timestamp significant pot changes so the delay until they are rendered can be measured
+                if (percentage - last_percentage).abs() > LATENCY_CHANGE_THRESHOLD {
+                    state.latency_probe.start(now);
+                }
+            }
+            last_percentage = percentage;
+            state.pot = percentage;
+
+            // every axis with a pot of its own follows that pot (curve shaped), unless RTT currently holds the axis.
+            // In the slideshow mode (or with the soft power off) they are ignored, like the selected-page pot. The
+            // writes are gathered first and applied under a single lock
+            let mut axis_writes = [None; AXIS_COUNT];
+            for (channel, input) in ADC_CHANNELS.into_iter().enumerate() {
+                let Some(AdcInput::AxisPot(page)) = input else {
+                    continue;
+                };
+                let axis = page as usize;
+                let average = average_samples(adc_sums[channel], adc_counter);
+                let reading = center_snap(adc_to_percentage(average, lut), state.center_snap);
+                if state.axis_pots[axis]
+                    .is_some_and(|last| (reading - last).abs() > POT_ACTIVITY_THRESHOLD)
+                {
+                    state.inactivity.touch(now);
+                    state.hsv_saver.touch(now);
+                }
+                state.axis_pots[axis] = Some(reading);
+                if state.mode == Mode::Slideshow
+                    || state.power.is_off()
+                    || !state.arbiter.pot_may_write(axis, now, reading)
+                {
+                    continue;
+                }
+                let value = state.config.curves[axis].apply(reading);
+                axis_writes[axis] = Some(PotWrite::new(ColorSpace::Hsv, page, hsv, value));
+            }
+            if axis_writes.iter().any(Option::is_some) {
+                COLOR_CONTROLER.with_lock(|color_controler| {
+                    for write in axis_writes.into_iter().flatten() {
+                        color_controler.apply_pot(write);
+                    }
+                });
+            }
+
+            // after the inactivity timeout, fade back to the default color. Like an RTT set, the reverted color is
+            // protected from the pot until the pot is turned
+            if let Some(default) = state.inactivity.poll(now) {
+                COLOR_CONTROLER.with_lock(|color_controler| {
+                    color_controler.fade_to(
+                        default,
+                        ColorControler::ms_to_frames(INACTIVITY_FADE_MS),
+                        HueDirection::Shortest,
+                        ColorSource::Inactivity,
+                    );
+                });
+                for axis in 0..AXIS_COUNT {
+                    state.arbiter.rtt_set(axis, now, pot_for_axis(&state, axis));
+                }
+                rprintln!("idle: reverting to the default color");
+            }
+
+            // with the output dark (but not switched off) and untouched for a while, show the dim standby shimmer
+            // until the next interaction
+            let due = !state.power.is_off()
+                && standby_due(hsv.v, now, state.inactivity.last_activity_us());
+            let mut hue_rate_cap = DEFAULT_HUE_RATE_CAP;
+            COLOR_CONTROLER
+                .with_lock(|color_controler| hue_rate_cap = color_controler.hue_rate_cap());
+            match state.standby.poll(due, now, hue_rate_cap) {
+                Some(StandbyUpdate::Show(color)) => COLOR_CONTROLER.with_lock(|color_controler| {
+                    color_controler.set_drive_override(Some(color.to_rgb()))
+                }),
+                Some(StandbyUpdate::Exit) => COLOR_CONTROLER
+                    .with_lock(|color_controler| color_controler.set_drive_override(None)),
+                None => {}
+            }
+
+            // run the periodic housekeeping tasks that have come due
+            scheduler.tick(now, &mut state);
+
+            // in the slideshow mode, start the fade to the next preset once the current one has been shown for the
+            // dwell time. Like an RTT set, the preset is protected from the pot after leaving the slideshow mode
+            if let Some(preset) = state.slideshow.poll(now) {
+                COLOR_CONTROLER.with_lock(|color_controler| {
+                    color_controler.fade_to(
+                        preset.color,
+                        ColorControler::ms_to_frames(state.slideshow.fade_ms()),
+                        HueDirection::Shortest,
+                        ColorSource::Slideshow,
+                    );
+                });
+                for axis in 0..AXIS_COUNT {
+                    state.arbiter.rtt_set(axis, now, pot_for_axis(&state, axis));
+                }
+                rprintln!("slideshow: {}", preset.name);
+            }
+
+            // in the ambient light mode, scale the rendered Value by the (smoothed) photoresistor light level
+            if state.ambient.is_enabled()
+                && let Some(channel) = LIGHT_CHANNEL
+            {
+                match adc_pins.read(&mut adc, channel) {
+                    Ok(raw_light) => {
+                        let light = preprocess_sample(raw_light) as f32 / MAX_ADC_VALUE as f32;
+                        let scale = state.ambient.update(light);
+                        COLOR_CONTROLER
+                            .with_lock(|color_controler| color_controler.set_value_scale(scale));
+                    }
+                    Err(()) => DISPLAY.with_lock(|display| display.flash_code(ADC_ERROR_CODE)),
+                }
+            }
+
+            // run a requested channel balance calibration, which needs the ADC owned here
+            if core::mem::take(&mut state.balance_requested) {
+                auto_calibrate_balance(&mut adc, &mut adc_pins);
+            }
+
+            // advance any error code flash on the matrix
+            DISPLAY.with_lock(|display| display.tick());
 
             // reset things for next iteration
             adc_counter = 0;
+            adc_sums = [0; ADC_PIN_COUNT];
             ADC_READY_READ.store(false, SeqCst);
-            ADC_ACCUMULATOR_VALUE.store(0, SeqCst);
+
+            // drain any bytes typed into the RTT terminal and dispatch completed lines
+            let mut rtt_bytes = [0u8; 16];
+            let count = rtt_input.read(&mut rtt_bytes);
+            for &byte in &rtt_bytes[..count] {
+                if let Some(typed) = command_reader.push(byte) {
+                    // `!` and `!n` replay a remembered command, which is then remembered again as the most recent
+                    let Some(line) = state.history.expand(typed) else {
+                        rprintln!("history: no command {}", typed);
+                        continue;
+                    };
+                    match rtt_command::parse(line.as_str()) {
+                        Some(command) => {
+                            state.history.push(line);
+                            state.inactivity.touch(now_us());
+                            stop_demo(&mut state);
+                            handle_command(command, &mut state)
+                        }
+                        None if line.as_str().is_empty() => {}
+                        None => rprintln!("unknown command: {}", line.as_str()),
+                    }
+                }
+            }
+
+            // pass the color down the daisy-chain whenever it has changed, and mirror it on any matrix swatch or
+            // overview. With `watch on` the change is also logged with the code path behind it
+            let mut hsv = STARTING_HSV;
+            let mut source = ColorSource::Rtt;
+            COLOR_CONTROLER.with_lock(|color_controler| {
+                hsv = color_controler.current_hsv();
+                source = color_controler.source();
+            });
+            if let Some(change) = state.watch.check(hsv, source) {
+                rprintln!("watch: {}", change);
+            }
+            state.link.send(hsv);
+            let states = axis_states(&state, display_page, now_us());
+            DISPLAY.with_lock(|display| {
+                display.render_swatch(&hsv);
+                display.render_all_axes(&hsv);
+                display.render_color_code(&hsv);
+                display.set_axis_states(states);
+            });
         }
     }
 }
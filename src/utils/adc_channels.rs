@@ -0,0 +1,130 @@
+//! adc_channels.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The adc_channels module maps the three analog edge pins (e00, e01, and e02) onto the inputs they feed, chosen at
+//! build time through the ADC_CHANNELS table. The main.rs event loop samples every pot channel in the table, averages
+//! each one over the refresh period, and dispatches it to its handler, while the light sensor channel (if any) is read
+//! by the ambient light mode and the channel balance calibration.
+//!
+//! The default table is the single-pot setup: the pot on e02 edits whichever page the A/B buttons select, and the
+//! photoresistor sits on e01. A multi-pot setup gives an axis its own pot, e.g. a Value pot on e00:
+//!
+//! `[Some(AdcInput::AxisPot(HSVPage::V)), Some(AdcInput::Light), Some(AdcInput::Pot)]`
+//!
+//! An axis with its own pot ignores the selected-page pot. The table is checked at compile time: the selected-page
+//! pot is required and no input may be fed by more than one pin.
+
+use microbit::hal::{
+    gpio::{
+        Floating, Input,
+        p0::{P0_02, P0_03, P0_04},
+    },
+    saadc::Saadc,
+};
+
+use super::hsv_display::HSVPage;
+
+/// The input fed by an analog edge pin
+///
+/// 1. Pot: the pot editing the page selected with the A/B buttons
+/// 2. AxisPot: a pot dedicated to one of the H, S, or V axes
+/// 3. Light: the light sensor used by the ambient light mode and the channel balance calibration
+#[derive(Clone, Copy)]
+pub enum AdcInput {
+    Pot,
+    AxisPot(HSVPage),
+    Light,
+}
+
+/// Constants
+pub const ADC_PIN_COUNT: usize = 3; // e00, e01, and e02
+pub const ADC_CHANNELS: [Option<AdcInput>; ADC_PIN_COUNT] =
+    [None, Some(AdcInput::Light), Some(AdcInput::Pot)]; // input fed by e00, e01, and e02, None for an unused pin
+pub const POT_CHANNEL: usize = match channel_for(&ADC_CHANNELS, AdcInput::Pot) {
+    Some(channel) => channel,
+    None => panic!("ADC_CHANNELS has no selected-page pot"),
+}; // pin of the selected-page pot
+pub const LIGHT_CHANNEL: Option<usize> = channel_for(&ADC_CHANNELS, AdcInput::Light); // pin of the light sensor, if any
+
+/// PRIVATE
+/// A number identifying input, equal for two inputs exactly when they are the same input
+const fn input_key(input: AdcInput) -> usize {
+    match input {
+        AdcInput::Pot => 0,
+        AdcInput::Light => 1,
+        AdcInput::AxisPot(page) => 2 + page as usize,
+    }
+}
+
+/// PUBLIC
+/// The pin (index into channels) feeding input, None if no pin does
+pub const fn channel_for(
+    channels: &[Option<AdcInput>; ADC_PIN_COUNT],
+    input: AdcInput,
+) -> Option<usize> {
+    let mut channel = 0;
+    while channel < ADC_PIN_COUNT {
+        if let Some(fed) = channels[channel]
+            && input_key(fed) == input_key(input)
+        {
+            return Some(channel);
+        }
+        channel += 1;
+    }
+    None
+}
+
+/// PUBLIC
+/// Whether the axis (indexed by HSVPage) has a pot of its own in ADC_CHANNELS
+pub fn has_axis_pot(page: HSVPage) -> bool {
+    channel_for(&ADC_CHANNELS, AdcInput::AxisPot(page)).is_some()
+}
+
+// every input is fed by at most one pin, so a table feeding e.g. two light sensors fails the build
+const _: () = {
+    let mut i = 0;
+    while i < ADC_PIN_COUNT {
+        if let Some(input) = ADC_CHANNELS[i] {
+            assert!(
+                matches!(channel_for(&ADC_CHANNELS, input), Some(first) if first == i),
+                "ADC_CHANNELS feeds an input from more than one pin"
+            );
+        }
+        i += 1;
+    }
+};
+
+/// AdcPins struct declaration: Note all fields are private
+///
+/// 1. e00/e01/e02: the analog edge pins, as floating inputs
+pub struct AdcPins {
+    e00: P0_02<Input<Floating>>,
+    e01: P0_03<Input<Floating>>,
+    e02: P0_04<Input<Floating>>,
+}
+
+/// Impl AdcPins
+impl AdcPins {
+    /// PUBLIC
+    /// Generate a new AdcPins owning the three analog edge pins
+    pub fn new(
+        e00: P0_02<Input<Floating>>,
+        e01: P0_03<Input<Floating>>,
+        e02: P0_04<Input<Floating>>,
+    ) -> Self {
+        AdcPins { e00, e01, e02 }
+    }
+
+    /// PUBLIC
+    /// Take one raw SAADC reading of the pin at channel (index into ADC_CHANNELS)
+    pub fn read(&mut self, adc: &mut Saadc, channel: usize) -> Result<i16, ()> {
+        match channel {
+            0 => adc.read_channel(&mut self.e00),
+            1 => adc.read_channel(&mut self.e01),
+            2 => adc.read_channel(&mut self.e02),
+            _ => Err(()),
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! adc_filter.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The adc_filter module contains the AdcFilter struct which smooths the pot percentage [0,1] before it is handed to
+//! ColorControler. The raw readings are always co-added and averaged over each 100ms refresh period (see
+//! adc_scaling.rs), and AdcFilter is fed that block average once per period. It can either:
+//!
+//! 1. Block: pass the block average through unchanged (default). A steady turn shows as a small step every period
+//! 2. Iir: run a first-order IIR low-pass (exponential moving average) over the block averages, each output moving
+//!    alpha of the way from the previous output to the new average, which smooths the steps away at the cost of some
+//!    lag (a time constant of roughly 100ms / alpha)
+//!
+//! The IIR starts at the first sample rather than at 0, so the pot does not fade in from 0 at boot (or after the
+//! filter is changed).
+//!
+//! Before any of that, every raw pot reading passes through a MedianFilter, a median over the last N readings (N =
+//! ADC_MEDIAN_WINDOW, odd). A single-reading spike is never the median, so it is dropped instead of being co-added into
+//! the block average and briefly flashing the color. The window is only a few 40us readings long, so the pot still
+//! responds at once. Until N readings have arrived the filter returns the latest reading as is.
+
+/// Constants
+const MIN_ADC_FILTER_ALPHA: f32 = 0.05; // ~2 sec time constant, anything slower feels like the pot is stuck
+const MAX_ADC_FILTER_ALPHA: f32 = 1.0; // every average taken as is, the same as the block average
+pub const ADC_MEDIAN_WINDOW: usize = 3; // readings each pot reading is the median of, odd (3 or 5; 1 for no filtering)
+
+// the median of an even window would be the mean of two readings, letting half of a spike through
+const _: () = assert!(
+    ADC_MEDIAN_WINDOW % 2 == 1,
+    "the ADC median window must be odd"
+);
+
+/// AdcFilter struct declaration: Note all fields are private
+///
+/// 1. alpha: weight [MIN_ADC_FILTER_ALPHA,1] of each new sample in the IIR, None for the plain block average
+/// 2. smoothed: the IIR output, None until the first sample so the filter starts at the real pot position
+pub struct AdcFilter {
+    alpha: Option<f32>,
+    smoothed: Option<f32>,
+}
+
+/// Impl AdcFilter
+impl AdcFilter {
+    /// PUBLIC
+    /// Generate a new AdcFilter passing the block average through
+    pub const fn new() -> Self {
+        AdcFilter {
+            alpha: None,
+            smoothed: None,
+        }
+    }
+
+    /// PUBLIC
+    /// Switch to the IIR with alpha (clamped to [MIN_ADC_FILTER_ALPHA,1]), or back to the block average for None or a
+    /// non-finite alpha, which would otherwise turn every later output into NaN. The IIR restarts from the next sample
+    pub const fn set_alpha(&mut self, alpha: Option<f32>) {
+        self.alpha = match alpha {
+            Some(alpha) if alpha.is_finite() => {
+                Some(alpha.clamp(MIN_ADC_FILTER_ALPHA, MAX_ADC_FILTER_ALPHA))
+            }
+            _ => None,
+        };
+        self.smoothed = None;
+    }
+
+    /// PUBLIC
+    /// return the IIR alpha, None for the block average
+    pub const fn alpha(&self) -> Option<f32> {
+        self.alpha
+    }
+
+    /// PUBLIC
+    /// Feed the latest block average sample [0,1] of the pot percentage and return the filtered percentage
+    pub const fn update(&mut self, sample: f32) -> f32 {
+        let Some(alpha) = self.alpha else {
+            return sample;
+        };
+        let smoothed = match self.smoothed {
+            Some(previous) => previous + alpha * (sample - previous),
+            None => sample,
+        };
+        self.smoothed = Some(smoothed);
+        smoothed
+    }
+}
+
+/// MedianFilter<N> struct declaration: Note all fields are private
+///
+/// <N> template contains the window length, odd
+///
+/// 1. window: ring buffer of the last N readings
+/// 2. next: index in window the next reading is written to
+/// 3. filled: how many readings window holds, up to N
+#[derive(Clone, Copy)]
+pub struct MedianFilter<const N: usize> {
+    window: [i16; N],
+    next: usize,
+    filled: usize,
+}
+
+/// Impl MedianFilter<N>
+impl<const N: usize> MedianFilter<N> {
+    /// PUBLIC
+    /// Generate a new, empty, MedianFilter<N>
+    pub const fn new() -> Self {
+        MedianFilter {
+            window: [0; N],
+            next: 0,
+            filled: 0,
+        }
+    }
+
+    /// PUBLIC
+    /// Feed the latest raw reading and return the median of the last N readings, or sample itself until N readings
+    /// have arrived
+    pub const fn update(&mut self, sample: i16) -> i16 {
+        self.window[self.next] = sample;
+        self.next = (self.next + 1) % N;
+        if self.filled < N {
+            self.filled += 1;
+            if self.filled < N {
+                return sample;
+            }
+        }
+
+        // insertion sort of a copy, N being a handful of readings
+        let mut sorted = self.window;
+        let mut i = 1;
+        while i < N {
+            let mut j = i;
+            while j > 0 && sorted[j - 1] > sorted[j] {
+                let swap = sorted[j - 1];
+                sorted[j - 1] = sorted[j];
+                sorted[j] = swap;
+                j -= 1;
+            }
+            i += 1;
+        }
+        sorted[N / 2]
+    }
+}
+
+// the warm-up passes readings through, a single-reading spike (either way) is dropped once the window is full, a
+// real step gets through one reading late, and the ring buffer keeps working as it wraps
+const _: () = {
+    let mut median = MedianFilter::<3>::new();
+    assert!(
+        median.update(100) == 100 && median.update(90) == 90,
+        "the warm-up was filtered"
+    );
+    assert!(median.update(95) == 95);
+    assert!(median.update(16_000) == 95, "a spike got through");
+    assert!(median.update(96) == 96);
+    assert!(median.update(-200) == 96, "a spike got through");
+    assert!(median.update(500) == 96);
+    assert!(median.update(500) == 500, "a step did not get through");
+
+    let mut median = MedianFilter::<5>::new();
+    let readings = [10, 20, 30, 40, 9_000, 50, 60, 0, 70];
+    let expected = [10, 20, 30, 40, 30, 40, 50, 50, 60];
+    let mut i = 0;
+    while i < readings.len() {
+        assert!(median.update(readings[i]) == expected[i]);
+        i += 1;
+    }
+};
+
+// the block average passes through, the IIR starts at the first sample (no fade in from 0), moves alpha of the way to
+// each new sample, and restarts when its alpha changes
+const _: () = {
+    let mut block = AdcFilter::new();
+    assert!(block.update(0.8) == 0.8 && block.update(0.2) == 0.2);
+
+    let mut iir = AdcFilter::new();
+    iir.set_alpha(Some(0.5));
+    assert!(iir.update(0.8) == 0.8, "the IIR faded in from 0");
+    assert!(iir.update(0.4) == 0.6);
+    assert!(iir.update(0.4) == 0.5);
+    let mut settled = 0.0;
+    let mut i = 0;
+    while i < 30 {
+        settled = iir.update(0.4);
+        i += 1;
+    }
+    assert!(
+        (settled - 0.4).abs() < 1e-6,
+        "the IIR did not settle on a steady pot"
+    );
+
+    iir.set_alpha(Some(0.0));
+    assert!(matches!(iir.alpha(), Some(MIN_ADC_FILTER_ALPHA)));
+    assert!(
+        iir.update(1.0) == 1.0,
+        "the IIR kept its state across an alpha change"
+    );
+    iir.set_alpha(Some(2.0));
+    assert!(matches!(iir.alpha(), Some(MAX_ADC_FILTER_ALPHA)));
+    iir.set_alpha(Some(f32::NAN));
+    assert!(
+        iir.alpha().is_none() && iir.update(0.3) == 0.3,
+        "a NaN alpha reached the IIR"
+    );
+};
@@ -0,0 +1,140 @@
+//! adc_scaling.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The adc_scaling module converts the averaged raw pot ADC reading into the pot percentage [0,1]. The SAADC has some
+//! integral/differential non-linearity and a pot's resistive taper is rarely perfectly linear, so an optional
+//! correction lookup table (ADC_CORRECTION_LUT) can be applied to the raw reading before it is scaled.
+//!
+//! Every raw reading is first passed through preprocess_sample(), which clamps the small negative readings the SAADC
+//! gives near ground (single-ended inputs still report the offset error with sign) to 0.
+//!
+//! The raw readings are co-added over each refresh period in u64 sums, which cannot overflow however long the period,
+//! and average_samples() keeps the average exact well past the old u32 limit.
+//!
+//! A pot with a center detent can also snap to exactly 0.5 near its middle (see center_snap), making the midpoint easy
+//! to hit, much like the clamping just inside the ADC range makes both ends easy to hit.
+//!
+//! To populate the table from a calibration sweep: set the pot to each of ADC_CORRECTION_POINTS evenly spaced
+//! positions (measured e.g. by angle or with a multimeter as a fraction of 3.3V), note the raw reading at each as a
+//! fraction of MAX_ADC_VALUE, and invert that: entry i is the true position [0,1] whose reading is i / (points - 1).
+
+use super::curve::interpolate;
+
+/// Constants
+pub const MAX_ADC_VALUE: i16 = (1_i16 << 14) - 1_i16; // max value of the ADC output
+const MAX_ADC_THRESHOLD: f32 = MAX_ADC_VALUE as f32 * 0.98; // 16,053; clamp upper ADC bound slightly below max (98%)
+const MIN_ADC_THRESHOLD: f32 = 10f32; // clamp lower ADC bound to 10
+pub const ADC_CORRECTION_POINTS: usize = 9; // correction table entries, evenly spaced over the raw ADC range
+pub const ADC_CORRECTION_LUT: Option<[f32; ADC_CORRECTION_POINTS]> = None; // Some(table) to correct the raw reading
+pub const MAX_CENTER_SNAP: f32 = 0.25; // widest center snap half-width, leaving at least half the travel outside it
+
+/// PUBLIC
+/// A raw SAADC reading as a sample in [0, MAX_ADC_VALUE] ADC counts: negative readings (the offset error near ground)
+/// clamp to 0, and anything above full scale to MAX_ADC_VALUE
+pub const fn preprocess_sample(raw: i16) -> u32 {
+    if raw < 0 {
+        0
+    } else if raw > MAX_ADC_VALUE {
+        MAX_ADC_VALUE as u32
+    } else {
+        raw as u32
+    }
+}
+
+// the preprocessing is pinned at compile time: negative readings clamp to 0, and 0 and full scale pass unchanged
+const _: () = {
+    assert!(preprocess_sample(-1) == 0 && preprocess_sample(i16::MIN) == 0);
+    assert!(preprocess_sample(0) == 0);
+    assert!(preprocess_sample(1) == 1);
+    assert!(preprocess_sample(MAX_ADC_VALUE) == MAX_ADC_VALUE as u32);
+    assert!(preprocess_sample(i16::MAX) == MAX_ADC_VALUE as u32);
+};
+
+/// PUBLIC
+/// The average of count co-added raw ADC readings summing to sum. The whole and fractional parts are divided
+/// separately, so a sum far beyond f32's exact integer range still averages exactly. A count of 0 averages to 0
+pub const fn average_samples(sum: u64, count: u32) -> f32 {
+    if count == 0 {
+        return 0.0;
+    }
+    let count = count as u64;
+    (sum / count) as f32 + (sum % count) as f32 / count as f32
+}
+
+// the averaging is pinned at compile time around and beyond the old u32 sum limit: (sum, count, expected average)
+const _: () = {
+    let cases: [(u64, u32, f32); 6] = [
+        (0, 0, 0.0),
+        (u32::MAX as u64, 1, u32::MAX as f32),
+        (u32::MAX as u64 + 1, 1 << 18, 16_384.0),
+        (16_383 * 300_000, 300_000, 16_383.0),
+        (16_383 * 300_000 + 150_000, 300_000, 16_383.5),
+        (u32::MAX as u64 * 4, u32::MAX, 4.0),
+    ];
+    let mut i = 0;
+    while i < cases.len() {
+        assert!(
+            average_samples(cases[i].0, cases[i].1) == cases[i].2,
+            "average_samples() misaveraged a pinned sum"
+        );
+        i += 1;
+    }
+};
+
+/// PUBLIC
+/// Correct a raw ADC reading (in ADC counts) through lut, whose entries are the corrected fractions [0,1] of full scale
+/// at readings evenly spaced over [0, MAX_ADC_VALUE]
+pub fn correct_adc(raw: f32, lut: &[f32]) -> f32 {
+    interpolate(lut, raw / MAX_ADC_VALUE as f32) * MAX_ADC_VALUE as f32
+}
+
+/// PUBLIC
+/// Scale an averaged raw ADC reading to the pot percentage [0,1]: corrected through lut (if any), clamped to the
+/// usable band just inside the ADC range, then scaled so that band spans [0,1]
+pub fn adc_to_percentage(average: f32, lut: Option<&[f32]>) -> f32 {
+    let corrected = match lut {
+        Some(lut) => correct_adc(average, lut),
+        None => average,
+    };
+    let clamped = corrected.clamp(MIN_ADC_THRESHOLD, MAX_ADC_THRESHOLD);
+    (clamped - MIN_ADC_THRESHOLD) / (MAX_ADC_THRESHOLD - MIN_ADC_THRESHOLD)
+}
+
+/// PUBLIC
+/// Snap the pot percentage [0,1] to exactly 0.5 within ±window (clamped to [0, MAX_CENTER_SNAP]) of the center. The
+/// travel on either side of the window is stretched linearly back over [0,0.5] and [0.5,1], so both ends are still
+/// reached and the mapping has no jumps. A window of 0 leaves the percentage unchanged
+pub const fn center_snap(percentage: f32, window: f32) -> f32 {
+    let window = window.clamp(0.0, MAX_CENTER_SNAP);
+    let offset = percentage - 0.5;
+    if offset.abs() <= window {
+        return 0.5;
+    }
+    0.5 + (offset - window.copysign(offset)) * 0.5 / (0.5 - window)
+}
+
+// the center snap is pinned at compile time: (percentage, window, expected), inside and outside a ±0.05 window
+const _: () = {
+    let cases: [(f32, f32, f32); 9] = [
+        (0.5, 0.05, 0.5),
+        (0.46, 0.05, 0.5),
+        (0.55, 0.05, 0.5),
+        (0.0, 0.05, 0.0),
+        (1.0, 0.05, 1.0),
+        (0.25, 0.05, 0.5 - 0.2 * 0.5 / 0.45),
+        (0.75, 0.05, 0.5 + 0.2 * 0.5 / 0.45),
+        (0.3, 0.0, 0.3),
+        (0.5, 0.0, 0.5),
+    ];
+    let mut i = 0;
+    while i < cases.len() {
+        let snapped = center_snap(cases[i].0, cases[i].1);
+        assert!(
+            (snapped - cases[i].2).abs() < 1e-6,
+            "center_snap() mapped a pinned percentage wrongly"
+        );
+        i += 1;
+    }
+};
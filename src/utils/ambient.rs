@@ -0,0 +1,105 @@
+//! ambient.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The ambient module contains the AmbientLight struct which implements the "value follows ambient light" mode.
+//! A photoresistor voltage divider is sampled on a second ADC channel, smoothed with an exponential moving average,
+//! and mapped to a scale factor which ColorControler applies to the Value (brightness) of the rendered color, so the
+//! LED is brighter in daylight and dimmer at night.
+//!
+//! The photoresistor is assumed to be on the high side of the divider so that more light gives a higher voltage.
+
+use super::app_config::DEFAULT_APP_CONFIG;
+
+/// Constants
+const SMOOTHING_ALPHA: f32 = 0.1; // EMA weight of each new light sample (~1 sec time constant at the 100ms refresh rate)
+
+/// Mapping bounds from the normalized light level [0,1] to the Value scale factor
+///
+/// 1. dark: light level at (and below) which the Value is scaled by min_scale
+/// 2. bright: light level at (and above) which the Value is left unscaled
+/// 3. min_scale: smallest Value scale factor, used in the dark so the LED never goes fully off
+#[derive(Clone, Copy)]
+pub struct AmbientBounds {
+    pub dark: f32,
+    pub bright: f32,
+    pub min_scale: f32,
+}
+
+/// Recommended starting AmbientBounds, those of DEFAULT_APP_CONFIG
+pub const DEFAULT_AMBIENT_BOUNDS: AmbientBounds = DEFAULT_APP_CONFIG.ambient;
+
+/// PUBLIC
+/// Map a normalized light level to a Value scale factor, interpolating linearly between bounds.dark and bounds.bright
+/// and clamping outside of them. Degenerate bounds (bright <= dark) act as a hard threshold at dark.
+pub fn ambient_to_scale(light: f32, bounds: &AmbientBounds) -> f32 {
+    let min_scale = bounds.min_scale.clamp(0.0, 1.0);
+    let fraction = if bounds.bright <= bounds.dark {
+        if light > bounds.dark { 1.0 } else { 0.0 }
+    } else {
+        ((light - bounds.dark) / (bounds.bright - bounds.dark)).clamp(0.0, 1.0)
+    };
+
+    min_scale + (1.0 - min_scale) * fraction
+}
+
+/// AmbientLight struct declaration: Note all fields are private
+///
+/// 1. enabled: whether the ambient light mode is active
+/// 2. bounds: mapping bounds from light level to Value scale
+/// 3. smoothed: EMA of the light level, None until the first sample so the filter starts at the real light level
+pub struct AmbientLight {
+    enabled: bool,
+    bounds: AmbientBounds,
+    smoothed: Option<f32>,
+}
+
+/// Impl AmbientLight
+impl AmbientLight {
+    /// PUBLIC
+    /// Generate a new, disabled AmbientLight using DEFAULT_AMBIENT_BOUNDS
+    pub const fn new() -> Self {
+        AmbientLight {
+            enabled: false,
+            bounds: DEFAULT_AMBIENT_BOUNDS,
+            smoothed: None,
+        }
+    }
+
+    /// PUBLIC
+    /// Turn the ambient light mode on or off. The smoothing restarts from the next sample.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.smoothed = None;
+    }
+
+    /// PUBLIC
+    /// return whether the ambient light mode is active
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// PUBLIC
+    /// Replace the light-level to Value scale mapping bounds
+    pub fn set_bounds(&mut self, bounds: AmbientBounds) {
+        self.bounds = bounds;
+    }
+
+    /// PUBLIC
+    /// Feed a new normalized light sample [0,1] and return the Value scale factor to apply (always 1.0 when disabled)
+    pub fn update(&mut self, light: f32) -> f32 {
+        if !self.enabled {
+            return 1.0;
+        }
+
+        let light = light.clamp(0.0, 1.0);
+        let smoothed = match self.smoothed {
+            Some(previous) => previous + SMOOTHING_ALPHA * (light - previous),
+            None => light,
+        };
+        self.smoothed = Some(smoothed);
+
+        ambient_to_scale(smoothed, &self.bounds)
+    }
+}
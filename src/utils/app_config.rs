@@ -0,0 +1,409 @@
+//! app_config.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The app_config module gathers the startup tunables (refresh rate, debounce, timeouts, ambient bounds, slideshow
+//! timing, starting mode and color, matrix gamma, channel balance) into one AppConfig, built with the
+//! AppConfigBuilder. build() validates the whole combination and returns a descriptive AppConfigError for anything
+//! nonsensical, so main.rs catches a bad setting once at startup instead of it surfacing later as odd behavior. The user
+//! settings persisted to flash (the pot input curves) live separately in config.rs.
+//!
+//! DEFAULT_APP_CONFIG below is the one place to retune the firmware defaults: the builder starts from it, and the
+//! modules' own defaults (STARTING_HSV, DEFAULT_DEBOUNCE_MS, DEFAULT_AMBIENT_BOUNDS, ...) are taken from it. It is
+//! validated at compile time, so a nonsensical default fails the build. The PWM resolution
+//! (ColorControler::BRIGHTNESS_STEPS) is tied to the PWM timing rather than a tunable, so it stays in color_control.rs.
+
+use core::fmt;
+
+use super::ambient::AmbientBounds;
+use super::color_control::ColorControler;
+use super::hsv_rgb_convert::Hsv;
+use super::inactivity::MAX_INACTIVITY_TIMEOUT_MS;
+use super::mode::Mode;
+
+/// The firmware defaults, validated at compile time (see the end of this file)
+pub const DEFAULT_APP_CONFIG: AppConfig = AppConfig {
+    refresh_ms: 100,
+    debounce_ms: 100,
+    pfm_min_on_us: ColorControler::DUTY_STEP_US,
+    idle_timeout_ms: None,
+    blank_timeout_ms: None,
+    ambient: AmbientBounds {
+        dark: 0.05,
+        bright: 0.8,
+        min_scale: 0.1,
+    },
+    slideshow_dwell_ms: 10_000,
+    slideshow_fade_ms: 3_000,
+    starting_mode: Mode::Manual,
+    starting_color: Hsv {
+        h: 0.9167,
+        s: 0.75,
+        v: 0.8,
+    }, // magenta
+    display_gamma: false,
+    channel_balance: [1.0; 3],
+};
+
+/// Constants
+const ADC_SAMPLE_US: u32 = 40; // SAADC acquisition time of each pot sample
+const MIN_ADC_SAMPLES: u32 = 100; // fewest pot samples averaged per refresh for a steady reading
+const MAX_REFRESH_MS: u32 = 1_000; // slower refreshes make the pot feel unresponsive
+const MAX_DEBOUNCE_MS: u32 = 1_000; // longer cooldowns swallow deliberate presses
+
+/// Reason an AppConfig was rejected by AppConfigBuilder::build(). Displays as a descriptive message.
+///
+/// 1. RefreshTooShort: the refresh period averages fewer than MIN_ADC_SAMPLES pot samples
+/// 2. RefreshTooLong: the refresh period is longer than MAX_REFRESH_MS
+/// 3. DebounceTooLong: the debounce cooldown is longer than MAX_DEBOUNCE_MS
+/// 4. PfmPulseTooLong: the minimum on-pulse is not shorter than a whole PWM frame
+/// 5. AmbientBoundsInverted: the ambient dark light level is not below the bright level
+/// 6. AmbientScaleOutOfRange: the ambient minimum Value scale is outside [0,1]
+/// 7. TimeoutTooLong: the named (idle or blank) timeout is longer than MAX_INACTIVITY_TIMEOUT_MS
+/// 8. SlideshowDwellZero: the slideshow dwell time is 0
+/// 9. StartingColorOutOfRange: a starting color component is outside [0,1]
+/// 10. BalanceOutOfRange: a channel balance factor is outside [0,1]
+#[derive(Clone, Copy, PartialEq)]
+pub enum AppConfigError {
+    RefreshTooShort { ms: u32 },
+    RefreshTooLong { ms: u32 },
+    DebounceTooLong { ms: u32 },
+    PfmPulseTooLong { us: u32 },
+    AmbientBoundsInverted { dark: f32, bright: f32 },
+    AmbientScaleOutOfRange { min_scale: f32 },
+    TimeoutTooLong { name: &'static str, ms: u32 },
+    SlideshowDwellZero,
+    StartingColorOutOfRange,
+    BalanceOutOfRange,
+}
+
+impl fmt::Display for AppConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            AppConfigError::RefreshTooShort { ms } => write!(
+                f,
+                "refresh of {}ms averages fewer than {} pot samples ({}us each)",
+                ms, MIN_ADC_SAMPLES, ADC_SAMPLE_US
+            ),
+            AppConfigError::RefreshTooLong { ms } => {
+                write!(f, "refresh of {}ms is longer than {}ms", ms, MAX_REFRESH_MS)
+            }
+            AppConfigError::DebounceTooLong { ms } => {
+                write!(
+                    f,
+                    "debounce of {}ms is longer than {}ms",
+                    ms, MAX_DEBOUNCE_MS
+                )
+            }
+            AppConfigError::PfmPulseTooLong { us } => write!(
+                f,
+                "minimum on-pulse of {}us is not shorter than the {}us PWM frame",
+                us,
+                ColorControler::FRAME_DURATION_US
+            ),
+            AppConfigError::AmbientBoundsInverted { dark, bright } => write!(
+                f,
+                "ambient dark level {} is not below the bright level {}",
+                dark, bright
+            ),
+            AppConfigError::AmbientScaleOutOfRange { min_scale } => {
+                write!(f, "ambient minimum scale {} is outside [0,1]", min_scale)
+            }
+            AppConfigError::TimeoutTooLong { name, ms } => write!(
+                f,
+                "{} timeout of {}ms is longer than {}ms",
+                name, ms, MAX_INACTIVITY_TIMEOUT_MS
+            ),
+            AppConfigError::SlideshowDwellZero => write!(f, "slideshow dwell time is 0"),
+            AppConfigError::StartingColorOutOfRange => {
+                write!(f, "starting color has a component outside [0,1]")
+            }
+            AppConfigError::BalanceOutOfRange => {
+                write!(f, "channel balance has a factor outside [0,1]")
+            }
+        }
+    }
+}
+
+/// AppConfig struct declaration: the validated startup tunables
+///
+/// 1. refresh_ms: pot averaging (and main loop refresh) period
+/// 2. debounce_ms: A/B button debounce cooldown
+/// 3. pfm_min_on_us: minimum on-pulse for pulse frequency modulation, 0 for off (one PWM duty step by default, see
+///    led_gamma.rs)
+/// 4. idle_timeout_ms: return to the starting color after this long without interaction, None for never
+/// 5. blank_timeout_ms: blank the matrix after this long without a button press, None for never
+/// 6. ambient: ambient light mode mapping bounds
+/// 7. slideshow_dwell_ms / slideshow_fade_ms: slideshow mode timing
+/// 8. starting_mode: the operating mode at boot
+/// 9. starting_color: the color at boot (and the initial idle default)
+/// 10. display_gamma: whether the matrix greyscale starts mapped through the perceptual gamma curve
+/// 11. channel_balance: the per-channel (R, G, B) balance factors at boot (see ColorControler::set_channel_balance)
+#[derive(Clone, Copy)]
+pub struct AppConfig {
+    pub refresh_ms: u32,
+    pub debounce_ms: u32,
+    pub pfm_min_on_us: u32,
+    pub idle_timeout_ms: Option<u32>,
+    pub blank_timeout_ms: Option<u32>,
+    pub ambient: AmbientBounds,
+    pub slideshow_dwell_ms: u32,
+    pub slideshow_fade_ms: u32,
+    pub starting_mode: Mode,
+    pub starting_color: Hsv,
+    pub display_gamma: bool,
+    pub channel_balance: [f32; 3],
+}
+
+/// AppConfigBuilder struct declaration: Note all fields are private
+///
+/// 1. config: the AppConfig being built, starting from the firmware defaults
+pub struct AppConfigBuilder {
+    config: AppConfig,
+}
+
+/// Impl AppConfigBuilder
+///
+/// Each setter replaces one tunable and hands the builder back for chaining, build() validates the result
+impl AppConfigBuilder {
+    /// PUBLIC
+    /// Generate a new AppConfigBuilder holding the firmware defaults (DEFAULT_APP_CONFIG)
+    pub const fn new() -> Self {
+        AppConfigBuilder {
+            config: DEFAULT_APP_CONFIG,
+        }
+    }
+
+    /// PUBLIC
+    /// Set the pot averaging (and main loop refresh) period
+    pub const fn refresh_ms(mut self, ms: u32) -> Self {
+        self.config.refresh_ms = ms;
+        self
+    }
+
+    /// PUBLIC
+    /// Set the A/B button debounce cooldown
+    pub const fn debounce_ms(mut self, ms: u32) -> Self {
+        self.config.debounce_ms = ms;
+        self
+    }
+
+    /// PUBLIC
+    /// Set the minimum on-pulse for pulse frequency modulation, 0 for off
+    pub const fn pfm_min_on_us(mut self, us: u32) -> Self {
+        self.config.pfm_min_on_us = us;
+        self
+    }
+
+    /// PUBLIC
+    /// Set the inactivity timeout, None for never
+    pub const fn idle_timeout_ms(mut self, ms: Option<u32>) -> Self {
+        self.config.idle_timeout_ms = ms;
+        self
+    }
+
+    /// PUBLIC
+    /// Set the matrix blanking timeout, None for never
+    pub const fn blank_timeout_ms(mut self, ms: Option<u32>) -> Self {
+        self.config.blank_timeout_ms = ms;
+        self
+    }
+
+    /// PUBLIC
+    /// Set the ambient light mode mapping bounds
+    pub const fn ambient(mut self, bounds: AmbientBounds) -> Self {
+        self.config.ambient = bounds;
+        self
+    }
+
+    /// PUBLIC
+    /// Set the slideshow dwell and fade times
+    pub const fn slideshow(mut self, dwell_ms: u32, fade_ms: u32) -> Self {
+        self.config.slideshow_dwell_ms = dwell_ms;
+        self.config.slideshow_fade_ms = fade_ms;
+        self
+    }
+
+    /// PUBLIC
+    /// Set the operating mode at boot
+    pub const fn starting_mode(mut self, mode: Mode) -> Self {
+        self.config.starting_mode = mode;
+        self
+    }
+
+    /// PUBLIC
+    /// Set the color at boot
+    pub const fn starting_color(mut self, color: Hsv) -> Self {
+        self.config.starting_color = color;
+        self
+    }
+
+    /// PUBLIC
+    /// Set whether the matrix greyscale starts mapped through the perceptual gamma curve
+    pub const fn display_gamma(mut self, enabled: bool) -> Self {
+        self.config.display_gamma = enabled;
+        self
+    }
+
+    /// PUBLIC
+    /// Set the per-channel (R, G, B) balance factors at boot
+    pub const fn channel_balance(mut self, balance: [f32; 3]) -> Self {
+        self.config.channel_balance = balance;
+        self
+    }
+
+    /// PUBLIC
+    /// Validate the configuration, returning it or the first problem found
+    pub const fn build(self) -> Result<AppConfig, AppConfigError> {
+        let config = self.config;
+
+        if config.refresh_ms.saturating_mul(1000) / ADC_SAMPLE_US < MIN_ADC_SAMPLES {
+            return Err(AppConfigError::RefreshTooShort {
+                ms: config.refresh_ms,
+            });
+        }
+        if config.refresh_ms > MAX_REFRESH_MS {
+            return Err(AppConfigError::RefreshTooLong {
+                ms: config.refresh_ms,
+            });
+        }
+        if config.debounce_ms > MAX_DEBOUNCE_MS {
+            return Err(AppConfigError::DebounceTooLong {
+                ms: config.debounce_ms,
+            });
+        }
+        if config.pfm_min_on_us >= ColorControler::FRAME_DURATION_US {
+            return Err(AppConfigError::PfmPulseTooLong {
+                us: config.pfm_min_on_us,
+            });
+        }
+        if config.ambient.dark >= config.ambient.bright {
+            return Err(AppConfigError::AmbientBoundsInverted {
+                dark: config.ambient.dark,
+                bright: config.ambient.bright,
+            });
+        }
+        if !in_unit(config.ambient.min_scale) {
+            return Err(AppConfigError::AmbientScaleOutOfRange {
+                min_scale: config.ambient.min_scale,
+            });
+        }
+        let timeouts = [
+            ("idle", config.idle_timeout_ms),
+            ("blank", config.blank_timeout_ms),
+        ];
+        let mut i = 0;
+        while i < timeouts.len() {
+            if let (name, Some(ms)) = timeouts[i]
+                && ms > MAX_INACTIVITY_TIMEOUT_MS
+            {
+                return Err(AppConfigError::TimeoutTooLong { name, ms });
+            }
+            i += 1;
+        }
+        if config.slideshow_dwell_ms == 0 {
+            return Err(AppConfigError::SlideshowDwellZero);
+        }
+        let color = config.starting_color;
+        if !(in_unit(color.h) && in_unit(color.s) && in_unit(color.v)) {
+            return Err(AppConfigError::StartingColorOutOfRange);
+        }
+        let [balance_r, balance_g, balance_b] = config.channel_balance;
+        if !(in_unit(balance_r) && in_unit(balance_g) && in_unit(balance_b)) {
+            return Err(AppConfigError::BalanceOutOfRange);
+        }
+        Ok(config)
+    }
+}
+
+/// PRIVATE
+/// Whether x lies in [0,1]
+const fn in_unit(x: f32) -> bool {
+    x >= 0.0 && x <= 1.0
+}
+
+// DEFAULT_APP_CONFIG builds unchanged, and each setter's nonsensical values are caught by build()
+const _: () = {
+    match AppConfigBuilder::new().build() {
+        Ok(config) => assert!(
+            config.refresh_ms == DEFAULT_APP_CONFIG.refresh_ms
+                && config.debounce_ms == DEFAULT_APP_CONFIG.debounce_ms
+                && config.starting_color.h == DEFAULT_APP_CONFIG.starting_color.h
+                && matches!(config.starting_mode, Mode::Manual),
+            "DEFAULT_APP_CONFIG changed by build()"
+        ),
+        Err(_) => panic!("DEFAULT_APP_CONFIG is invalid"),
+    }
+
+    let defaults = AppConfigBuilder::new;
+    assert!(matches!(
+        defaults().refresh_ms(1).build(),
+        Err(AppConfigError::RefreshTooShort { ms: 1 })
+    ));
+    assert!(matches!(
+        defaults().refresh_ms(MAX_REFRESH_MS + 1).build(),
+        Err(AppConfigError::RefreshTooLong { .. })
+    ));
+    assert!(matches!(
+        defaults().debounce_ms(MAX_DEBOUNCE_MS + 1).build(),
+        Err(AppConfigError::DebounceTooLong { .. })
+    ));
+    assert!(matches!(
+        defaults()
+            .pfm_min_on_us(ColorControler::FRAME_DURATION_US)
+            .build(),
+        Err(AppConfigError::PfmPulseTooLong { .. })
+    ));
+    assert!(matches!(
+        defaults()
+            .ambient(AmbientBounds {
+                dark: 0.8,
+                bright: 0.05,
+                min_scale: 0.1
+            })
+            .build(),
+        Err(AppConfigError::AmbientBoundsInverted { .. })
+    ));
+    assert!(matches!(
+        defaults()
+            .idle_timeout_ms(Some(MAX_INACTIVITY_TIMEOUT_MS + 1))
+            .build(),
+        Err(AppConfigError::TimeoutTooLong { .. })
+    ));
+    assert!(matches!(
+        defaults()
+            .blank_timeout_ms(Some(MAX_INACTIVITY_TIMEOUT_MS + 1))
+            .build(),
+        Err(AppConfigError::TimeoutTooLong { .. })
+    ));
+    assert!(matches!(
+        defaults().slideshow(0, 0).build(),
+        Err(AppConfigError::SlideshowDwellZero)
+    ));
+    assert!(matches!(
+        defaults()
+            .starting_color(Hsv {
+                h: 0.5,
+                s: 1.5,
+                v: 1.0
+            })
+            .build(),
+        Err(AppConfigError::StartingColorOutOfRange)
+    ));
+    assert!(matches!(
+        defaults().channel_balance([1.0, 1.2, 1.0]).build(),
+        Err(AppConfigError::BalanceOutOfRange)
+    ));
+    assert!(matches!(
+        defaults()
+            .starting_mode(Mode::Slideshow)
+            .display_gamma(true)
+            .build(),
+        Ok(AppConfig {
+            starting_mode: Mode::Slideshow,
+            display_gamma: true,
+            ..
+        })
+    ));
+};
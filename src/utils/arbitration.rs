@@ -0,0 +1,125 @@
+//! arbitration.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The arbitration module contains the AxisArbiter struct which decides whether the pot or the RTT interface owns
+//! each of the H, S, and V axes. Without it, the pot would overwrite an RTT-set value on the very next 100ms refresh.
+//!
+//! Whenever RTT sets an axis, that axis is "held" and the pot is ignored for it. How the hold ends depends upon the
+//! ArbitrationPolicy:
+//! 1. HoldFor (the default, 3 seconds): once the hold time has elapsed the pot takes the axis back, but only after it
+//!    has actually been turned away from where it was when RTT set the axis. This way the color does not jump back
+//!    to the pot position just because the timeout ran out.
+//! 2. Lock: the pot is ignored for the axis until it is explicitly released over RTT.
+
+/// Constants
+pub const AXIS_COUNT: usize = 3; // H, S, and V
+pub const POT_ACTIVITY_THRESHOLD: f32 = 0.02; // pot must move 2% of full scale to count as activity
+pub const DEFAULT_ARBITRATION_POLICY: ArbitrationPolicy = ArbitrationPolicy::HoldFor { ms: 3000 };
+
+/// How an RTT hold on an axis ends (see module docs)
+#[derive(Clone, Copy)]
+pub enum ArbitrationPolicy {
+    HoldFor { ms: u32 },
+    Lock,
+}
+
+/// Record of an RTT set on an axis
+///
+/// 1. set_us: SYSTEM_CLOCK timestamp at which RTT set the axis
+/// 2. pot_at_set: pot percentage [0,1] at the time RTT set the axis
+#[derive(Clone, Copy)]
+pub struct Hold {
+    pub set_us: u32,
+    pub pot_at_set: f32,
+}
+
+/// PUBLIC
+/// The arbitration decision: may the pot (currently at pot) write an axis with the given hold at time now_us?
+/// Timestamps are compared with wrap-around.
+pub fn pot_may_write(hold: Option<Hold>, policy: ArbitrationPolicy, now_us: u32, pot: f32) -> bool {
+    let Some(hold) = hold else {
+        return true;
+    };
+
+    match policy {
+        ArbitrationPolicy::Lock => false,
+        ArbitrationPolicy::HoldFor { ms } => {
+            let expired = now_us.wrapping_sub(hold.set_us) >= ms.saturating_mul(1000);
+            let pot_moved = (pot - hold.pot_at_set).abs() > POT_ACTIVITY_THRESHOLD;
+            expired && pot_moved
+        }
+    }
+}
+
+/// AxisArbiter struct declaration: Note all fields are private
+///
+/// 1. policy: how RTT holds end
+/// 2. holds: per-axis (indexed by HSVPage) RTT hold, None when the pot owns the axis
+pub struct AxisArbiter {
+    policy: ArbitrationPolicy,
+    holds: [Option<Hold>; AXIS_COUNT],
+}
+
+/// Impl AxisArbiter
+impl AxisArbiter {
+    /// PUBLIC
+    /// Generate a new AxisArbiter with DEFAULT_ARBITRATION_POLICY and every axis owned by the pot
+    pub const fn new() -> Self {
+        AxisArbiter {
+            policy: DEFAULT_ARBITRATION_POLICY,
+            holds: [None; AXIS_COUNT],
+        }
+    }
+
+    /// PUBLIC
+    /// Change the policy. Existing holds are kept and end according to the new policy.
+    pub fn set_policy(&mut self, policy: ArbitrationPolicy) {
+        self.policy = policy;
+    }
+
+    /// PUBLIC
+    /// Record that RTT set the axis at now_us while the pot was at pot
+    pub fn rtt_set(&mut self, axis: usize, now_us: u32, pot: f32) {
+        self.holds[axis] = Some(Hold {
+            set_us: now_us,
+            pot_at_set: pot,
+        });
+    }
+
+    /// PUBLIC
+    /// Hand every axis back to the pot
+    pub fn release_all(&mut self) {
+        self.holds = [None; AXIS_COUNT];
+    }
+
+    /// PUBLIC
+    /// Returns true while RTT holds the axis against the pot
+    pub fn is_held(&self, axis: usize) -> bool {
+        self.holds[axis].is_some()
+    }
+
+    /// PUBLIC
+    /// Returns true if the hold on the axis has run out and only waits for the pot to be turned (HoldFor policy), i.e.
+    /// the pot takes the axis back as soon as it moves. Timestamps are compared with wrap-around.
+    pub fn awaiting_pot(&self, axis: usize, now_us: u32) -> bool {
+        match (self.holds[axis], self.policy) {
+            (Some(hold), ArbitrationPolicy::HoldFor { ms }) => {
+                now_us.wrapping_sub(hold.set_us) >= ms.saturating_mul(1000)
+            }
+            _ => false,
+        }
+    }
+
+    /// PUBLIC
+    /// Returns true if the pot may write the axis now. When an expired hold is released here, the pot keeps the axis
+    /// until RTT sets it again.
+    pub fn pot_may_write(&mut self, axis: usize, now_us: u32, pot: f32) -> bool {
+        let allowed = pot_may_write(self.holds[axis], self.policy, now_us, pot);
+        if allowed {
+            self.holds[axis] = None;
+        }
+        allowed
+    }
+}
@@ -0,0 +1,33 @@
+//! auto_balance.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The auto_balance module contains the math behind the closed-loop channel balance calibration. With a photodiode
+//! (or the photoresistor) on e01 facing the LED, main.rs measures the light with the LED dark and then with each
+//! channel alone driven at BALANCE_DRIVE_DUTY, and balance_factors() turns those readings into per-channel scales
+//! (see ColorControler::set_channel_balance) which dim the brighter channels down to the dimmest one, so that equal
+//! R, G, and B levels give equal light and a white renders neutral.
+//!
+//! The factors equalize the channels as the sensor sees them, so a sensor with a strongly uneven spectral response
+//! skews the result.
+
+/// Constants
+pub const BALANCE_DRIVE_DUTY: f32 = 0.5; // each channel is measured at half duty, keeping the sensor out of saturation
+pub const BALANCE_SETTLE_MS: u32 = 200; // photoresistors take ~100ms to settle after the light changes
+pub const BALANCE_SAMPLES: u32 = 16; // ADC readings averaged per measurement
+const MIN_CHANNEL_LIGHT: f32 = 0.01; // a channel adding less light than this (fraction of full scale) went unseen
+
+/// PUBLIC
+/// The per-channel (R, G, B) balance factors equalizing the channels: dark is the light level measured with the LED
+/// off and measured the level with each channel alone on, all fractions [0,1] of full scale. The dimmest channel keeps
+/// a factor of 1 and the others are scaled down to match it. Returns None if a channel added too little light to be
+/// measured, e.g. with the sensor not facing the LED.
+pub fn balance_factors(dark: f32, measured: [f32; 3]) -> Option<[f32; 3]> {
+    let light = measured.map(|level| level - dark);
+    if light.iter().any(|&level| level < MIN_CHANNEL_LIGHT) {
+        return None;
+    }
+    let dimmest = light.iter().copied().fold(f32::MAX, f32::min);
+    Some(light.map(|level| dimmest / level))
+}
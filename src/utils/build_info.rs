@@ -0,0 +1,50 @@
+//! build_info.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The build_info module identifies the flashed firmware for the `version` RTT command: the crate version from
+//! Cargo.toml and the git hash and build time injected by build.rs, all baked in at compile time. Quote the printed
+//! line when filing an issue.
+
+use core::fmt;
+
+/// Constants
+pub const BUILD_INFO: BuildInfo = BuildInfo {
+    version: env!("CARGO_PKG_VERSION"),
+    git_hash: env!("HSV_GIT_HASH"),
+    build_time: env!("HSV_BUILD_TIME"),
+}; // this firmware's build info
+
+/// BuildInfo struct declaration: the identity of a firmware build. Displays as `HSV <version> (<git_hash>) built
+/// <build_time>`
+///
+/// 1. version: the crate version
+/// 2. git_hash: the short hash of the commit built, `-dirty` for uncommitted changes, or `unknown`
+/// 3. build_time: the UTC build time, `YYYY-MM-DDTHH:MM:SSZ`
+#[derive(Clone, Copy)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub git_hash: &'static str,
+    pub build_time: &'static str,
+}
+
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "HSV {} ({}) built {}",
+            self.version, self.git_hash, self.build_time
+        )
+    }
+}
+
+// build.rs always injects every field, so a missing one points at a broken build script
+const _: () = {
+    assert!(!BUILD_INFO.version.is_empty(), "no crate version");
+    assert!(!BUILD_INFO.git_hash.is_empty(), "no git hash");
+    assert!(
+        BUILD_INFO.build_time.len() == "YYYY-MM-DDTHH:MM:SSZ".len(),
+        "build time not formatted as YYYY-MM-DDTHH:MM:SSZ"
+    );
+};
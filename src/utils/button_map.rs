@@ -0,0 +1,90 @@
+//! button_map.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The button_map module is the single place deciding what the A and B buttons do. By default A rotates the page left
+//! and B rotates it right, the soft power long press (see power_toggle.rs) is held on the right-turning button, B, and
+//! the demo reel long press (see demo_reel.rs) on the left-turning button, A. Building with the `swap-buttons` feature
+//! mirrors the whole layout: A rotates right and is the power button, and B rotates left and starts the demo reel.
+//! The A+B chord needs both buttons and is the same either way.
+//!
+//! The GPIOTE handler and the main.rs event loop ask page_turn() and button_for() rather than naming A or B directly.
+
+/// Constants
+pub const SWAP_BUTTONS: bool = cfg!(feature = "swap-buttons"); // whether A and B trade places
+
+/// A physical button, its value indexing the [A, B] button pins and held states
+///
+/// 1. A: button A, GPIOTE channel 0
+/// 2. B: button B, GPIOTE channel 1
+#[derive(Clone, Copy, PartialEq)]
+pub enum Button {
+    A = 0,
+    B = 1,
+}
+
+/// Impl Button
+impl Button {
+    /// PUBLIC
+    /// The other one of the two buttons
+    pub const fn other(self) -> Self {
+        match self {
+            Button::A => Button::B,
+            Button::B => Button::A,
+        }
+    }
+}
+
+/// The direction a button rotates the page in
+///
+/// 1. Left: HSVDisplay::left()
+/// 2. Right: HSVDisplay::right(), this button also being the soft power button
+#[derive(Clone, Copy, PartialEq)]
+pub enum PageTurn {
+    Left,
+    Right,
+}
+
+/// PUBLIC
+/// The direction button rotates the page in, with A and B traded when swapped
+pub const fn page_turn(button: Button, swapped: bool) -> PageTurn {
+    match (button, swapped) {
+        (Button::A, false) | (Button::B, true) => PageTurn::Left,
+        (Button::B, false) | (Button::A, true) => PageTurn::Right,
+    }
+}
+
+/// PUBLIC
+/// The button rotating the page in the direction turn, with A and B traded when swapped
+pub const fn button_for(turn: PageTurn, swapped: bool) -> Button {
+    match page_turn(Button::A, swapped) {
+        a_turn if a_turn as u8 == turn as u8 => Button::A,
+        _ => Button::B,
+    }
+}
+
+// A goes left and B right unless swapped, when A goes right (and so holds the soft power) and B left; for either
+// setting button_for() inverts page_turn()
+const _: () = {
+    assert!(matches!(page_turn(Button::A, false), PageTurn::Left));
+    assert!(matches!(page_turn(Button::B, false), PageTurn::Right));
+    assert!(matches!(page_turn(Button::A, true), PageTurn::Right));
+    assert!(matches!(page_turn(Button::B, true), PageTurn::Left));
+    assert!(matches!(button_for(PageTurn::Right, false), Button::B));
+    assert!(matches!(button_for(PageTurn::Right, true), Button::A));
+
+    let buttons = [Button::A, Button::B];
+    let mut i = 0;
+    while i < buttons.len() {
+        let swapped = [false, true];
+        let mut j = 0;
+        while j < swapped.len() {
+            let turn = page_turn(buttons[i], swapped[j]);
+            assert!(button_for(turn, swapped[j]) as u8 == buttons[i] as u8);
+            assert!(page_turn(buttons[i].other(), swapped[j]) as u8 != turn as u8);
+            j += 1;
+        }
+        i += 1;
+    }
+};
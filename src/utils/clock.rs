@@ -0,0 +1,112 @@
+//! clock.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The clock module contains the Clock trait, the one way the firmware asks for the current time, and its two
+//! implementations:
+//!
+//! 1. TimerClock<T>: on the device, a free running 1MHz HAL TIMER (the SYSTEM_CLOCK read by now_us() in main.rs, and
+//!    the Debouncer's press captures, see debounce.rs)
+//! 2. ManualClock: a clock which only moves when advanced, so the time-based state machines can be driven through an
+//!    exact sequence of timestamps in the compile-time checks
+//!
+//! Ticks are TIMER ticks, i.e. usecs (see timers.rs), and wrap every ~71 minutes, so compare them with wrapping_sub.
+//! The state machines built on the SYSTEM_CLOCK (inactivity, long press, commit debounce, slideshow, and so on) take
+//! the timestamp read from the clock rather than the clock itself, which keeps their poll() const and checkable with
+//! plain numbers; only the Debouncer reads its clock itself, as its capture has to happen inside the GPIOTE handler.
+
+use microbit::hal::{
+    Timer,
+    timer::{Instance, Periodic},
+};
+
+use super::timers::TIMER_TICKS_PER_MS;
+
+/// A source of the current time in TIMER ticks (usecs), wrapping at u32::MAX
+pub trait Clock {
+    /// return the current time in ticks
+    fn now_ticks(&self) -> u32;
+}
+
+/// TimerClock<T> struct declaration: Note all fields are private
+///
+/// <T> template contains the TIMER instance counted on
+///
+/// 1. timer: free-running 1MHz TIMER
+pub struct TimerClock<T>
+where
+    T: Instance,
+{
+    timer: Timer<T, Periodic>,
+}
+
+/// Impl TimerClock<T>
+impl<T> TimerClock<T>
+where
+    T: Instance,
+{
+    /// PUBLIC
+    /// Generate a new TimerClock<T>, starting the TIMER free-running
+    pub fn new(timer: T) -> Self {
+        let mut timer = Timer::periodic(timer);
+        timer.start(u32::MAX);
+        TimerClock { timer }
+    }
+}
+
+impl<T> Clock for TimerClock<T>
+where
+    T: Instance,
+{
+    fn now_ticks(&self) -> u32 {
+        self.timer.read()
+    }
+}
+
+/// ManualClock struct declaration: Note all fields are private
+///
+/// 1. ticks: the current time, only changed by advance_ms()
+#[derive(Clone, Copy)]
+pub struct ManualClock {
+    ticks: u32,
+}
+
+/// Impl ManualClock
+impl ManualClock {
+    /// PUBLIC
+    /// Generate a new ManualClock reading ticks
+    pub const fn new(ticks: u32) -> Self {
+        ManualClock { ticks }
+    }
+
+    /// PUBLIC
+    /// Move the clock on by ms, wrapping like the TIMER does
+    pub const fn advance_ms(&mut self, ms: u32) {
+        self.ticks = self.ticks.wrapping_add(ms * TIMER_TICKS_PER_MS);
+    }
+
+    /// PUBLIC
+    /// return the current time in ticks. The same as Clock::now_ticks(), callable in a const context
+    pub const fn now_ticks(&self) -> u32 {
+        self.ticks
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_ticks(&self) -> u32 {
+        ManualClock::now_ticks(self)
+    }
+}
+
+// a manual clock only moves when advanced, in TIMER ticks, and wraps like the hardware counter
+const _: () = {
+    let mut clock = ManualClock::new(u32::MAX - 500);
+    assert!(clock.now_ticks() == u32::MAX - 500);
+    clock.advance_ms(1);
+    assert!(
+        clock.now_ticks() == 499,
+        "a manual clock does not wrap like the TIMER"
+    );
+    assert!(clock.now_ticks().wrapping_sub(u32::MAX - 500) == TIMER_TICKS_PER_MS);
+};
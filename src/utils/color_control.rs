@@ -6,23 +6,309 @@
 //! The color_control module contains the ColorControler struct which is a wrapper around the RGB LED
 //! state and pin control, conviently allowing for pulse width modulation (PWM) of the R, G, and B pin states
 //! to generate the requested color via the HSV setting. The PWM is updated at a 100usec refresh rate.
+//!
+//! With the `ppi-pwm` cargo feature the same three pins are instead driven entirely in hardware: TIMER2 compare events
+//! are wired through PPI channels to GPIOTE set/clear tasks, so the CPU is only interrupted once per frame (to load the
+//! next frame's duty cycles) rather than on every PWM step. See the README for the PPI/GPIOTE channel allocation.
+//!
+//! With the `hw-pwm` cargo feature the pins are instead handed to the PWM0 peripheral, which generates a 16kHz PWM on
+//! all three with 1000 duty steps (~10 bits, finer than the BRIGHTNESS_STEPS the software PWM rounds to) and reads
+//! the duty cycles from RAM by EasyDMA (see PWM_SEQUENCE). One PWM instance has four channels sharing one counter, so
+//! PWM0 alone drives R, G, and B in lockstep and PWM1/PWM2 stay free. TIMER2 is not used at all, and the CPU is only
+//! interrupted once per frame, by PWM0, to write the next frame's duty cycles. The faster PWM also moves the LED's
+//! flicker far above what the eye (or a camera) picks up.
+//!
+//! Locking: main.rs keeps the ColorControler in the COLOR_CONTROLER LockMut, whose with_lock() is a critical section
+//! (interrupts masked). The contention is one-directional: the event loop cannot interrupt the TIMER2 handler, so an
+//! update never waits on render(), but every event loop critical section holds off the next TIMER2 interrupt (a PWM
+//! step edge) for as long as it lasts, stretching that step. The event loop closures therefore only store, and
+//! anything costly is resolved before taking the lock. The pot writes are the most frequent: they used to run the RGB
+//! page's Hsv -> Rgb -> Hsv round trip with interrupts masked and take the lock once per axis pot, whereas a PotWrite
+//! is now resolved outside the lock and a refresh applies all of its pot writes in one closure of a few stores each
+//! (see apply_pot). render() holds the lock for one PWM step's bookkeeping, and a frame's next_frame_rgb() at most;
+//! the `pwm` RTT command reports the resulting PWM interrupt CPU load.
 
-use embedded_hal::digital::OutputPin;
+#[cfg(not(any(feature = "ppi-pwm", feature = "hw-pwm")))]
+use embedded_hal::digital::{OutputPin, PinState};
+#[cfg(feature = "hw-pwm")]
+use microbit::pac::PWM0;
+#[cfg(feature = "ppi-pwm")]
+use microbit::{
+    hal::{
+        gpiote::{Gpiote, GpioteChannel},
+        ppi::{self, ConfigurablePpi, Ppi},
+    },
+    pac::{TIMER2, timer0::EVENTS_COMPARE},
+};
 //use rtt_target::rprint;
 
-use super::hsv_rgb_convert::{Hsv, Rgb};
+use super::app_config::DEFAULT_APP_CONFIG;
+use super::color_space::ColorSpace;
+use super::color_watch::ColorSource;
+use super::effects::{Effect, EffectStack};
+use super::fade::{Fade, HueDirection};
+use super::hsv_display::HSVPage;
+use super::hsv_rgb_convert::{Hsv, Rgb, normalize_hue};
+use super::hue_rate::{DEFAULT_HUE_RATE_CAP, clamp_hue_rate_cap};
+use super::hue_warp::PrimaryCalibration;
+use super::kelvin::{clamp_kelvin, kelvin_to_rgb};
+use super::led_gamma::{DEFAULT_LED_GAMMA, clamp_led_gamma, gamma_curve};
+#[cfg(not(feature = "hw-pwm"))]
+use super::timers::TIMER_TICKS_PER_US;
+use super::value_floor::ValueFloor;
 
 use crate::BluePinType;
+#[cfg(not(feature = "hw-pwm"))]
 use crate::ColorTimer;
 use crate::GreenPinType;
 use crate::RedPinType;
 
-/// Recommended starting HSV state, represnting the color magenta
-pub const STARTING_HSV: Hsv = Hsv {
-    h: 0.9167,
-    s: 0.75,
-    v: 0.8,
-}; //magenta
+#[cfg(all(feature = "ppi-pwm", feature = "hw-pwm"))]
+compile_error!("the ppi-pwm and hw-pwm features are alternative PWM backends, enable at most one");
+
+/// The `hw-pwm` feature's PWM0 sequence: the compare values (see ColorControler::pwm_compare) of the R, G, and B
+/// channels, plus the unused fourth channel, for the frame being shown. EasyDMA can only read RAM at a fixed address, so
+/// it lives in a static rather than in the ColorControler (which is moved into its LockMut after new()). Only
+/// render() writes it, with the PWM0 interrupt the lock holds off.
+#[cfg(feature = "hw-pwm")]
+static mut PWM_SEQUENCE: [u16; 4] = [0; 4];
+
+/// Recommended starting HSV state, the DEFAULT_APP_CONFIG starting color
+pub const STARTING_HSV: Hsv = DEFAULT_APP_CONFIG.starting_color;
+
+/// Electrical wiring of the RGB LED.
+///
+/// 1. CommonAnode: the shared LED leg is tied to 3.3V, so a channel lights when its pin is driven low (default)
+/// 2. CommonCathode: the shared LED leg is tied to GND, so a channel lights when its pin is driven high
+#[derive(Clone, Copy, PartialEq)]
+pub enum Polarity {
+    CommonAnode,
+    CommonCathode,
+}
+
+/// Impl Polarity
+impl Polarity {
+    /// PUBLIC
+    /// Map the state of the polarity configuration jumper (see main.rs) to a Polarity. The jumper pin is pulled up
+    /// internally, so an open jumper (pin reads high) selects the default CommonAnode wiring while a jumper to
+    /// GND (pin reads low) selects CommonCathode.
+    pub fn from_jumper(jumper_is_low: bool) -> Self {
+        if jumper_is_low {
+            Polarity::CommonCathode
+        } else {
+            Polarity::CommonAnode
+        }
+    }
+
+    /// PUBLIC
+    /// The pin level (true == high) which turns an LED channel on or off for this wiring
+    pub fn pin_level(&self, on: bool) -> bool {
+        match self {
+            Polarity::CommonAnode => !on,
+            Polarity::CommonCathode => on,
+        }
+    }
+}
+
+/// PUBLIC
+/// Pulse frequency modulation (PFM) of one channel's level [0,1] for a frame, for LED drivers that won't light below a
+/// minimum on-pulse of min_level (fraction of a frame, 0 disables PFM). A level at or above min_level is rendered as
+/// normal PWM. Below it, the channel is instead lit for exactly min_level in some frames and left dark in the others:
+/// accumulator carries the owed brightness between frames so the average over many frames still equals level, with
+/// longer and longer off periods the dimmer the level.
+pub const fn pfm_level(level: f32, min_level: f32, accumulator: &mut f32) -> f32 {
+    if level >= min_level || level <= 0.0 {
+        *accumulator = 0.0;
+        return level;
+    }
+
+    *accumulator += level;
+    if *accumulator >= min_level {
+        *accumulator -= min_level;
+        min_level
+    } else {
+        0.0
+    }
+}
+
+/// PUBLIC
+/// Posterize one channel's level [0,1] to levels evenly spaced levels from 0 to 1 (at least 2), rounding to the
+/// nearest, e.g. 3 levels snap every level to 0, 0.5, or 1
+pub const fn posterize(level: f32, levels: u8) -> f32 {
+    let top = if levels < 2 { 1.0 } else { (levels - 1) as f32 };
+    let scaled = level.clamp(0.0, 1.0) * top + 0.5;
+    (scaled as u32) as f32 / top
+}
+
+/// A pot reading resolved into the write it makes to base_color, computed outside the COLOR_CONTROLER lock so that
+/// applying it (see ColorControler::apply_pot) only stores
+///
+/// 1. Hue: set the hue, already wrapped into [0,1)
+/// 2. Sat: set the saturation
+/// 3. Value: set the value, subject to the value floor's pot policy (see value_floor.rs)
+/// 4. Color: set all three components, for an RGB channel edit converted back to Hsv
+#[derive(Clone, Copy)]
+pub enum PotWrite {
+    Hue(f32),
+    Sat(f32),
+    Value(f32),
+    Color(Hsv),
+}
+
+/// Impl PotWrite
+impl PotWrite {
+    /// PUBLIC
+    /// The write a pot reading of value makes to page in color_space, hsv being the color in use
+    pub const fn new(color_space: ColorSpace, page: HSVPage, hsv: Hsv, value: f32) -> Self {
+        match (color_space, page) {
+            (ColorSpace::Hsv, HSVPage::H) => PotWrite::Hue(normalize_hue(value)),
+            (ColorSpace::Hsv, HSVPage::S) => PotWrite::Sat(value),
+            (ColorSpace::Hsv, HSVPage::V) => PotWrite::Value(value),
+            (ColorSpace::Rgb, _) => PotWrite::Color(color_space.with_component(page, hsv, value)),
+        }
+    }
+}
+
+// an HSV page writes just its component (the hue wrapped, so the seam of the color wheel is stored as 0), while an RGB
+// channel edit is resolved into the whole color up front: full red turning green up to full becomes yellow
+const _: () = {
+    let red = Hsv {
+        h: 0.0,
+        s: 1.0,
+        v: 1.0,
+    };
+    assert!(matches!(
+        PotWrite::new(ColorSpace::Hsv, HSVPage::H, red, 1.0),
+        PotWrite::Hue(0.0)
+    ));
+    assert!(matches!(
+        PotWrite::new(ColorSpace::Hsv, HSVPage::S, red, 0.25),
+        PotWrite::Sat(0.25)
+    ));
+    assert!(matches!(
+        PotWrite::new(ColorSpace::Hsv, HSVPage::V, red, 0.5),
+        PotWrite::Value(0.5)
+    ));
+    match PotWrite::new(ColorSpace::Rgb, HSVPage::S, red, 1.0) {
+        PotWrite::Color(yellow) => {
+            assert!(yellow.h * 6.0 == 1.0 && yellow.s == 1.0 && yellow.v == 1.0)
+        }
+        _ => panic!("an RGB channel edit was not resolved into a color"),
+    }
+};
+
+/// PwmStep struct declaration: a snapshot of one software PWM step, taken from the on-time each channel still owes in
+/// the current frame
+///
+/// 1. on: whether the R, G, and B channels are lit during the step (those with on-time left)
+/// 2. level: length of the step as a fraction of a frame, the smallest non-zero owed on-time (0 when all are dark)
+#[cfg(not(any(feature = "ppi-pwm", feature = "hw-pwm")))]
+#[derive(Clone, Copy, PartialEq)]
+pub struct PwmStep {
+    pub on: [bool; 3],
+    pub level: f32,
+}
+
+/// Impl PwmStep
+#[cfg(not(any(feature = "ppi-pwm", feature = "hw-pwm")))]
+impl PwmStep {
+    /// PUBLIC
+    /// The step taken from remaining, the on-time each channel still owes in the frame. The step lasts until the
+    /// dimmest lit channel has had its on-time, so the level is 0 only when all three channels are dark.
+    pub const fn of(remaining: &Rgb) -> Self {
+        let mut min = 1.1; // a number greater than what any of the r,g,b values can be
+        let levels = [remaining.r, remaining.g, remaining.b];
+        let mut i = 0;
+        while i < levels.len() {
+            if levels[i] < min && levels[i] > 0.0 {
+                min = levels[i];
+            }
+            i += 1;
+        }
+
+        PwmStep {
+            on: [remaining.r > 0.0, remaining.g > 0.0, remaining.b > 0.0],
+            // if min is > 1 then all rgb values are 0
+            level: if min > 1.0 { 0.0 } else { min },
+        }
+    }
+}
+
+/// Transition struct declaration: the linear glide of the rendered R/G/B levels (before PFM and rounding) from the
+/// levels shown when the target last changed to the target. Note all fields are private
+///
+/// 1. from: the levels shown when the target last changed, where the glide starts
+/// 2. target: the levels the glide ends at
+/// 3. shown: the levels of the last frame started
+/// 4. step: frames of the glide shown so far, the glide has ended once it reaches the frame count
+#[derive(Clone, Copy)]
+struct Transition {
+    from: Rgb,
+    target: Rgb,
+    shown: Rgb,
+    step: u32,
+}
+
+/// Impl Transition
+impl Transition {
+    /// PRIVATE
+    /// Generate a new Transition settled at black
+    const fn new() -> Self {
+        let black = Rgb {
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+        };
+        Transition {
+            from: black,
+            target: black,
+            shown: black,
+            step: u32::MAX,
+        }
+    }
+
+    /// PRIVATE
+    /// Show levels at once, ending any glide
+    const fn snap(&mut self, levels: Rgb) {
+        self.from = levels;
+        self.target = levels;
+        self.shown = levels;
+        self.step = u32::MAX;
+    }
+
+    /// PRIVATE
+    /// The levels of the next frame of a glide to target lasting frames frames (0 or 1 for none). A target which moves
+    /// by half a BRIGHTNESS_STEPS bin or more restarts the glide from the levels shown, so a change is reached after
+    /// exactly frames frames however far it is. A target moving in smaller steps (an animation, or a fade) keeps the
+    /// glide running and just drags its end along.
+    const fn next(&mut self, target: Rgb, frames: u32) -> Rgb {
+        if frames <= 1 {
+            self.snap(target);
+            return target;
+        }
+        let threshold = 0.5 / ColorControler::BRIGHTNESS_STEPS;
+        if (target.r - self.target.r).abs() >= threshold
+            || (target.g - self.target.g).abs() >= threshold
+            || (target.b - self.target.b).abs() >= threshold
+        {
+            self.from = self.shown;
+            self.step = 0;
+        }
+        self.target = target;
+        self.step = self.step.saturating_add(1);
+        self.shown = if self.step >= frames {
+            target
+        } else {
+            let t = self.step as f32 / frames as f32;
+            Rgb {
+                r: self.from.r + (target.r - self.from.r) * t,
+                g: self.from.g + (target.g - self.from.g) * t,
+                b: self.from.b + (target.b - self.from.b) * t,
+            }
+        };
+        self.shown
+    }
+}
 
 /// ColorControler struct declaration. Note, all fields are private - use the impl methods for controlling these parameters.
 ///
@@ -33,16 +319,94 @@ pub const STARTING_HSV: Hsv = Hsv {
 /// 5. blue_pin: instance to the blue RGB pin connection point on the MB2 (see main.rs types)
 /// 6. timer: PWM timer used to toggle the states of the RGB pin voltages
 /// 7. remaining_frames: record of the frames left to render for the current base_color
+/// 8. effects: stack of animated effects composed onto base_color at the start of every frame
+/// 9. frame_count: number of complete frames rendered (wrapping), used as the time base of the effects
+/// 10. polarity: LED wiring, determines whether a pin is driven low or high to turn its channel on
+/// 11. value_scale: factor [0,1] applied to the rendered Value (e.g. by the ambient light mode) without changing base_color
+/// 12. fade: in-progress transition of base_color towards a target color, stepped once per frame
+/// 13. min_on_levels: per-channel (R, G, B) shortest on-pulse (fraction of a frame) the LED can show, below which that
+///     channel uses PFM
+/// 14. pfm_accumulators: per-channel (R, G, B) brightness owed by PFM, see pfm_level()
+/// 15. locked_hue: while Some, the exact hue base_color keeps no matter what is written or faded to (see set_hue_lock)
+/// 16. primaries: the LED's calibrated primary hues the rendered hue is warped onto, None for a spec LED
+/// 17. balance: per-channel (R, G, B) factor [0,1] dimming the brighter dies so equal levels give equal light
+/// 18. drive_override: while Some, the exact RGB levels rendered instead of base_color (see set_drive_override)
+/// 19. output_level: master factor [0,1] applied to the rendered Value, 0 while the soft power is off
+/// 20. output_ramp: the output_level being faded to and the change per frame, None when output_level is settled
+/// 21. paused: whether the PWM timer is stopped with the pins frozen for a measurement (see pause)
+/// 22. hue_rate_cap: the most turns of the color wheel per second any effect may carry the hue (see hue_rate.rs)
+/// 23. white_point: per-channel (R, G, B) tint [0,1] of the target white, applied on top of the balance (see
+///     set_white_point_kelvin)
+/// 24. posterize: while Some, the number of levels every channel is snapped to for a banded look (see posterize())
+/// 25. value_floor: the policy for the bottom of the Value axis (see value_floor.rs)
+/// 26. source: the code path behind the latest change of base_color, a running fade keeping the one that started it
+///     (see color_watch.rs)
+/// 27. gamma: exponent of the curve every channel's duty cycle is shaped by, 1 for linear (see led_gamma.rs)
+/// 28. transition_frames: number of frames a change of the rendered color is smoothed over, 0 (or 1) for an instant
+///     change (see set_transition_frames)
+/// 29. transition: the linear glide of the R/G/B levels (before PFM and rounding) towards the latest color
+///
+/// With the `ppi-pwm` feature, fields 2-7 and 10 are replaced by:
+///
+/// 2. timer: the raw TIMER2 peripheral, free running with CC[3] marking the end of every frame
+/// 3. frame_on: PPI channels 0-2 (CC[3] -> R/G/B on task), disabled for a channel that stays dark for the frame
+/// 4. next_duty: R/G/B on-time in timer ticks for the upcoming frame, loaded into CC[0..3] when that frame starts
+///
+/// With the `hw-pwm` feature, fields 2-7 are replaced by:
+///
+/// 2. pwm: the raw PWM0 peripheral, endlessly playing PWM_SEQUENCE with each frame's values held for
+///    PWM_PERIODS_PER_FRAME periods
 pub struct ColorControler {
     base_color: Hsv,
+    #[cfg(not(any(feature = "ppi-pwm", feature = "hw-pwm")))]
     cur_color: Rgb,
 
+    #[cfg(not(any(feature = "ppi-pwm", feature = "hw-pwm")))]
     red_pin: RedPinType,
+    #[cfg(not(any(feature = "ppi-pwm", feature = "hw-pwm")))]
     green_pin: GreenPinType,
+    #[cfg(not(any(feature = "ppi-pwm", feature = "hw-pwm")))]
     blue_pin: BluePinType,
 
+    #[cfg(not(any(feature = "ppi-pwm", feature = "hw-pwm")))]
     timer: ColorTimer,
+    #[cfg(not(any(feature = "ppi-pwm", feature = "hw-pwm")))]
     remaining_frames: u32,
+
+    #[cfg(feature = "ppi-pwm")]
+    timer: TIMER2,
+    #[cfg(feature = "ppi-pwm")]
+    frame_on: (ppi::Ppi0, ppi::Ppi1, ppi::Ppi2),
+    #[cfg(feature = "ppi-pwm")]
+    next_duty: [u32; 3],
+
+    #[cfg(feature = "hw-pwm")]
+    pwm: PWM0,
+
+    effects: EffectStack,
+    frame_count: u32,
+
+    #[cfg(not(feature = "ppi-pwm"))]
+    polarity: Polarity,
+    value_scale: f32,
+    fade: Option<Fade>,
+    min_on_levels: [f32; 3],
+    pfm_accumulators: [f32; 3],
+    locked_hue: Option<f32>,
+    primaries: Option<PrimaryCalibration>,
+    balance: [f32; 3],
+    drive_override: Option<Rgb>,
+    output_level: f32,
+    output_ramp: Option<(f32, f32)>,
+    paused: bool,
+    hue_rate_cap: f32,
+    white_point: [f32; 3],
+    posterize: Option<u8>,
+    value_floor: ValueFloor,
+    source: ColorSource,
+    gamma: f32,
+    transition_frames: u32,
+    transition: Transition,
 }
 
 /// Impl ColorControler
@@ -52,23 +416,49 @@ pub struct ColorControler {
 impl ColorControler {
     const STEPS_PER_FRAME: u32 = 100; // 100 steps at 100us means takes 10ms to make a color
     const DURATION_PER_STEP_US: u32 = 100; // 100 us PWM update rate
-    const TICKS_PER_US: u32 = ColorTimer::TICKS_PER_SECOND / 1000 / 1000; // should be 1
+    #[cfg(not(feature = "hw-pwm"))]
+    const TICKS_PER_US: u32 = TIMER_TICKS_PER_US; // TIMER2 runs at 1MHz, see timers.rs
     const BRIGHTNESS_STEPS: f32 = 100.0; // Limit each RGB value to 100 bins
+    pub const FRAME_DURATION_US: u32 =
+        ColorControler::STEPS_PER_FRAME * ColorControler::DURATION_PER_STEP_US; // 10ms per complete color frame
+    #[cfg(feature = "ppi-pwm")]
+    const FRAME_TICKS: u32 = ColorControler::TICKS_PER_US * ColorControler::FRAME_DURATION_US; // CC[3] frame length
+    #[cfg(feature = "ppi-pwm")]
+    const NEVER: u32 = u32::MAX; // compare value never reached, since CC[3] clears the timer first
+    #[cfg(feature = "hw-pwm")]
+    const PWM_TICKS_PER_US: u32 = 16; // PWM0 at DIV_1 counts the 16MHz PWM clock
+    #[cfg(feature = "hw-pwm")]
+    const PWM_PERIOD_TICKS: u16 = 1_000; // COUNTERTOP: a 16kHz hardware PWM with 1000 duty steps per period
+    #[cfg(not(feature = "hw-pwm"))]
+    pub const DUTY_STEP_US: u32 = ColorControler::DURATION_PER_STEP_US; // on-time of one BRIGHTNESS_STEPS duty step
+    #[cfg(feature = "hw-pwm")]
+    pub const DUTY_STEP_US: u32 =
+        ColorControler::FRAME_DURATION_US / ColorControler::PWM_PERIOD_TICKS as u32; // on-time of one PWM0 duty step
+    #[cfg(feature = "hw-pwm")]
+    const PWM_PERIODS_PER_FRAME: u32 = ColorControler::PWM_TICKS_PER_US
+        * ColorControler::FRAME_DURATION_US
+        / ColorControler::PWM_PERIOD_TICKS as u32; // each frame's duty cycles last 160 periods
+    #[cfg(feature = "hw-pwm")]
+    const POLARITY_FALLING: u16 = 0x8000; // compare value bit 15: the channel starts the period high
 
     /// Generate a new ColorControler struct. Requires the following parameters:
     /// 1. color: a starting Hsv color
     /// 2. timer: a TIMER peripheral from the MB2
-    /// 3. red_pin: a pin on the MB2 which connects to the red LED
-    /// 4. green_pin: a pin on the MB2 which connects to the green LED
-    /// 5. blue_pin: a pin on the MB2 which connects to the blue LED
+    /// 3. polarity: the LED wiring, which determines whether a pin is driven low or high to turn its channel on
+    /// 4. red_pin: a pin on the MB2 which connects to the red LED
+    /// 5. green_pin: a pin on the MB2 which connects to the green LED
+    /// 6. blue_pin: a pin on the MB2 which connects to the blue LED
+    #[cfg(not(any(feature = "ppi-pwm", feature = "hw-pwm")))]
     pub fn new(
         color: Hsv,
         mut timer: ColorTimer,
+        polarity: Polarity,
         red_pin: RedPinType,
         green_pin: GreenPinType,
         blue_pin: BluePinType,
     ) -> Self {
-        ColorControler::clamp(&mut color.clone());
+        // clamp the color that is actually stored (not a throwaway copy) so an out-of-range Hsv never reaches to_rgb()
+        let color = ColorControler::stored_hsv(color, None);
         timer.enable_interrupt();
         timer.reset_event();
 
@@ -83,144 +473,1407 @@ impl ColorControler {
             timer,
 
             remaining_frames: ColorControler::STEPS_PER_FRAME,
+
+            effects: EffectStack::new(),
+            frame_count: 0,
+
+            polarity,
+            value_scale: 1.0,
+            fade: None,
+            min_on_levels: [0.0; 3],
+            pfm_accumulators: [0.0; 3],
+            locked_hue: None,
+            primaries: None,
+            balance: [1.0; 3],
+            drive_override: None,
+            output_level: 1.0,
+            output_ramp: None,
+            paused: false,
+            hue_rate_cap: DEFAULT_HUE_RATE_CAP,
+            white_point: [1.0; 3],
+            posterize: None,
+            value_floor: ValueFloor::Off,
+            source: ColorSource::Rtt,
+            gamma: DEFAULT_LED_GAMMA,
+            transition_frames: 0,
+            transition: Transition::new(),
+        }
+    }
+
+    /// Generate a new hardware driven ColorControler (`ppi-pwm` feature). Takes the same parameters as above, plus:
+    /// 7. gpiote: the GPIOTE peripheral, whose channels 2, 3, and 4 become the R, G, and B pin tasks
+    /// 8. ppi: the PPI peripheral, of which channels 0-5 are used
+    ///
+    /// PPI channels 0-2 turn R/G/B on when TIMER2 reaches CC[3] (which also clears the timer, starting the next frame)
+    /// and PPI channels 3-5 turn them back off at CC[0], CC[1], and CC[2]. The TIMER2 interrupt is only enabled for CC[3].
+    #[cfg(feature = "ppi-pwm")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        color: Hsv,
+        timer: ColorTimer,
+        polarity: Polarity,
+        red_pin: RedPinType,
+        green_pin: GreenPinType,
+        blue_pin: BluePinType,
+        gpiote: &Gpiote,
+        ppi: ppi::Parts,
+    ) -> Self {
+        let color = ColorControler::stored_hsv(color, None);
+
+        // hand each pin to a GPIOTE task channel, starting in the off state
+        let (red, green, blue) = (gpiote.channel2(), gpiote.channel3(), gpiote.channel4());
+        let outputs = (
+            red.output_pin(red_pin.degrade()),
+            green.output_pin(green_pin.degrade()),
+            blue.output_pin(blue_pin.degrade()),
+        );
+        if polarity.pin_level(false) {
+            outputs.0.init_high();
+            outputs.1.init_high();
+            outputs.2.init_high();
+        } else {
+            outputs.0.init_low();
+            outputs.1.init_low();
+            outputs.2.init_low();
+        }
+
+        // free running 1MHz timer (as configured by Timer::new) which clears itself at the end of every frame
+        let timer = timer.free();
+        timer.tasks_stop.write(|w| unsafe { w.bits(1) });
+        timer.tasks_clear.write(|w| unsafe { w.bits(1) });
+        for cc in &timer.cc[0..3] {
+            cc.write(|w| unsafe { w.bits(ColorControler::NEVER) });
+        }
+        timer.cc[3].write(|w| unsafe { w.bits(ColorControler::FRAME_TICKS) });
+        timer.shorts.write(|w| w.compare3_clear().enabled());
+        timer.events_compare[3].reset();
+        timer.intenset.write(|w| w.compare3().set());
+
+        let mut frame_on = (ppi.ppi0, ppi.ppi1, ppi.ppi2);
+        let mut frame_off = (ppi.ppi3, ppi.ppi4, ppi.ppi5);
+        let frame_end = &timer.events_compare[3];
+        ColorControler::route(
+            &red,
+            &mut frame_on.0,
+            frame_end,
+            &mut frame_off.0,
+            &timer.events_compare[0],
+            polarity,
+        );
+        ColorControler::route(
+            &green,
+            &mut frame_on.1,
+            frame_end,
+            &mut frame_off.1,
+            &timer.events_compare[1],
+            polarity,
+        );
+        ColorControler::route(
+            &blue,
+            &mut frame_on.2,
+            frame_end,
+            &mut frame_off.2,
+            &timer.events_compare[2],
+            polarity,
+        );
+        timer.tasks_start.write(|w| unsafe { w.bits(1) });
+
+        ColorControler {
+            base_color: color,
+
+            timer,
+            frame_on,
+            next_duty: [0; 3],
+
+            effects: EffectStack::new(),
+            frame_count: 0,
+
+            value_scale: 1.0,
+            fade: None,
+            min_on_levels: [0.0; 3],
+            pfm_accumulators: [0.0; 3],
+            locked_hue: None,
+            primaries: None,
+            balance: [1.0; 3],
+            drive_override: None,
+            output_level: 1.0,
+            output_ramp: None,
+            paused: false,
+            hue_rate_cap: DEFAULT_HUE_RATE_CAP,
+            white_point: [1.0; 3],
+            posterize: None,
+            value_floor: ValueFloor::Off,
+            source: ColorSource::Rtt,
+            gamma: DEFAULT_LED_GAMMA,
+            transition_frames: 0,
+            transition: Transition::new(),
+        }
+    }
+
+    /// Generate a new hardware PWM driven ColorControler (`hw-pwm` feature). Takes the same parameters as the
+    /// software PWM new(), except for:
+    /// 2. pwm: the PWM0 peripheral, whose channels 0, 1, and 2 become the R, G, and B outputs
+    ///
+    /// The pins keep the off level they were configured with until PWM0 takes them over. PWM0 is started straight away,
+    /// with every channel off until the first render().
+    #[cfg(feature = "hw-pwm")]
+    pub fn new(
+        color: Hsv,
+        pwm: PWM0,
+        polarity: Polarity,
+        red_pin: RedPinType,
+        green_pin: GreenPinType,
+        blue_pin: BluePinType,
+    ) -> Self {
+        let color = ColorControler::stored_hsv(color, None);
+
+        let pins = [
+            red_pin.degrade().psel_bits(),
+            green_pin.degrade().psel_bits(),
+            blue_pin.degrade().psel_bits(),
+        ];
+        for (out, bits) in pwm.psel.out.iter().zip(pins) {
+            out.write(|w| {
+                unsafe { w.bits(bits) };
+                w.connect().connected()
+            });
+        }
+
+        let off = ColorControler::pwm_compare(0, polarity);
+        // SAFETY: PWM0 is not started yet and its interrupt is still masked, so nothing else accesses the sequence
+        unsafe { (&raw mut PWM_SEQUENCE).write_volatile([off, off, off, 0]) };
+        let sequence = (&raw const PWM_SEQUENCE) as u32;
+
+        // a 16MHz up counter wrapping every PWM_PERIOD_TICKS, loading all four compare values each sequence step and
+        // holding them for a whole frame. SEQ[0] and SEQ[1] both play PWM_SEQUENCE and the loop restarts SEQ[0] when
+        // SEQ[1] ends, so the playback never stops and every SEQEND marks the start of a new frame
+        pwm.enable.write(|w| w.enable().enabled());
+        pwm.mode.write(|w| w.updown().up());
+        pwm.prescaler.write(|w| w.prescaler().div_1());
+        pwm.countertop
+            .write(|w| unsafe { w.countertop().bits(ColorControler::PWM_PERIOD_TICKS) });
+        pwm.decoder.write(|w| {
+            w.load().individual();
+            w.mode().refresh_count()
+        });
+        for seq in [&pwm.seq0, &pwm.seq1] {
+            seq.ptr.write(|w| unsafe { w.bits(sequence) });
+            seq.cnt.write(|w| unsafe { w.bits(4) });
+            seq.refresh
+                .write(|w| unsafe { w.bits(ColorControler::PWM_PERIODS_PER_FRAME - 1) });
+            seq.enddelay.write(|w| unsafe { w.bits(0) });
+        }
+        pwm.loop_.write(|w| unsafe { w.cnt().bits(1) });
+        pwm.shorts.write(|w| w.loopsdone_seqstart0().enabled());
+        pwm.intenset.write(|w| w.seqend0().set().seqend1().set());
+        pwm.tasks_seqstart[0].write(|w| unsafe { w.bits(1) });
+
+        ColorControler {
+            base_color: color,
+
+            pwm,
+
+            effects: EffectStack::new(),
+            frame_count: 0,
+
+            polarity,
+            value_scale: 1.0,
+            fade: None,
+            min_on_levels: [0.0; 3],
+            pfm_accumulators: [0.0; 3],
+            locked_hue: None,
+            primaries: None,
+            balance: [1.0; 3],
+            drive_override: None,
+            output_level: 1.0,
+            output_ramp: None,
+            paused: false,
+            hue_rate_cap: DEFAULT_HUE_RATE_CAP,
+            white_point: [1.0; 3],
+            posterize: None,
+            value_floor: ValueFloor::Off,
+            source: ColorSource::Rtt,
+            gamma: DEFAULT_LED_GAMMA,
+            transition_frames: 0,
+            transition: Transition::new(),
+        }
+    }
+
+    /// PRIVATE
+    /// The on-time in PWM0 ticks of a channel at level [0,1], rounded to the nearest of the PWM_PERIOD_TICKS steps
+    #[cfg(feature = "hw-pwm")]
+    const fn pwm_duty(level: f32) -> u16 {
+        (level.clamp(0.0, 1.0) * ColorControler::PWM_PERIOD_TICKS as f32 + 0.5) as u16
+    }
+
+    /// PRIVATE
+    /// The PWM0 compare value lighting an LED channel for on_ticks of every PWM period: the channel starts the period
+    /// on and turns off when the counter reaches on_ticks, so bit 15 makes the period start high (on for a
+    /// CommonCathode LED) or low (on for a CommonAnode LED). 0 keeps the channel off and PWM_PERIOD_TICKS on all period
+    #[cfg(feature = "hw-pwm")]
+    fn pwm_compare(on_ticks: u16, polarity: Polarity) -> u16 {
+        let on_ticks = on_ticks.min(ColorControler::PWM_PERIOD_TICKS);
+        if polarity.pin_level(true) {
+            on_ticks | ColorControler::POLARITY_FALLING
+        } else {
+            on_ticks
+        }
+    }
+
+    /// PRIVATE
+    /// Wire one LED channel's GPIOTE tasks into a pair of PPI channels: on_event turns the LED channel on and off_event
+    /// turns it back off, using the set or clear task according to the LED polarity. The off channel is enabled
+    /// permanently while the on channel is left disabled until render() has a non-zero duty cycle for it.
+    #[cfg(feature = "ppi-pwm")]
+    fn route<On: ConfigurablePpi, Off: ConfigurablePpi>(
+        channel: &GpioteChannel<'_>,
+        on: &mut On,
+        on_event: &EVENTS_COMPARE,
+        off: &mut Off,
+        off_event: &EVENTS_COMPARE,
+        polarity: Polarity,
+    ) {
+        on.set_event_endpoint(on_event);
+        off.set_event_endpoint(off_event);
+        if polarity.pin_level(true) {
+            on.set_task_endpoint(channel.task_set());
+            off.set_task_endpoint(channel.task_clr());
+        } else {
+            on.set_task_endpoint(channel.task_clr());
+            off.set_task_endpoint(channel.task_set());
+        }
+        off.enable();
+    }
+
+    /// PRIVATE
+    /// Enable or disable a PPI channel
+    #[cfg(feature = "ppi-pwm")]
+    fn set_ppi_enabled<C: Ppi>(channel: &mut C, enabled: bool) {
+        if enabled {
+            channel.enable();
+        } else {
+            channel.disable();
         }
     }
 
     /// PRIVATE
     /// Thin wrapper around the f32::clamp method which clamps the value (intende for either an Hsv or Rgb single value)
-    /// to the appropriate range of [0,1].
-    fn _clamp(value: f32) -> f32 {
+    /// to the appropriate range of [0,1]. f32::clamp passes NaN through, so NaN maps to 0 to keep the [0,1] invariant
+    const fn _clamp(value: f32) -> f32 {
+        if value.is_nan() {
+            return 0.0;
+        }
         value.clamp(0.0, 1.0)
     }
 
     /// PRIVATE
     /// Custom round implementation which rounds an f32 to the neareset 1/100th decimal (the 1/100th place rounding is
     /// dictated by the ColorControler::BRIGHTNESS_STEPS parameter)
-    fn round(number: f32) -> f32 {
+    const fn round(number: f32) -> f32 {
+        ColorControler::round_bin(number) as f32 / ColorControler::BRIGHTNESS_STEPS
+    }
+
+    /// PUBLIC
+    /// The color the PWM can actually show for rgb: each channel clamped to [0,1] and rounded to its BRIGHTNESS_STEPS
+    /// bin, before any effects, value_scale, channel balance, or PFM
+    pub const fn quantize(rgb: Rgb) -> Rgb {
+        const fn level(channel: f32) -> f32 {
+            ColorControler::round_bin(channel.clamp(0.0, 1.0)) as f32
+                / ColorControler::BRIGHTNESS_STEPS
+        }
+        Rgb {
+            r: level(rgb.r),
+            g: level(rgb.g),
+            b: level(rgb.b),
+        }
+    }
+
+    /// PRIVATE
+    /// The BRIGHTNESS_STEPS bin round() puts number in, rounding half-up: a tie (e.g. 0.005 at 100 steps) goes to the
+    /// upper bin. The bins are pinned by the compile-time checks below the impl
+    const fn round_bin(number: f32) -> u32 {
         let scaled_number = number * ColorControler::BRIGHTNESS_STEPS;
         let mut integer = scaled_number as u32;
         let remainder = scaled_number - (integer as f32);
-        if remainder > 0.5 {
+        if remainder >= 0.5 {
             integer += 1;
         }
+        integer
+    }
 
-        integer as f32 / ColorControler::BRIGHTNESS_STEPS
+    /// PRIVATE
+    /// Subtracts value from all the cur_color r,g,b components with clamping and rounding. After each
+    /// PWM step, cur_color is updated to subtract the percentage of time spent at the current PWM step
+    /// from each cur_color rgb.
+    #[cfg(not(any(feature = "ppi-pwm", feature = "hw-pwm")))]
+    const fn subtract_rgb(cur_color: &mut Rgb, value: f32) {
+        cur_color.r = ColorControler::round(ColorControler::_clamp(cur_color.r - value));
+        cur_color.g = ColorControler::round(ColorControler::_clamp(cur_color.g - value));
+        cur_color.b = ColorControler::round(ColorControler::_clamp(cur_color.b - value));
     }
 
     /// PRIVATE
-    /// Determines the minimum value in the Rgb struct that is NOT zero. This is value is used to determine the duration of the
-    /// current PWM step. Note that this function should only ever return 0 if all three red, green, and blue values are currently 0.
-    fn find_min_nonzero(rgb: &Rgb) -> f32 {
-        let mut min = 1.1; // a number greater than what any of the r,g,b values can be
+    /// The bookkeeping of one software PWM step, apart from the pins and timer: takes the PwmStep snapshot of
+    /// cur_color (the on-time each channel still owes in the frame), advances cur_color and remaining_frames past it,
+    /// and returns the snapshot with its length in 100usec steps. A dark snapshot lasts the rest of the frame.
+    ///
+    /// Invariants, checked with debug_assert! in debug builds: remaining_frames stays in [0,STEPS_PER_FRAME], every
+    /// cur_color channel stays in [0,1], and a step never lasts longer than the steps remaining in its frame.
+    #[cfg(not(any(feature = "ppi-pwm", feature = "hw-pwm")))]
+    const fn pwm_step(cur_color: &mut Rgb, remaining_frames: &mut u32) -> (PwmStep, u32) {
+        debug_assert!(
+            *remaining_frames <= ColorControler::STEPS_PER_FRAME,
+            "PWM frame overran its steps"
+        );
+        debug_assert!(
+            ColorControler::rgb_in_range(cur_color),
+            "PWM channel on-time outside [0,1]"
+        );
 
-        if rgb.r < min && rgb.r > 0.0 {
-            min = rgb.r;
-        }
-        if rgb.g < min && rgb.g > 0.0 {
-            min = rgb.g;
-        }
-        if rgb.b < min && rgb.b > 0.0 {
-            min = rgb.b;
+        let step = PwmStep::of(cur_color);
+        debug_assert!(
+            step.level >= 0.0 && step.level <= 1.0,
+            "PWM step outside [0,1] of a frame"
+        );
+
+        // number of 100usec steps to wait at these pin states
+        let mut steps = (step.level * ColorControler::STEPS_PER_FRAME as f32) as u32; //round down makes sense bc all this takes time
+
+        // if steps == 0 then all RGB pins should be off and the duration of the frame will
+        // have the LED completely off
+        if steps == 0 {
+            steps = *remaining_frames;
         }
 
-        // if min is > 1 then all rgb values are 0
-        if min > 1.0 { 0.0 } else { min }
+        debug_assert!(
+            steps <= *remaining_frames,
+            "PWM step longer than the rest of its frame"
+        );
+
+        // the snapshot has been fully used, so advance the running state to the "new color" for the next PWM step
+        *remaining_frames -= steps;
+        ColorControler::subtract_rgb(cur_color, step.level);
+        debug_assert!(
+            ColorControler::rgb_in_range(cur_color),
+            "PWM channel on-time outside [0,1] after a step"
+        );
+        (step, steps)
     }
 
     /// PRIVATE
-    /// Subtracts value from all the self.cur_color r,g,b components with clamping and rounding. After each
-    /// PWM step, self.cur_color is updated to subtract the percentage of time spent at the current PWM step
-    /// from each self.cur_color rgb.
-    fn subtract_rgb(&mut self, value: f32) {
-        self.cur_color.r = ColorControler::round(ColorControler::_clamp(self.cur_color.r - value));
-        self.cur_color.g = ColorControler::round(ColorControler::_clamp(self.cur_color.g - value));
-        self.cur_color.b = ColorControler::round(ColorControler::_clamp(self.cur_color.b - value));
+    /// Drive a single RGB pin so that its LED channel is on or off, according to the LED polarity
+    #[cfg(not(any(feature = "ppi-pwm", feature = "hw-pwm")))]
+    fn set_channel<P: OutputPin>(pin: &mut P, on: bool, polarity: Polarity) {
+        pin.set_state(PinState::from(polarity.pin_level(on))).ok();
     }
 
     /// PUBLIC
     /// Convience function for clamping all parameters of the Hsv struct to [0,1] range
-    pub fn clamp(hsv: &mut Hsv) {
+    pub const fn clamp(hsv: &mut Hsv) {
         hsv.h = ColorControler::_clamp(hsv.h);
         hsv.s = ColorControler::_clamp(hsv.s);
         hsv.v = ColorControler::_clamp(hsv.v);
     }
 
     /// PUBLIC
-    /// update self.base_color's hue component. Called by main.rs event loop with the ADC result
-    pub fn update_hue(&mut self, hue: f32) {
-        self.base_color.h = ColorControler::_clamp(hue);
+    /// Returns true if every component of the Hsv struct lies within the [0,1] range
+    pub const fn in_range(hsv: &Hsv) -> bool {
+        const fn unit(x: f32) -> bool {
+            x >= 0.0 && x <= 1.0
+        }
+        unit(hsv.h) && unit(hsv.s) && unit(hsv.v)
+    }
+
+    /// PRIVATE
+    /// Returns true if every channel of the Rgb struct lies within the [0,1] range, for the render() invariant checks
+    const fn rgb_in_range(rgb: &Rgb) -> bool {
+        const fn unit(x: f32) -> bool {
+            x >= 0.0 && x <= 1.0
+        }
+        unit(rgb.r) && unit(rgb.g) && unit(rgb.b)
+    }
+
+    /// PUBLIC
+    /// return the Hsv (Copy) currently stored as self.base_color, i.e. the color the PWM is converging on
+    pub fn current_hsv(&self) -> Hsv {
+        self.base_color
+    }
+
+    /// PUBLIC
+    /// return the code path behind the latest change of the base color (see color_watch.rs)
+    pub fn source(&self) -> ColorSource {
+        self.source
+    }
+
+    /// PUBLIC
+    /// update self.base_color's hue component, cancelling any fade, source being the code path behind the change.
+    /// Called by main.rs event loop with the ADC result. While the hue is locked the hue is left as it is.
+    pub fn update_hue(&mut self, hue: f32, source: ColorSource) {
+        self.fade = None;
+        self.source = source;
+        self.base_color =
+            ColorControler::edited_hsv(self.base_color, PotWrite::Hue(hue), self.locked_hue);
+    }
+
+    /// PUBLIC
+    /// The color set_hsv() stores for color: every component clamped to [0,1], the hue kept at locked_hue while the
+    /// hue is locked
+    pub const fn stored_hsv(color: Hsv, locked_hue: Option<f32>) -> Hsv {
+        let mut stored = color;
+        ColorControler::clamp(&mut stored);
+        if let Some(hue) = locked_hue {
+            stored.h = hue;
+        }
+        stored
+    }
+
+    /// PRIVATE
+    /// The color stored when write edits base (a Hue write is taken as is, not wrapped): only the written component
+    /// changes, and the result goes through stored_hsv(), so while the hue is locked no edit can move the hue
+    const fn edited_hsv(base: Hsv, write: PotWrite, locked_hue: Option<f32>) -> Hsv {
+        let mut color = base;
+        match write {
+            PotWrite::Hue(hue) => color.h = hue,
+            PotWrite::Sat(sat) => color.s = sat,
+            PotWrite::Value(value) => color.v = value,
+            PotWrite::Color(written) => color = written,
+        }
+        ColorControler::stored_hsv(color, locked_hue)
+    }
+
+    /// PUBLIC
+    /// update all three components of self.base_color in one write, cancelling any fade, source being the code path
+    /// behind the change. Used wherever a whole color is applied (RTT match, the follower link, power on, a page-less
+    /// pot), so no caller can leave base_color holding a mix of the old and new color between its writes. While the
+    /// hue is locked the hue is left as it is.
+    pub fn set_hsv(&mut self, color: Hsv, source: ColorSource) {
+        self.fade = None;
+        self.source = source;
+        self.base_color = ColorControler::stored_hsv(color, self.locked_hue);
+    }
+
+    /// PUBLIC
+    /// Lock (or unlock) base_color's current hue. While locked, the hue is restored bit for bit after every write or
+    /// fade step, so neither the setters nor a fade (whose hue is wrapped into [0,1)) can move it. Called by main.rs
+    /// for the shade mode
+    pub fn set_hue_lock(&mut self, locked: bool) {
+        self.locked_hue = locked.then_some(self.base_color.h);
+    }
+
+    /// PUBLIC
+    /// update self.base_color's saturation component, cancelling any fade, source being the code path behind the
+    /// change. Called by main.rs event loop with the ADC result
+    pub fn update_sat(&mut self, sat: f32, source: ColorSource) {
+        self.fade = None;
+        self.source = source;
+        self.base_color =
+            ColorControler::edited_hsv(self.base_color, PotWrite::Sat(sat), self.locked_hue);
     }
 
     /// PUBLIC
-    /// update self.base_color's saturation component. Called by main.rs event loop with the ADC result
-    pub fn update_sat(&mut self, sat: f32) {
-        self.base_color.s = ColorControler::_clamp(sat);
+    /// update self.base_color's value component, cancelling any fade, source being the code path behind the change.
+    /// Called by main.rs event loop with the ADC result
+    pub fn update_value(&mut self, value: f32, source: ColorSource) {
+        self.fade = None;
+        self.source = source;
+        self.base_color =
+            ColorControler::edited_hsv(self.base_color, PotWrite::Value(value), self.locked_hue);
     }
 
     /// PUBLIC
-    /// update self.base_color's value component. Called by main.rs event loop with the ADC result
-    pub fn update_value(&mut self, value: f32) {
-        self.base_color.v = ColorControler::_clamp(value);
+    /// Apply a pot reading resolved (outside the lock) into a PotWrite. A Value write goes through the value floor
+    /// policy, which may keep the pot from reaching off (see value_floor.rs). Called by main.rs event loop with the ADC
+    /// result
+    pub fn apply_pot(&mut self, write: PotWrite) {
+        match write {
+            PotWrite::Hue(hue) => self.update_hue(hue, ColorSource::Pot),
+            PotWrite::Sat(sat) => self.update_sat(sat, ColorSource::Pot),
+            PotWrite::Value(value) => {
+                self.update_value(self.value_floor.pot_value(value), ColorSource::Pot)
+            }
+            PotWrite::Color(color) => self.set_hsv(color, ColorSource::Pot),
+        }
+    }
+
+    /// PUBLIC
+    /// Set the policy for the bottom of the Value axis (see value_floor.rs)
+    pub fn set_value_floor(&mut self, floor: ValueFloor) {
+        self.value_floor = floor;
+    }
+
+    /// PRIVATE
+    /// Compare a setter's input with the value actually stored: Ok if it was stored unchanged, otherwise the clamped
+    /// value that was stored instead
+    fn check_clamped(input: f32, stored: f32) -> Result<(), f32> {
+        if input == stored { Ok(()) } else { Err(stored) }
+    }
+
+    /// PUBLIC
+    /// update_hue() which also reports clamping: returns Err with the stored (clamped) hue if hue was outside [0,1]
+    pub fn try_update_hue(&mut self, hue: f32, source: ColorSource) -> Result<(), f32> {
+        self.update_hue(hue, source);
+        ColorControler::check_clamped(hue, self.base_color.h)
+    }
+
+    /// PUBLIC
+    /// update_sat() which also reports clamping: returns Err with the stored (clamped) saturation if sat was outside [0,1]
+    pub fn try_update_sat(&mut self, sat: f32, source: ColorSource) -> Result<(), f32> {
+        self.update_sat(sat, source);
+        ColorControler::check_clamped(sat, self.base_color.s)
+    }
+
+    /// PUBLIC
+    /// update_value() which also reports clamping: returns Err with the stored (clamped) value if value was outside [0,1]
+    pub fn try_update_value(&mut self, value: f32, source: ColorSource) -> Result<(), f32> {
+        self.update_value(value, source);
+        ColorControler::check_clamped(value, self.base_color.v)
+    }
+
+    /// PUBLIC
+    /// Smoothly transition self.base_color to the (clamped) target color over the given number of frames, the hue
+    /// travelling around the color wheel in direction, source being the code path behind the fade. Any of the update_*
+    /// setters cancels the fade, leaving base_color wherever the fade had reached.
+    pub fn fade_to(
+        &mut self,
+        target: Hsv,
+        frames: u32,
+        direction: HueDirection,
+        source: ColorSource,
+    ) {
+        self.source = source;
+        let mut target = target;
+        ColorControler::clamp(&mut target);
+        self.fade = Some(Fade::new(self.base_color, target, frames, direction));
+    }
+
+    /// PUBLIC
+    /// Set the factor applied to the rendered Value, clamped to [0,1]. base_color itself is unchanged so the user's
+    /// Value setting is restored when the scale returns to 1.0. Called by main.rs event loop for the ambient light mode
+    pub fn set_value_scale(&mut self, scale: f32) {
+        self.value_scale = ColorControler::_clamp(scale);
+    }
+
+    /// PUBLIC
+    /// Set the shortest on-pulse, in usecs, that the LED driver can show on every channel (0 disables PFM). A channel
+    /// whose on-time falls below it is rendered with pulses of exactly this width at a reduced frequency instead (see
+    /// pfm_level())
+    pub fn set_min_on_pulse_us(&mut self, us: u32) {
+        for channel in 0..self.min_on_levels.len() {
+            self.set_channel_min_pulse(channel, us);
+        }
+    }
+
+    /// PUBLIC
+    /// set_min_on_pulse_us() for a single channel (0 = R, 1 = G, 2 = B; others are ignored), for LEDs whose dies
+    /// need different minimum pulses, e.g. a blue die that won't light below a longer pulse than the red one
+    pub fn set_channel_min_pulse(&mut self, channel: usize, us: u32) {
+        if let Some(min_on_level) = self.min_on_levels.get_mut(channel) {
+            *min_on_level =
+                ColorControler::_clamp(us as f32 / ColorControler::FRAME_DURATION_US as f32);
+            self.pfm_accumulators[channel] = 0.0;
+        }
+    }
+
+    /// PUBLIC
+    /// Calibrate for an LED with off-spec primaries: the rendered hue is warped so that red, green, and blue land on the
+    /// given hues (see hue_warp.rs). Returns false, leaving the calibration unchanged, unless the hues go red -> green
+    /// -> blue around the color wheel
+    pub fn set_primary_calibration(&mut self, r_hue: f32, g_hue: f32, b_hue: f32) -> bool {
+        match PrimaryCalibration::new(r_hue, g_hue, b_hue) {
+            Some(calibration) => {
+                self.primaries = Some(calibration);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// PUBLIC
+    /// Go back to rendering hues unwarped, for an LED with spec primaries
+    pub fn clear_primary_calibration(&mut self) {
+        self.primaries = None;
+    }
+
+    /// PUBLIC
+    /// Set the balance factor (clamped to [0,1]) of a single channel (0 = R, 1 = G, 2 = B; others are ignored), by
+    /// which that channel's level is scaled on every frame. 1 for every channel renders unbalanced
+    pub fn set_channel_balance(&mut self, channel: usize, scale: f32) {
+        if let Some(balance) = self.balance.get_mut(channel) {
+            *balance = ColorControler::_clamp(scale);
+        }
+    }
+
+    /// PUBLIC
+    /// Make the LED's white match a blackbody at kelvin (clamped to [MIN_KELVIN,MAX_KELVIN], see kelvin.rs) as closely
+    /// as its gamut allows: base_color becomes a white (saturation 0, hue and Value kept) and every channel is scaled
+    /// by the blackbody tint on top of its balance, so a calibrated LED renders the temperature rather than its raw
+    /// dies, source being the code path behind the change. Returns the Kelvin applied
+    pub fn set_white_point_kelvin(&mut self, kelvin: f32, source: ColorSource) -> f32 {
+        let kelvin = clamp_kelvin(kelvin);
+        let tint = kelvin_to_rgb(kelvin);
+        self.white_point = [tint.r, tint.g, tint.b];
+        self.update_sat(0.0, source);
+        kelvin
+    }
+
+    /// PUBLIC
+    /// Drop the white point tint set by set_white_point_kelvin, rendering the LED's native (balanced) white again
+    pub fn clear_white_point(&mut self) {
+        self.white_point = [1.0; 3];
+    }
+
+    /// PUBLIC
+    /// Posterize the rendered color: snap every channel to levels evenly spaced levels (at least 2) for a coarse,
+    /// banded look, or render smoothly again (None). The bands are taken before the balance, white point, and PFM, so
+    /// a balanced LED shows them balanced
+    pub fn set_posterize(&mut self, levels: Option<u8>) {
+        self.posterize = levels.map(|levels| levels.max(2));
+    }
+
+    /// PUBLIC
+    /// Render exactly the given RGB levels (e.g. for measuring the LED) until called again with None. The levels skip
+    /// the effects, value_scale, primaries, balance, and PFM, and any fade is paused meanwhile
+    pub fn set_drive_override(&mut self, rgb: Option<Rgb>) {
+        self.drive_override = rgb;
+    }
+
+    /// PUBLIC
+    /// Fade the master output level to target (clamped to [0,1]) over the given number of frames, e.g. 0 to switch
+    /// the LED off. base_color is untouched, so fading back to 1 shows exactly the color from before
+    pub fn fade_output(&mut self, target: f32, frames: u32) {
+        let target = ColorControler::_clamp(target);
+        let step = (target - self.output_level).abs() / frames.max(1) as f32;
+        self.output_ramp = Some((target, step));
+    }
+
+    /// PUBLIC
+    /// Set the most turns of the color wheel per second any effect may carry the hue, clamped to
+    /// [MIN_HUE_RATE_CAP,MAX_HUE_RATE_CAP] (see hue_rate.rs). Returns the cap applied
+    pub fn set_hue_rate_cap(&mut self, cap: f32) -> f32 {
+        self.hue_rate_cap = clamp_hue_rate_cap(cap);
+        self.hue_rate_cap
+    }
+
+    /// PUBLIC
+    /// Set the exponent of the gamma curve the duty cycles are shaped by, clamped to [MIN_LED_GAMMA,MAX_LED_GAMMA]
+    /// (see led_gamma.rs), taking effect at the next frame. Returns the exponent applied
+    pub fn set_gamma(&mut self, gamma: f32) -> f32 {
+        self.gamma = clamp_led_gamma(gamma);
+        self.gamma
+    }
+
+    /// PUBLIC
+    /// Smooth every change of the rendered color over frames frames: the levels glide linearly from those shown to
+    /// the new ones and arrive after exactly frames frames, so a pot turned quickly glides rather than snaps, and a
+    /// change arriving mid-transition starts a new glide from the levels shown. 0 (or 1) changes the color instantly
+    pub fn set_transition_frames(&mut self, frames: u32) {
+        self.transition_frames = frames;
+    }
+
+    /// PRIVATE
+    /// Shape one channel's level [0,1] into its duty cycle by the gamma curve
+    fn apply_gamma(&self, level: f32) -> f32 {
+        gamma_curve(level, self.gamma)
+    }
+
+    /// PUBLIC
+    /// return the hue rate cap in turns of the color wheel per second
+    pub fn hue_rate_cap(&self) -> f32 {
+        self.hue_rate_cap
+    }
+
+    /// PUBLIC
+    /// Add an animated effect on top of the currently running effects. Effects are applied in the order they were
+    /// added. If the effect stack is full, the effect is handed back as the Err value.
+    pub fn push_effect(&mut self, effect: Effect) -> Result<(), Effect> {
+        self.effects.push(effect)
+    }
+
+    /// PUBLIC
+    /// Stop all running effects, returning to the plain base_color
+    pub fn clear_effects(&mut self) {
+        self.effects.clear();
+    }
+
+    /// PUBLIC
+    /// return the number of complete frames started so far (wrapping). A change in this count after render() indicates
+    /// that a new frame, reflecting the latest base_color, has begun.
+    pub fn frames_rendered(&self) -> u32 {
+        self.frame_count
+    }
+
+    /// PUBLIC
+    /// Convert a duration in msecs into a number of complete PWM frames (minimum of 1)
+    pub fn ms_to_frames(ms: u32) -> u32 {
+        (ms.saturating_mul(1000) / ColorControler::FRAME_DURATION_US).max(1)
+    }
+
+    /// PRIVATE
+    /// Start a new frame: steps any fade, then returns the color to render for the whole frame, i.e. self.base_color as
+    /// modified by any running effects, the value_scale, and the channel balance, rounded to the ColorControler::BRIGHTNESS_STEPS resolution
+    /// (left unrounded for the finer `hw-pwm` duty steps)
+    ///
+    /// The returned levels are duty cycles, already shaped by the gamma curve: the curve is applied here, once per
+    /// frame, rather than per PWM step, since render() and subtract_rgb() only count down on-time that has already
+    /// been shaped (level^gamma of a remainder is not the remainder of level^gamma). The ppi-pwm render() uses the
+    /// same levels.
+    fn next_frame_rgb(&mut self) -> Rgb {
+        if let Some(rgb) = self.drive_override {
+            let levels = Rgb {
+                r: ColorControler::_clamp(rgb.r),
+                g: ColorControler::_clamp(rgb.g),
+                b: ColorControler::_clamp(rgb.b),
+            };
+            self.transition.snap(levels);
+            return Rgb {
+                r: ColorControler::round(levels.r),
+                g: ColorControler::round(levels.g),
+                b: ColorControler::round(levels.b),
+            };
+        }
+        if let Some(fade) = &mut self.fade {
+            self.base_color = ColorControler::stored_hsv(fade.step(), self.locked_hue);
+            if fade.is_done() {
+                self.fade = None;
+            }
+        }
+
+        let mut color = self.base_color;
+        color.v = self.value_floor.rendered_value(color.v);
+        let mut color = self
+            .effects
+            .apply(color, self.frame_count, self.hue_rate_cap);
+        if let Some((target, step)) = self.output_ramp {
+            if (target - self.output_level).abs() <= step {
+                self.output_level = target;
+                self.output_ramp = None;
+            } else if target > self.output_level {
+                self.output_level += step;
+            } else {
+                self.output_level -= step;
+            }
+        }
+        color.v *= self.value_scale * self.output_level;
+        if let Some(primaries) = &self.primaries {
+            color.h = primaries.warp_hue(color.h);
+        }
+        self.frame_count = self.frame_count.wrapping_add(1);
+
+        // very low levels fall back to PFM before rounding, so they can go dimmer than one BRIGHTNESS_STEPS step
+        let mut rgb = color.to_rgb();
+        if let Some(levels) = self.posterize {
+            rgb.r = posterize(rgb.r, levels);
+            rgb.g = posterize(rgb.g, levels);
+            rgb.b = posterize(rgb.b, levels);
+        }
+        // the balance, white point, and PFM work on light output, so they come after the perceptual curve
+        rgb.r = self.apply_gamma(rgb.r);
+        rgb.g = self.apply_gamma(rgb.g);
+        rgb.b = self.apply_gamma(rgb.b);
+        let [balance_r, balance_g, balance_b] = self.balance;
+        let [white_r, white_g, white_b] = self.white_point;
+        rgb.r *= balance_r * white_r;
+        rgb.g *= balance_g * white_g;
+        rgb.b *= balance_b * white_b;
+        // the transition glides between the levels actually driven, after every other stage so it also smooths
+        // changes of the balance or white point, and before PFM so a glide to black still ends in PFM's dim levels
+        rgb = self.transition.next(rgb, self.transition_frames);
+        let [acc_r, acc_g, acc_b] = &mut self.pfm_accumulators;
+        let [min_r, min_g, min_b] = self.min_on_levels;
+        rgb.r = pfm_level(rgb.r, min_r, acc_r);
+        rgb.g = pfm_level(rgb.g, min_g, acc_g);
+        rgb.b = pfm_level(rgb.b, min_b, acc_b);
+
+        // PWM0 resolves PWM_PERIOD_TICKS duty steps, so the hardware PWM keeps the levels unrounded (see pwm_duty)
+        #[cfg(not(feature = "hw-pwm"))]
+        {
+            rgb.r = ColorControler::round(rgb.r);
+            rgb.g = ColorControler::round(rgb.g);
+            rgb.b = ColorControler::round(rgb.b);
+        }
+        rgb
     }
 
     /// PUBLIC
     /// Render the RGB color by setting each RGB pin state and set up the new PWM interval by starting the self.timer duration.
     /// This function is called by the TIMER2() interrupt handler in main.rs
+    ///
+    /// self.cur_color is the running state: the on-time each channel still owes in this frame. Each call takes a
+    /// PwmStep snapshot of it (which channels are lit, and for how long) while advancing the running state past the
+    /// step (see pwm_step, which also checks the invariants), then drives the pins and timer from that snapshot only.
+    #[cfg(not(any(feature = "ppi-pwm", feature = "hw-pwm")))]
     pub fn render(&mut self) {
+        // an interrupt already pending when the PWM was paused must not start the timer again
+        if self.paused {
+            self.timer.reset_event();
+            return;
+        }
+
         // if self.remaining_frames == 0, then a total frame has completed so update self.cur_color (the color to be rendered on the
-        // RGB LED) during this frame with the value currently stored in self.base_color.
+        // RGB LED) during this frame with the value currently stored in self.base_color, as modified by any running effects.
         if self.remaining_frames == 0 {
-            self.cur_color = self.base_color.to_rgb();
-            self.cur_color.r = ColorControler::round(self.cur_color.r);
-            self.cur_color.g = ColorControler::round(self.cur_color.g);
-            self.cur_color.b = ColorControler::round(self.cur_color.b);
+            self.cur_color = self.next_frame_rgb();
 
             // reset the frame duration to 10msec
             self.remaining_frames = ColorControler::STEPS_PER_FRAME;
         }
 
-        let rgb = self.cur_color;
-        let min_val = ColorControler::find_min_nonzero(&rgb); //dicates the duration of this PWM step
+        let (step, steps) =
+            ColorControler::pwm_step(&mut self.cur_color, &mut self.remaining_frames);
+
+        // each channel stays on for as long as it has remaining brightness in this frame
+        let [red_on, green_on, blue_on] = step.on;
+        ColorControler::set_channel(&mut self.red_pin, red_on, self.polarity);
+        ColorControler::set_channel(&mut self.green_pin, green_on, self.polarity);
+        ColorControler::set_channel(&mut self.blue_pin, blue_on, self.polarity);
+
+        let duration_us = steps * ColorControler::DURATION_PER_STEP_US;
+        let clock_cycles = ColorControler::TICKS_PER_US * duration_us; //PWM duration in clock cycles
 
-        if rgb.r > 0.0 {
-            self.red_pin.set_low(); //turn on
+        // clock_cycles should never be 0, but this is provided just-in-case: If self.timer is passed 0 then the
+        // timer will never interrupt and the LED is essentially stuck
+        if clock_cycles == 0 {
+            self.timer.start(2);
         } else {
-            self.red_pin.set_high(); // turn off
+            self.timer.start(clock_cycles); //round down makes sense bc all this takes time    
+        }
+    }
+
+    /// PUBLIC
+    /// Restart the PWM timer from the start of a fresh frame of the current color, e.g. after it was stopped for sleep.
+    /// Safe to call at any time and any number of times, and does nothing while paused
+    #[cfg(not(any(feature = "ppi-pwm", feature = "hw-pwm")))]
+    pub fn restart(&mut self) {
+        self.remaining_frames = 0;
+        self.render();
+    }
+
+    /// PUBLIC
+    /// Restart the free running PWM timer from the start of a frame, e.g. after it was stopped for sleep. The PPI
+    /// routing is untouched, so this is safe to call at any time and any number of times, and does nothing while
+    /// paused
+    #[cfg(feature = "ppi-pwm")]
+    pub fn restart(&mut self) {
+        if self.paused {
+            return;
+        }
+        self.timer.tasks_stop.write(|w| unsafe { w.bits(1) });
+        self.timer.tasks_clear.write(|w| unsafe { w.bits(1) });
+        self.timer.tasks_start.write(|w| unsafe { w.bits(1) });
+    }
+
+    /// PUBLIC
+    /// Restart the PWM0 sequence playback from the start of a frame, e.g. after it was stopped for sleep. Safe to call
+    /// at any time and any number of times, and does nothing while paused
+    #[cfg(feature = "hw-pwm")]
+    pub fn restart(&mut self) {
+        if self.paused {
+            return;
+        }
+        self.pwm.tasks_seqstart[0].write(|w| unsafe { w.bits(1) });
+    }
+
+    /// PUBLIC
+    /// Freeze the LED for a measurement (e.g. with a meter on a pin): stop the PWM timer, holding every pin at the
+    /// state it is in right now, until resume(). The color is kept, and the color setters and effects carry on
+    /// updating it meanwhile. Returns false if the PWM was already paused. With the `hw-pwm` feature PWM0 stops at the
+    /// end of the 62.5us period in progress, so the pins hold that period's final state rather than the exact moment's
+    pub fn pause(&mut self) -> bool {
+        if !ColorControler::pause_request(&mut self.paused, true) {
+            return false;
+        }
+        #[cfg(not(any(feature = "ppi-pwm", feature = "hw-pwm")))]
+        {
+            self.timer.task_stop().write(|w| unsafe { w.bits(1) });
+            self.timer.reset_event();
+        }
+        #[cfg(feature = "ppi-pwm")]
+        self.timer.tasks_stop.write(|w| unsafe { w.bits(1) });
+        #[cfg(feature = "hw-pwm")]
+        self.pwm.tasks_stop.write(|w| unsafe { w.bits(1) });
+        true
+    }
+
+    /// PUBLIC
+    /// Undo pause(): the PWM restarts at the start of a fresh frame of the current color rather than in the middle of
+    /// the frame it was frozen in. Returns false if the PWM was not paused
+    pub fn resume(&mut self) -> bool {
+        if !ColorControler::pause_request(&mut self.paused, false) {
+            return false;
+        }
+        self.restart();
+        true
+    }
+
+    /// PRIVATE
+    /// The pause state machine behind pause() and resume(): apply a request to pause (true) or resume (false) to the
+    /// paused flag, returning whether it changed anything (a pause while paused, or a resume while running, does not)
+    const fn pause_request(paused: &mut bool, pause: bool) -> bool {
+        if *paused == pause {
+            return false;
+        }
+        *paused = pause;
+        true
+    }
+
+    /// PUBLIC
+    /// Hardware PWM (`ppi-pwm` feature) frame interrupt. Called by the TIMER2() interrupt handler in main.rs at the start of
+    /// every frame, which the PPI has already begun by turning on every channel with a non-zero duty cycle.
+    ///
+    /// The off compares for this frame (prepared one frame ago) are loaded first: the smallest non-zero on-time is one
+    /// brightness step (100usec), leaving ample time for this write to land before the compare is reached. Then the next
+    /// frame's color is prepared and its on channels enabled, since those fire in hardware before the next interrupt.
+    /// A fully-on channel is turned off for the final tick of the frame so that a following dark frame starts off.
+    #[cfg(feature = "ppi-pwm")]
+    pub fn render(&mut self) {
+        self.timer.events_compare[3].reset();
+        if self.paused {
+            return;
+        }
+
+        for (cc, &duty) in self.timer.cc.iter().zip(&self.next_duty) {
+            let compare = if duty == 0 {
+                ColorControler::NEVER
+            } else {
+                duty.min(ColorControler::FRAME_TICKS - 1)
+            };
+            cc.write(|w| unsafe { w.bits(compare) });
+        }
+
+        let rgb = self.next_frame_rgb();
+        debug_assert!(
+            ColorControler::rgb_in_range(&rgb),
+            "PWM channel duty outside [0,1]"
+        );
+        self.next_duty =
+            [rgb.r, rgb.g, rgb.b].map(|level| (level * ColorControler::FRAME_TICKS as f32) as u32);
+        ColorControler::set_ppi_enabled(&mut self.frame_on.0, self.next_duty[0] > 0);
+        ColorControler::set_ppi_enabled(&mut self.frame_on.1, self.next_duty[1] > 0);
+        ColorControler::set_ppi_enabled(&mut self.frame_on.2, self.next_duty[2] > 0);
+    }
+
+    /// PUBLIC
+    /// Hardware PWM (`hw-pwm` feature) frame interrupt. Called by the PWM0() interrupt handler in main.rs whenever a
+    /// sequence ends, i.e. at the start of every frame, to write the next frame's compare values into PWM_SEQUENCE.
+    /// The sequence now playing has already read its values, so the new ones are shown from the next frame on, a frame
+    /// later than the software PWM shows them. There is no per-step bookkeeping: PWM0 holds the duty cycles itself.
+    #[cfg(feature = "hw-pwm")]
+    pub fn render(&mut self) {
+        self.pwm.events_seqend[0].reset();
+        self.pwm.events_seqend[1].reset();
+        if self.paused {
+            return;
         }
 
-        if rgb.g > 0.0 {
-            self.green_pin.set_low(); //turn on
+        let rgb = self.next_frame_rgb();
+        debug_assert!(
+            ColorControler::rgb_in_range(&rgb),
+            "PWM channel duty outside [0,1]"
+        );
+        let [r, g, b] = [rgb.r, rgb.g, rgb.b].map(|level| {
+            ColorControler::pwm_compare(ColorControler::pwm_duty(level), self.polarity)
+        });
+        // SAFETY: the sequence is only written here, under the COLOR_CONTROLER lock, and EasyDMA reads it as a whole
+        // at the next sequence start, a full frame away
+        unsafe { (&raw mut PWM_SEQUENCE).write_volatile([r, g, b, 0]) };
+    }
+}
+
+// the rounding behind every PWM level is pinned at compile time: (input, expected bin at 100 BRIGHTNESS_STEPS), ties
+// included, so a change to round_bin() that moves any of them fails the build
+const _: () = {
+    let cases: [(f32, u32); 11] = [
+        (0.0, 0),
+        (0.004, 0),
+        (0.005, 1),
+        (0.014999, 1),
+        (0.015, 2),
+        (0.025, 3),
+        (0.5, 50),
+        (0.994, 99),
+        (0.995, 100),
+        (0.999, 100),
+        (1.0, 100),
+    ];
+    let mut i = 0;
+    while i < cases.len() {
+        assert!(
+            ColorControler::round_bin(cases[i].0) == cases[i].1,
+            "round_bin() put a pinned value in the wrong bin"
+        );
+        i += 1;
+    }
+};
+
+// with the default LED gamma and the default PFM pulse of one duty step, the dimmest levels other features rely on are
+// still shown, in some frames rather than rounded to off in all of them: the NeverOff floor, the minimum glow, and the
+// standby shimmer (a saturated color, so its brightest channel is its Value). Over many frames the pulses average out
+// to the shaped level
+const _: () = {
+    let min_level =
+        DEFAULT_APP_CONFIG.pfm_min_on_us as f32 / ColorControler::FRAME_DURATION_US as f32;
+    let levels = [
+        super::value_floor::MIN_VISIBLE_VALUE,
+        super::value_floor::DEFAULT_MIN_GLOW,
+        super::standby::DEFAULT_STANDBY_BRIGHTNESS,
+    ];
+    let frames = 10_000;
+    let mut i = 0;
+    while i < levels.len() {
+        let shaped = gamma_curve(levels[i], DEFAULT_LED_GAMMA);
+        let mut accumulator = 0.0;
+        let mut shown = 0.0;
+        let mut frame = 0;
+        while frame < frames {
+            let level = pfm_level(shaped, min_level, &mut accumulator);
+            #[cfg(not(feature = "hw-pwm"))]
+            let duty = ColorControler::round(level);
+            #[cfg(feature = "hw-pwm")]
+            let duty =
+                ColorControler::pwm_duty(level) as f32 / ColorControler::PWM_PERIOD_TICKS as f32;
+            shown += duty;
+            frame += 1;
+        }
+        let average = shown / frames as f32;
+        assert!(
+            average > 0.0,
+            "a dim level rounds to off after the default LED gamma"
+        );
+        // a level below the pulse is averaged by PFM, one above it is only rounded to its nearest duty step
+        let tolerance = if shaped < min_level {
+            0.1 * shaped
         } else {
-            self.green_pin.set_high(); //turn off
+            0.5 * min_level + f32::EPSILON
+        };
+        assert!(
+            (average - shaped).abs() <= tolerance,
+            "the PFM pulses of a dim level do not average out to it"
+        );
+        i += 1;
+    }
+};
+
+// a fully desaturated color renders as an exact grey: whatever its hue, all three channels land in the same PWM bin, so
+// greys never show a tint before the per-channel balance and PFM
+const _: () = {
+    let values = [0.0, 0.004, 0.005, 0.1, 0.333, 0.5, 0.75, 0.995, 1.0];
+    let hues = [0.0, 0.1, 0.3, 0.5, 0.7, 0.99];
+    let mut i = 0;
+    while i < values.len() {
+        let mut j = 0;
+        while j < hues.len() {
+            let rgb = Hsv {
+                h: hues[j],
+                s: 0.0,
+                v: values[i],
+            }
+            .to_rgb();
+            let bin = ColorControler::round_bin(values[i]);
+            assert!(
+                ColorControler::round_bin(rgb.r) == bin
+                    && ColorControler::round_bin(rgb.g) == bin
+                    && ColorControler::round_bin(rgb.b) == bin,
+                "a grey rendered with a tint"
+            );
+            j += 1;
         }
+        i += 1;
+    }
+};
+
+// regression guard for the clamp-on-a-clone bug: the color every new() stores, stored_hsv(color, None), pulls every
+// out-of-range component back into [0,1] (NaN to 0), so rendering it gives in-range channels and PWM bins no longer
+// than a frame
+const _: () = {
+    let color = Hsv {
+        h: 2.0,
+        s: -0.5,
+        v: 3.0,
+    };
+    assert!(!ColorControler::in_range(&color));
+    let stored = ColorControler::stored_hsv(color, None);
+    assert!(
+        ColorControler::in_range(&stored),
+        "new() stored a component outside [0,1]"
+    );
+    assert!(stored.h == 1.0 && stored.s == 0.0 && stored.v == 1.0);
+    let rgb = stored.to_rgb();
+    assert!(
+        ColorControler::rgb_in_range(&rgb),
+        "an out-of-range color rendered outside [0,1]"
+    );
+    let bins = [
+        ColorControler::round_bin(rgb.r),
+        ColorControler::round_bin(rgb.g),
+        ColorControler::round_bin(rgb.b),
+    ];
+    let mut i = 0;
+    while i < bins.len() {
+        assert!(
+            bins[i] <= ColorControler::STEPS_PER_FRAME,
+            "an out-of-range color gave a PWM step longer than a frame"
+        );
+        i += 1;
+    }
+
+    let stored = ColorControler::stored_hsv(
+        Hsv {
+            h: f32::NAN,
+            s: 0.5,
+            v: 0.5,
+        },
+        None,
+    );
+    assert!(stored.h == 0.0, "clamp let a NaN component through");
+};
+
+// posterizing snaps every channel to its levels: 2 levels leave only off and full, 3 levels add exactly half
+const _: () = {
+    let inputs = [0.0, 0.1, 0.24, 0.26, 0.49, 0.5, 0.74, 0.76, 0.9, 1.0];
+    let two = [0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0];
+    let three = [0.0, 0.0, 0.0, 0.5, 0.5, 0.5, 0.5, 1.0, 1.0, 1.0];
+    let mut i = 0;
+    while i < inputs.len() {
+        assert!(
+            posterize(inputs[i], 2) == two[i],
+            "2 levels is not off/full"
+        );
+        assert!(
+            posterize(inputs[i], 3) == three[i],
+            "3 levels is not off/half/full"
+        );
+        i += 1;
+    }
+};
 
-        if rgb.b > 0.0 {
-            self.blue_pin.set_low(); //turn on
+// set_hsv() stores the clamped input in one write, and a locked hue survives it bit for bit
+const _: () = {
+    let color = Hsv {
+        h: 0.3,
+        s: 0.6,
+        v: 0.9,
+    };
+    let stored = ColorControler::stored_hsv(color, None);
+    assert!(stored.h == color.h && stored.s == color.s && stored.v == color.v);
+
+    let stored = ColorControler::stored_hsv(
+        Hsv {
+            h: -0.5,
+            s: 1.5,
+            v: 0.25,
+        },
+        None,
+    );
+    assert!(
+        stored.h == 0.0 && stored.s == 1.0 && stored.v == 0.25,
+        "set_hsv stored an unclamped color"
+    );
+
+    let stored = ColorControler::stored_hsv(color, Some(0.7));
+    assert!(
+        stored.h == 0.7 && stored.s == color.s && stored.v == color.v,
+        "set_hsv moved a locked hue"
+    );
+};
+
+// the shade mode's hue lock holds H bit for bit: hundreds of S and V edits (in and out of range, NaN included), with
+// hue and RGB writes mixed in, then every frame of a fade towards another hue (whose hue is wrapped into [0,1)), all
+// leave the locked hue as it was, while S and V still reach what was written and faded to
+const _: () = {
+    let mut color = ColorControler::stored_hsv(
+        Hsv {
+            h: 0.1 + 0.2,
+            s: 0.8,
+            v: 0.6,
+        },
+        None,
+    );
+    let locked = color.h;
+    let locked_hue = Some(locked);
+
+    let mut i = 0;
+    while i < 600 {
+        let x = if i % 97 == 0 {
+            f32::NAN
         } else {
-            self.blue_pin.set_high(); //turn off
+            (i % 37) as f32 * 0.05 - 0.4
+        };
+        let write = match i % 6 {
+            0 | 2 => PotWrite::Sat(x),
+            1 | 3 => PotWrite::Value(x),
+            4 => PotWrite::Hue(x),
+            _ => PotWrite::new(ColorSpace::Rgb, HSVPage::S, color, x),
+        };
+        color = ColorControler::edited_hsv(color, write, locked_hue);
+        assert!(
+            color.h.to_bits() == locked.to_bits(),
+            "an edit moved a locked hue"
+        );
+        if let PotWrite::Sat(_) = write {
+            assert!(color.s == ColorControler::_clamp(x));
+        }
+        if let PotWrite::Value(_) = write {
+            assert!(color.v == ColorControler::_clamp(x));
         }
+        i += 1;
+    }
 
-        // number of 100usec steps to wait at these pin states
-        let mut steps = (min_val * ColorControler::STEPS_PER_FRAME as f32) as u32; //round down makes sense bc all this takes time
+    let target = Hsv {
+        h: 0.75,
+        s: 0.2,
+        v: 0.9,
+    };
+    let mut fade = Fade::new(color, target, 50, HueDirection::Clockwise);
+    while !fade.is_done() {
+        color = ColorControler::stored_hsv(fade.step(), locked_hue);
+        assert!(
+            color.h.to_bits() == locked.to_bits(),
+            "a fade step moved a locked hue"
+        );
+    }
+    assert!(color.s == target.s && color.v == target.v);
+};
 
-        // if steps == 0 then all RGB pins should be off (set_high) and the duration of the frame will
-        // have the LED completely off
-        if steps == 0 {
-            steps = self.remaining_frames;
+// without a transition a frame shows its new levels at once, with one the levels move in equal steps and arrive after
+// exactly frames frames, a change arriving mid-transition glides on from the levels shown (again over frames frames),
+// and a target moving in small steps is followed without restarting the glide
+const _: () = {
+    const fn grey(level: f32) -> Rgb {
+        Rgb {
+            r: level,
+            g: level,
+            b: level,
         }
+    }
 
-        let duration_us = steps * ColorControler::DURATION_PER_STEP_US;
-        let clock_cycles = ColorControler::TICKS_PER_US * duration_us; //PWM duration in clock cycles
+    let mut transition = Transition::new();
+    assert!(transition.next(grey(0.9), 0).r == 0.9);
+    assert!(transition.next(grey(0.2), 1).r == 0.2);
 
-        self.remaining_frames -= steps;
-        self.subtract_rgb(min_val); // indicate the "new color" for the next PWM cycle
+    let mut transition = Transition::new();
+    let mut frame = 1;
+    while frame < 10 {
+        let shown = transition.next(grey(1.0), 10).r;
+        assert!(
+            (shown - frame as f32 / 10.0).abs() < 1e-6,
+            "a transition was not linear"
+        );
+        frame += 1;
+    }
+    assert!(
+        transition.next(grey(1.0), 10).r == 1.0,
+        "a transition did not end after its frames"
+    );
+    assert!(transition.next(grey(1.0), 10).r == 1.0);
 
-        // clock_cycles should never be 0, but this is provided just-in-case: If self.timer is passed 0 then the
-        // timer will never interrupt and the LED is essentially stuck
-        if clock_cycles == 0 {
-            self.timer.start(2);
-        } else {
-            self.timer.start(clock_cycles); //round down makes sense bc all this takes time    
+    // retarget mid-transition: the glide turns around from where it is rather than jumping
+    let mut transition = Transition::new();
+    let mut frame = 0;
+    while frame < 4 {
+        transition.next(grey(1.0), 10);
+        frame += 1;
+    }
+    let mut frame = 1;
+    while frame < 10 {
+        let shown = transition.next(grey(0.0), 10).r;
+        assert!(
+            (shown - 0.4 * (1.0 - frame as f32 / 10.0)).abs() < 1e-6,
+            "a retargeted transition jumped"
+        );
+        frame += 1;
+    }
+    assert!(transition.next(grey(0.0), 10).r == 0.0);
+
+    // a slow animation after the glide has ended is shown as it moves
+    let slow = 0.4 / ColorControler::BRIGHTNESS_STEPS;
+    assert!(transition.next(grey(slow), 10).r == slow);
+    assert!(transition.next(grey(2.0 * slow), 10).r == 2.0 * slow);
+};
+
+// the hardware PWM period divides a frame exactly, so effects keep their 100Hz time base, and its duty steps are finer
+// than the software PWM's BRIGHTNESS_STEPS and reach both fully off and fully on
+#[cfg(feature = "hw-pwm")]
+const _: () = {
+    assert!(
+        ColorControler::PWM_PERIODS_PER_FRAME * ColorControler::PWM_PERIOD_TICKS as u32
+            == ColorControler::PWM_TICKS_PER_US * ColorControler::FRAME_DURATION_US,
+        "a frame is not a whole number of PWM periods"
+    );
+    assert!(ColorControler::PWM_PERIOD_TICKS as f32 > ColorControler::BRIGHTNESS_STEPS);
+    assert!(ColorControler::pwm_duty(0.0) == 0);
+    assert!(ColorControler::pwm_duty(1.0) == ColorControler::PWM_PERIOD_TICKS);
+    assert!(
+        ColorControler::pwm_duty(0.0005) == 1,
+        "a duty step is rounded down"
+    );
+    assert!(ColorControler::pwm_duty(0.5) == ColorControler::PWM_PERIOD_TICKS / 2);
+};
+
+// render()'s step bookkeeping driven through whole frames of many colors (every bin of red against others of green and
+// blue): every invariant holds, a frame takes at most one step per lit level plus one dark step, its steps add up to
+// exactly one frame, and each channel is lit for exactly its rounded level
+#[cfg(not(any(feature = "ppi-pwm", feature = "hw-pwm")))]
+const _: () = {
+    let steps_per_frame = ColorControler::STEPS_PER_FRAME;
+    let mut bin = 0;
+    while bin <= steps_per_frame {
+        let bins = [
+            bin,
+            steps_per_frame - bin,
+            (bin * 37) % (steps_per_frame + 1),
+        ];
+        let mut cur_color = ColorControler::quantize(Rgb {
+            r: bins[0] as f32 / ColorControler::BRIGHTNESS_STEPS,
+            g: bins[1] as f32 / ColorControler::BRIGHTNESS_STEPS,
+            b: bins[2] as f32 / ColorControler::BRIGHTNESS_STEPS,
+        });
+        let mut remaining_frames = steps_per_frame;
+        let mut lit = [0; 3];
+        let mut total = 0;
+        let mut count = 0;
+        while remaining_frames > 0 {
+            let (step, steps) = ColorControler::pwm_step(&mut cur_color, &mut remaining_frames);
+            assert!(
+                remaining_frames <= steps_per_frame && ColorControler::rgb_in_range(&cur_color),
+                "a PWM invariant broke"
+            );
+            let mut channel = 0;
+            while channel < 3 {
+                if step.on[channel] {
+                    lit[channel] += steps;
+                }
+                channel += 1;
+            }
+            total += steps;
+            count += 1;
+            assert!(count <= 4, "a frame took more steps than it has levels");
+        }
+        assert!(total == steps_per_frame, "a frame did not last one frame");
+        let mut channel = 0;
+        while channel < 3 {
+            assert!(
+                lit[channel] == bins[channel],
+                "a channel was lit for the wrong on-time"
+            );
+            channel += 1;
         }
+        bin += 1;
     }
-}
+};
+
+// pause and resume only act once each: a second pause leaves the PWM paused (without stopping it again), and a resume
+// while running does not restart it mid-frame
+const _: () = {
+    let mut paused = false;
+    assert!(
+        !ColorControler::pause_request(&mut paused, false),
+        "resumed a running PWM"
+    );
+    assert!(ColorControler::pause_request(&mut paused, true) && paused);
+    assert!(
+        !ColorControler::pause_request(&mut paused, true) && paused,
+        "paused twice"
+    );
+    assert!(ColorControler::pause_request(&mut paused, false) && !paused);
+    assert!(!ColorControler::pause_request(&mut paused, false));
+};
@@ -0,0 +1,119 @@
+//! color_match.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The color_match module backs the `match <r> <g> <b>` RTT command, used to reproduce a color picked from a design
+//! or a reference image: the 8-bit RGB triple is converted to HSV and applied, and the ColorMatch report compares the
+//! requested color with the one the LED can actually show. The LED only has ColorControler::BRIGHTNESS_STEPS PWM
+//! levels per channel, so the achievable color can be a few 8-bit counts off the requested one.
+//!
+//! The distance is the largest per-channel difference in 8-bit counts, i.e. how far the worst channel is off.
+
+use core::fmt;
+
+use super::color_control::ColorControler;
+use super::hsv_rgb_convert::{Hsv, Rgb};
+
+/// Constants
+pub const MAX_QUANTIZATION_ERROR: u8 = 2; // the PWM bins are 2.55 counts wide, so rounding costs at most 2 counts
+
+/// PUBLIC
+/// The distance between two 8-bit colors: the largest difference between any of their channels
+pub const fn channel_distance(a: [u8; 3], b: [u8; 3]) -> u8 {
+    let mut distance = 0;
+    let mut i = 0;
+    while i < 3 {
+        let difference = a[i].abs_diff(b[i]);
+        if difference > distance {
+            distance = difference;
+        }
+        i += 1;
+    }
+    distance
+}
+
+/// PUBLIC
+/// The 8-bit color the LED actually shows for hsv, at the PWM resolution
+pub const fn achievable(hsv: Hsv) -> [u8; 3] {
+    ColorControler::quantize(hsv.to_rgb()).to_u8()
+}
+
+/// ColorMatch struct declaration: the outcome of a `match` command. Displays as e.g. `#8040c8 -> Hsv { h: 0.7451, s:
+/// 0.6800, v: 0.7843 }, achievable #8040c7, off by 1/255`
+///
+/// 1. requested: the 8-bit color asked for
+/// 2. hsv: the color applied
+/// 3. achievable: the 8-bit color the LED shows for hsv
+/// 4. distance: channel_distance() between requested and achievable
+#[derive(Clone, Copy)]
+pub struct ColorMatch {
+    pub requested: [u8; 3],
+    pub hsv: Hsv,
+    pub achievable: [u8; 3],
+    pub distance: u8,
+}
+
+/// Impl ColorMatch
+impl ColorMatch {
+    /// PUBLIC
+    /// Compare requested with the color hsv actually applied for it (which differs from the requested color when e.g.
+    /// the shade mode locks the hue)
+    pub const fn new(requested: [u8; 3], hsv: Hsv) -> Self {
+        let achievable = achievable(hsv);
+        ColorMatch {
+            requested,
+            hsv,
+            achievable,
+            distance: channel_distance(requested, achievable),
+        }
+    }
+}
+
+impl fmt::Display for ColorMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [r, g, b] = self.requested;
+        let [ar, ag, ab] = self.achievable;
+        write!(
+            f,
+            "#{:02x}{:02x}{:02x} -> Hsv {{ h: {:.4}, s: {:.4}, v: {:.4} }}, achievable #{:02x}{:02x}{:02x}, off by {}/255",
+            r, g, b, self.hsv.h, self.hsv.s, self.hsv.v, ar, ag, ab, self.distance
+        )
+    }
+}
+
+// the reference case 128 64 200 (HSV 0.7451 0.68 0.7843) lands in the PWM bins 50, 25, and 78, so the LED shows
+// 128 64 199, one count off in blue
+const _: () = {
+    let requested = [128, 64, 200];
+    assert!(channel_distance(requested, requested) == 0);
+    assert!(channel_distance([0, 255, 10], [3, 250, 10]) == 5);
+    let color = ColorMatch::new(
+        requested,
+        Hsv {
+            h: 0.745098,
+            s: 0.68,
+            v: 0.784314,
+        },
+    );
+    assert!(
+        color.achievable[0] == 128 && color.achievable[1] == 64 && color.achievable[2] == 199,
+        "128 64 200 is not quantized to 128 64 199"
+    );
+    assert!(color.distance == 1);
+};
+
+// every 8-bit level survives the PWM quantization within MAX_QUANTIZATION_ERROR counts, so a `match` of any color
+// reports a distance of at most MAX_QUANTIZATION_ERROR (outside of the shade mode)
+const _: () = {
+    let mut level = 0;
+    while level <= 255 {
+        let channel = [level as u8, 0, 0];
+        let shown = ColorControler::quantize(Rgb::from_u8(channel)).to_u8();
+        assert!(
+            channel_distance(channel, shown) <= MAX_QUANTIZATION_ERROR,
+            "the PWM quantization moves a level too far"
+        );
+        level += 1;
+    }
+};
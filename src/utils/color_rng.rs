@@ -0,0 +1,71 @@
+//! color_rng.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The color_rng module contains the ColorRng struct, a small xorshift32 pseudo random number generator used by the
+//! random color jump. The generator is seeded from the nRF hardware RNG at boot unless FIXED_RNG_SEED is set, and can
+//! be re-seeded at any time (e.g. over RTT) so the sequence of "random" colors is repeatable for demos, testing, or
+//! keeping several devices in sync.
+
+use super::hsv_rgb_convert::Hsv;
+
+/// Constants
+pub const FIXED_RNG_SEED: Option<u32> = None; // Some(seed) to make every boot produce the same color sequence
+const ZERO_SEED_REPLACEMENT: u32 = 0x9E37_79B9; // xorshift gets stuck at 0, so a 0 seed is swapped for this constant
+const MIN_RANDOM_SAT: f32 = 0.5; // random colors stay reasonably saturated so they don't all look washed out
+
+/// ColorRng struct declaration: Note all fields are private
+///
+/// 1. state: the xorshift32 state, never 0
+pub struct ColorRng {
+    state: u32,
+}
+
+/// Impl ColorRng
+impl ColorRng {
+    /// PUBLIC
+    /// Generate a new ColorRng from the given seed
+    pub const fn new(seed: u32) -> Self {
+        ColorRng {
+            state: if seed == 0 {
+                ZERO_SEED_REPLACEMENT
+            } else {
+                seed
+            },
+        }
+    }
+
+    /// PUBLIC
+    /// Restart the generator from seed. Two generators given the same seed produce identical sequences.
+    pub fn seed_rng(&mut self, seed: u32) {
+        *self = ColorRng::new(seed);
+    }
+
+    /// PUBLIC
+    /// Advance the generator and return the next 32-bit value (Marsaglia xorshift32)
+    pub fn next_u32(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        x
+    }
+
+    /// PUBLIC
+    /// Return the next value uniformly distributed in [0,1) (uses the top 24 bits so every value is exact in an f32)
+    pub fn next_unit(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// PUBLIC
+    /// Return a random color with any hue, a saturation of at least MIN_RANDOM_SAT, and the given value
+    pub fn random_hsv(&mut self, value: f32) -> Hsv {
+        Hsv {
+            h: self.next_unit(),
+            s: MIN_RANDOM_SAT + (1.0 - MIN_RANDOM_SAT) * self.next_unit(),
+            v: value,
+        }
+    }
+}
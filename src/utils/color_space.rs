@@ -0,0 +1,82 @@
+//! color_space.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The color_space module lets the three editing pages edit either the H, S, and V components (the default) or the
+//! R, G, and B channels directly. The ColorControler always stores the color as Hsv: in the RGB space the page's
+//! channel is read from, and written back through, a conversion of that Hsv color.
+
+use super::hsv_display::HSVPage;
+use super::hsv_rgb_convert::Hsv;
+
+/// The components edited on the three pages
+///
+/// 1. Hsv: the H, S, and V pages edit hue, saturation, and value (default)
+/// 2. Rgb: the same three pages edit the red, green, and blue channels
+#[derive(Clone, Copy, PartialEq)]
+pub enum ColorSpace {
+    Hsv,
+    Rgb,
+}
+
+/// Impl ColorSpace
+impl ColorSpace {
+    /// PUBLIC
+    /// Look up a color space by its RTT name (hsv or rgb)
+    pub fn from_name(name: &str) -> Option<ColorSpace> {
+        match name {
+            "hsv" => Some(ColorSpace::Hsv),
+            "rgb" => Some(ColorSpace::Rgb),
+            _ => None,
+        }
+    }
+
+    /// PUBLIC
+    /// return the component of hsv edited on page in this color space
+    pub fn component(&self, page: HSVPage, hsv: &Hsv) -> f32 {
+        match self {
+            ColorSpace::Hsv => page.component(hsv),
+            ColorSpace::Rgb => {
+                let rgb = hsv.to_rgb();
+                match page {
+                    HSVPage::H => rgb.r,
+                    HSVPage::S => rgb.g,
+                    HSVPage::V => rgb.b,
+                }
+            }
+        }
+    }
+
+    /// PUBLIC
+    /// return hsv with the component edited on page in this color space set to value (clamped to [0,1]). In the RGB
+    /// space a result without a defined hue (grey) keeps the hue of hsv, and one without a defined saturation (black)
+    /// keeps its saturation, so turning a channel down to 0 and back up returns to the same color.
+    pub const fn with_component(&self, page: HSVPage, hsv: Hsv, value: f32) -> Hsv {
+        let value = value.clamp(0.0, 1.0);
+        let mut edited = hsv;
+        match self {
+            ColorSpace::Hsv => match page {
+                HSVPage::H => edited.h = value,
+                HSVPage::S => edited.s = value,
+                HSVPage::V => edited.v = value,
+            },
+            ColorSpace::Rgb => {
+                let mut rgb = hsv.to_rgb();
+                match page {
+                    HSVPage::H => rgb.r = value,
+                    HSVPage::S => rgb.g = value,
+                    HSVPage::V => rgb.b = value,
+                }
+                edited = rgb.to_hsv();
+                if edited.s == 0.0 {
+                    edited.h = hsv.h;
+                }
+                if edited.v == 0.0 {
+                    edited.s = hsv.s;
+                }
+            }
+        }
+        edited
+    }
+}
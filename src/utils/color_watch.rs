@@ -0,0 +1,161 @@
+//! color_watch.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The color_watch module is a debugging aid for the modes and effects: every ColorControler setter changing the base
+//! color is tagged with the ColorSource behind it, and with the RTT `watch on` command the main.rs event loop logs
+//! each change of the base color together with that source, e.g. to find what moved the color when nothing should
+//! have. A fade keeps the source that started it for every step.
+//!
+//! The color is compared once per refresh, so several changes within one refresh are logged as one, tagged with the
+//! latest source. Rendering-only changes (the effects, the value scale, the output fades) leave the base color as it
+//! is and are not logged.
+
+use core::fmt;
+
+use super::hsv_rgb_convert::Hsv;
+
+/// The code path behind a change of the base color
+///
+/// 1. Pot: the selected-page pot or an axis pot
+/// 2. Rtt: an RTT command (set, match, random, fade, kelvin, ...)
+/// 3. Link: the color received by a daisy-chain follower (`follower` feature, see uart_link.rs)
+/// 4. Power: the soft power switching back on (see power_toggle.rs)
+/// 5. Inactivity: the fade back to the default color after inactivity (see inactivity.rs)
+/// 6. Slideshow: the slideshow mode's fade to the next preset (see slideshow.rs)
+/// 7. Serial: a command received over the USB serial port (see serial_control.rs)
+#[derive(Clone, Copy, PartialEq)]
+pub enum ColorSource {
+    Pot,
+    Rtt,
+    #[cfg(feature = "follower")]
+    Link,
+    Power,
+    Inactivity,
+    Slideshow,
+    Serial,
+}
+
+/// Impl ColorSource
+impl ColorSource {
+    /// PUBLIC
+    /// Short name of the source, as logged
+    pub const fn name(&self) -> &'static str {
+        match self {
+            ColorSource::Pot => "pot",
+            ColorSource::Rtt => "rtt",
+            #[cfg(feature = "follower")]
+            ColorSource::Link => "link",
+            ColorSource::Power => "power",
+            ColorSource::Inactivity => "inactivity",
+            ColorSource::Slideshow => "slideshow",
+            ColorSource::Serial => "serial",
+        }
+    }
+}
+
+/// ColorChange struct declaration: one logged change of the base color. Displays as
+/// `<source>: h <h> s <s> v <v> (was h <h> s <s> v <v>)`
+///
+/// 1. source: the code path behind the change
+/// 2. from: the base color before the change
+/// 3. to: the base color after the change
+#[derive(Clone, Copy)]
+pub struct ColorChange {
+    pub source: ColorSource,
+    pub from: Hsv,
+    pub to: Hsv,
+}
+
+impl fmt::Display for ColorChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: h {:.3} s {:.3} v {:.3} (was h {:.3} s {:.3} v {:.3})",
+            self.source.name(),
+            self.to.h,
+            self.to.s,
+            self.to.v,
+            self.from.h,
+            self.from.s,
+            self.from.v
+        )
+    }
+}
+
+/// ColorWatch struct declaration: Note all fields are private
+///
+/// 1. enabled: whether changes are logged at all
+/// 2. last: the base color at the previous check, None until the first check after enabling
+pub struct ColorWatch {
+    enabled: bool,
+    last: Option<Hsv>,
+}
+
+/// Impl ColorWatch
+impl ColorWatch {
+    /// PUBLIC
+    /// Generate a new, disabled, ColorWatch
+    pub const fn new() -> Self {
+        ColorWatch {
+            enabled: false,
+            last: None,
+        }
+    }
+
+    /// PUBLIC
+    /// Enable or disable the watch. Enabling starts from the color at the next check, which is not logged
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.last = None;
+    }
+
+    /// PUBLIC
+    /// Compare the base color current, last changed by source, with the color at the previous check. Returns the
+    /// change to log while enabled and the color differs, otherwise None
+    pub const fn check(&mut self, current: Hsv, source: ColorSource) -> Option<ColorChange> {
+        if !self.enabled {
+            return None;
+        }
+        let change = match self.last {
+            Some(last) if last.h != current.h || last.s != current.s || last.v != current.v => {
+                Some(ColorChange {
+                    source,
+                    from: last,
+                    to: current,
+                })
+            }
+            _ => None,
+        };
+        self.last = Some(current);
+        change
+    }
+}
+
+// a disabled watch and the first check after enabling stay quiet, then a change is reported with the tag and the
+// values before and after it, once
+const _: () = {
+    let red = Hsv {
+        h: 0.0,
+        s: 1.0,
+        v: 1.0,
+    };
+    let dim_red = Hsv { v: 0.25, ..red };
+    assert!(ColorWatch::new().check(red, ColorSource::Pot).is_none());
+
+    let mut watch = ColorWatch {
+        enabled: true,
+        last: None,
+    };
+    assert!(watch.check(red, ColorSource::Rtt).is_none());
+    assert!(watch.check(red, ColorSource::Rtt).is_none());
+    match watch.check(dim_red, ColorSource::Pot) {
+        Some(change) => {
+            assert!(matches!(change.source, ColorSource::Pot));
+            assert!(change.from.v == 1.0 && change.to.v == 0.25 && change.to.h == 0.0);
+        }
+        None => panic!("a change was not logged"),
+    }
+    assert!(watch.check(dim_red, ColorSource::Pot).is_none());
+};
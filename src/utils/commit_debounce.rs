@@ -0,0 +1,91 @@
+//! commit_debounce.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The commit_debounce module contains the CommitDebouncer struct which protects the flash endurance (each page only
+//! survives ~10,000 erases) from a burst of setting changes. Every change restarts a stability timer, and a value is
+//! only handed back to be persisted once it has stayed unchanged for the configured delay, so a flurry of edits costs
+//! a single flash write. A change back to the value already persisted cancels the pending write altogether.
+
+/// Constants
+pub const DEFAULT_COMMIT_DELAY_MS: u32 = 2_000; // persist a setting once it has been left alone for 2 seconds
+pub const MAX_COMMIT_DELAY_MS: u32 = 60_000; // longer delays risk losing the setting to a power-off
+
+/// PUBLIC
+/// The commit decision: has the value been left unchanged for at least delay_ms between changed_us and now_us?
+/// Timestamps are compared with wrap-around.
+pub const fn commit_due(now_us: u32, changed_us: u32, delay_ms: u32) -> bool {
+    now_us.wrapping_sub(changed_us) >= delay_ms.saturating_mul(1000)
+}
+
+/// CommitDebouncer struct declaration: Note all fields are private
+///
+/// 1. delay_ms: how long a value must stay unchanged before it is committed
+/// 2. committed: the value last committed (or loaded), which needs no write
+/// 3. pending: the changed value awaiting its commit and the SYSTEM_CLOCK timestamp of its last change, None when
+///    nothing is waiting
+pub struct CommitDebouncer<T: Copy + PartialEq> {
+    delay_ms: u32,
+    committed: T,
+    pending: Option<(T, u32)>,
+}
+
+/// Impl CommitDebouncer
+impl<T: Copy + PartialEq> CommitDebouncer<T> {
+    /// PUBLIC
+    /// Generate a new CommitDebouncer with the default delay, committed holding the value already persisted
+    pub const fn new(committed: T) -> Self {
+        CommitDebouncer {
+            delay_ms: DEFAULT_COMMIT_DELAY_MS,
+            committed,
+            pending: None,
+        }
+    }
+
+    /// PUBLIC
+    /// Set how long a value must stay unchanged before it is committed, clamped to MAX_COMMIT_DELAY_MS. Takes effect
+    /// for any pending value too.
+    pub fn set_delay_ms(&mut self, delay_ms: u32) {
+        self.delay_ms = delay_ms.min(MAX_COMMIT_DELAY_MS);
+    }
+
+    /// PUBLIC
+    /// return how long a value must stay unchanged before it is committed
+    pub fn delay_ms(&self) -> u32 {
+        self.delay_ms
+    }
+
+    /// PUBLIC
+    /// Record the latest value at now_us. A different value restarts the stability timer, while returning to the
+    /// committed value cancels the pending commit.
+    pub fn update(&mut self, value: T, now_us: u32) {
+        if value == self.committed {
+            self.pending = None;
+        } else if self.pending.map(|(pending, _)| pending) != Some(value) {
+            self.pending = Some((value, now_us));
+        }
+    }
+
+    /// PUBLIC
+    /// Returns the pending value at once, without waiting out the delay (e.g. to save it before shutting down), or
+    /// None if there is none. The returned value is from then on the committed one.
+    pub fn flush(&mut self) -> Option<T> {
+        let (value, _) = self.pending.take()?;
+        self.committed = value;
+        Some(value)
+    }
+
+    /// PUBLIC
+    /// Returns the pending value once it has been unchanged for the delay (only once per change), or None. The
+    /// returned value is from then on the committed one.
+    pub fn poll(&mut self, now_us: u32) -> Option<T> {
+        let (value, changed_us) = self.pending?;
+        if !commit_due(now_us, changed_us, self.delay_ms) {
+            return None;
+        }
+        self.committed = value;
+        self.pending = None;
+        Some(value)
+    }
+}
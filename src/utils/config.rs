@@ -0,0 +1,226 @@
+//! config.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The config module contains the Config struct holding the user settings which persist across reboots (see
+//! storage.rs), and its serialization to and from a small fixed-size byte blob. The blob starts with a magic number
+//! and layout version and ends with a CRC-16, so a blank (erased) or corrupt flash page (e.g. a save cut short by a
+//! reset) is recognized rather than loaded, and main.rs falls back to the defaults. The CRC replaced the Fletcher-16
+//! checksum of layout version 1, which misses e.g. a byte erased from 0x00 to 0xFF. A page saved in an older layout
+//! reads as blank rather than corrupt: nothing is wrong with it, so the defaults are used silently until the next save.
+//! Only a newer version, the wrong magic, or a CRC mismatch reads as corrupt.
+//!
+//! Blob layout (CONFIG_SIZE bytes):
+//! 1. bytes 0-3: CONFIG_MAGIC
+//! 2. byte 4: CONFIG_VERSION
+//! 3. bytes 5-22: the H, S, and V input curves, 6 bytes each (tag, then the LUT_POINTS table, zero unless a Lut)
+//! 4. bytes 23-24: CRC-16 (see crc16) of bytes 0-22, little endian
+//! 5. bytes 25-27: zero padding to a whole number of flash words
+
+use super::arbitration::AXIS_COUNT;
+use super::curve::{Curve, LUT_POINTS};
+
+/// Constants
+pub const CONFIG_SIZE: usize = 28; // serialized Config size in bytes, a multiple of the 4 byte flash word
+const CONFIG_MAGIC: [u8; 4] = *b"HSVC"; // marks a flash page holding a Config
+const CONFIG_VERSION: u8 = 2; // bumped whenever the blob layout changes
+const CURVE_SIZE: usize = 1 + LUT_POINTS; // curve tag followed by the LUT table
+const CHECKSUM_OFFSET: usize = 5 + AXIS_COUNT * CURVE_SIZE; // checksum follows the header and the curves
+const CRC16_POLY: u16 = 0x1021; // CRC-16/CCITT-FALSE generator polynomial, x^16 + x^12 + x^5 + 1
+const CRC16_INIT: u16 = 0xFFFF; // CRC-16/CCITT-FALSE initial value
+pub const DEFAULT_CONFIG: Config = Config {
+    curves: [Curve::Linear; AXIS_COUNT],
+};
+
+/// PUBLIC
+/// Fletcher-16 checksum of bytes
+pub fn fletcher16(bytes: &[u8]) -> u16 {
+    let (mut sum1, mut sum2) = (0u16, 0u16);
+    for &byte in bytes {
+        sum1 = (sum1 + byte as u16) % 255;
+        sum2 = (sum2 + sum1) % 255;
+    }
+    (sum2 << 8) | sum1
+}
+
+/// PUBLIC
+/// CRC-16/CCITT-FALSE of bytes (polynomial 0x1021, initial value 0xFFFF, no reflection, no final xor), computed bit
+/// by bit since a config blob is only a few dozen bytes
+pub const fn crc16(bytes: &[u8]) -> u16 {
+    let mut crc = CRC16_INIT;
+    let mut i = 0;
+    while i < bytes.len() {
+        crc ^= (bytes[i] as u16) << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ CRC16_POLY
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        i += 1;
+    }
+    crc
+}
+
+/// PUBLIC
+/// Check the framing of a blob before its contents are decoded: Blank for erased flash or an older layout version,
+/// Corrupt for the wrong magic, a newer version, or a CRC mismatch
+pub const fn check_blob(bytes: &[u8; CONFIG_SIZE]) -> Result<(), ConfigError> {
+    let mut blank = true;
+    let mut i = 0;
+    while i < CONFIG_SIZE {
+        blank &= bytes[i] == 0xFF;
+        i += 1;
+    }
+    if blank {
+        return Err(ConfigError::Blank);
+    }
+
+    let (covered, rest) = bytes.split_at(CHECKSUM_OFFSET);
+    let stored = u16::from_le_bytes([rest[0], rest[1]]);
+    if bytes[0] != CONFIG_MAGIC[0]
+        || bytes[1] != CONFIG_MAGIC[1]
+        || bytes[2] != CONFIG_MAGIC[2]
+        || bytes[3] != CONFIG_MAGIC[3]
+    {
+        return Err(ConfigError::Corrupt);
+    }
+    // an older layout is not migrated, just replaced by the defaults (its checksum isn't ours to check)
+    if bytes[4] < CONFIG_VERSION {
+        return Err(ConfigError::Blank);
+    }
+    if bytes[4] != CONFIG_VERSION || stored != crc16(covered) {
+        return Err(ConfigError::Corrupt);
+    }
+    Ok(())
+}
+
+/// Reason a byte blob could not be loaded as a Config
+///
+/// 1. Blank: the blob is erased flash (all 0xFF) or in an older layout, i.e. no current Config has been saved yet
+/// 2. Corrupt: the blob has the wrong magic, a newer version, or the wrong CRC, or an unknown curve
+#[derive(Clone, Copy, PartialEq)]
+pub enum ConfigError {
+    Blank,
+    Corrupt,
+}
+
+/// Config struct declaration: the persisted user settings
+///
+/// 1. curves: the pot input curve of each axis, indexed by HSVPage
+#[derive(Clone, Copy, PartialEq)]
+pub struct Config {
+    pub curves: [Curve; AXIS_COUNT],
+}
+
+/// Impl Config
+impl Config {
+    /// PUBLIC
+    /// Serialize into the fixed-size blob described in the module docs
+    pub fn to_bytes(self) -> [u8; CONFIG_SIZE] {
+        let mut bytes = [0u8; CONFIG_SIZE];
+        bytes[0..4].copy_from_slice(&CONFIG_MAGIC);
+        bytes[4] = CONFIG_VERSION;
+
+        for (curve, chunk) in self
+            .curves
+            .iter()
+            .zip(bytes[5..CHECKSUM_OFFSET].chunks_exact_mut(CURVE_SIZE))
+        {
+            match curve {
+                Curve::Linear => chunk[0] = 0,
+                Curve::Exponential => chunk[0] = 1,
+                Curve::Lut(table) => {
+                    chunk[0] = 2;
+                    chunk[1..].copy_from_slice(table);
+                }
+            }
+        }
+
+        let checksum = crc16(&bytes[..CHECKSUM_OFFSET]);
+        bytes[CHECKSUM_OFFSET..CHECKSUM_OFFSET + 2].copy_from_slice(&checksum.to_le_bytes());
+        bytes
+    }
+
+    /// PUBLIC
+    /// Deserialize a blob written by to_bytes()
+    pub fn from_bytes(bytes: &[u8; CONFIG_SIZE]) -> Result<Config, ConfigError> {
+        check_blob(bytes)?;
+
+        let mut config = DEFAULT_CONFIG;
+        for (curve, chunk) in config
+            .curves
+            .iter_mut()
+            .zip(bytes[5..CHECKSUM_OFFSET].chunks_exact(CURVE_SIZE))
+        {
+            *curve = match chunk[0] {
+                0 => Curve::Linear,
+                1 => Curve::Exponential,
+                2 => {
+                    let mut table = [0u8; LUT_POINTS];
+                    table.copy_from_slice(&chunk[1..]);
+                    Curve::Lut(table)
+                }
+                _ => return Err(ConfigError::Corrupt),
+            };
+        }
+        Ok(config)
+    }
+}
+
+// the CRC matches the CRC-16/CCITT-FALSE check value, a well-formed blob passes, erased flash reads as blank, and
+// every single bit flipped in the covered bytes or the CRC itself (a partial write) reads as corrupt, except a version
+// flipped down to an older layout, which reads as blank like a genuine older page
+const _: () = {
+    assert!(crc16(b"123456789") == 0x29B1, "not CRC-16/CCITT-FALSE");
+
+    // the DEFAULT_CONFIG blob: header, all-Linear curves (tag and table 0), CRC, padding
+    let mut blob = [0u8; CONFIG_SIZE];
+    blob[0] = CONFIG_MAGIC[0];
+    blob[1] = CONFIG_MAGIC[1];
+    blob[2] = CONFIG_MAGIC[2];
+    blob[3] = CONFIG_MAGIC[3];
+    blob[4] = CONFIG_VERSION;
+    let crc = crc16(blob.split_at(CHECKSUM_OFFSET).0).to_le_bytes();
+    blob[CHECKSUM_OFFSET] = crc[0];
+    blob[CHECKSUM_OFFSET + 1] = crc[1];
+    assert!(check_blob(&blob).is_ok());
+    assert!(matches!(
+        check_blob(&[0xFF; CONFIG_SIZE]),
+        Err(ConfigError::Blank)
+    ));
+
+    let mut byte = 0;
+    while byte < CHECKSUM_OFFSET + 2 {
+        let mut bit = 0;
+        while bit < 8 {
+            let mut flipped = blob;
+            flipped[byte] ^= 1 << bit;
+            let expected = if byte == 4 && flipped[4] < CONFIG_VERSION {
+                ConfigError::Blank
+            } else {
+                ConfigError::Corrupt
+            };
+            assert!(
+                matches!(check_blob(&flipped), Err(error) if error as u8 == expected as u8),
+                "a flipped bit went unnoticed"
+            );
+            bit += 1;
+        }
+        byte += 1;
+    }
+
+    let mut old = blob;
+    old[4] = 1;
+    assert!(
+        matches!(check_blob(&old), Err(ConfigError::Blank)),
+        "a version 1 blob did not fall back to the defaults silently"
+    );
+    let mut newer = blob;
+    newer[4] = CONFIG_VERSION + 1;
+    assert!(matches!(check_blob(&newer), Err(ConfigError::Corrupt)));
+};
@@ -0,0 +1,53 @@
+//! curve.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The curve module contains the Curve enum, the input curve which shapes the averaged pot percentage before it is
+//! mapped onto an H, S, or V axis. Each axis has its own curve (see config.rs), so e.g. Value can get a gentle
+//! exponential feel at the dark end while Hue stays linear.
+
+/// Constants
+pub const LUT_POINTS: usize = 5; // number of points in a custom lookup table curve, evenly spaced over the pot travel
+const EXPONENTIAL_CURVE: [f32; 9] = [
+    0.0, 0.0121, 0.0321, 0.065, 0.1192, 0.2086, 0.3561, 0.5992, 1.0,
+]; // (e^4x - 1) / (e^4 - 1)
+
+/// PUBLIC
+/// Piecewise linear interpolation of x (clamped to [0,1]) through points evenly spaced over [0,1]
+pub fn interpolate(points: &[f32], x: f32) -> f32 {
+    let segments = points.len().saturating_sub(1);
+    if segments == 0 {
+        return points.first().copied().unwrap_or(x);
+    }
+
+    let position = x.clamp(0.0, 1.0) * segments as f32;
+    let index = (position as usize).min(segments - 1);
+    let frac = position - index as f32;
+    points[index] + (points[index + 1] - points[index]) * frac
+}
+
+/// Pot input curve
+///
+/// 1. Linear: the pot percentage is used as-is
+/// 2. Exponential: (e^4x - 1) / (e^4 - 1), giving fine control at the low end of the pot travel
+/// 3. Lut: custom lookup table of LUT_POINTS outputs (0 = 0.0, 255 = 1.0) evenly spaced over the pot travel
+#[derive(Clone, Copy, PartialEq)]
+pub enum Curve {
+    Linear,
+    Exponential,
+    Lut([u8; LUT_POINTS]),
+}
+
+/// Impl Curve
+impl Curve {
+    /// PUBLIC
+    /// Shape the pot percentage x [0,1] by this curve, returning a value in [0,1]
+    pub fn apply(&self, x: f32) -> f32 {
+        match self {
+            Curve::Linear => x.clamp(0.0, 1.0),
+            Curve::Exponential => interpolate(&EXPONENTIAL_CURVE, x),
+            Curve::Lut(table) => interpolate(&table.map(|point| point as f32 / 255.0), x),
+        }
+    }
+}
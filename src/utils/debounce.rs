@@ -0,0 +1,173 @@
+//! debounce.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The debounce module contains the Debouncer<C> struct which protects the A/B button presses against contact bounce.
+//! Its Clock (on the device a TimerClock running freely at 1MHz, see clock.rs) is read on every press (TASKS_CAPTURE
+//! into a CC register). A press is accepted when its capture is at least the cooldown after the capture of the last
+//! accepted press, which is a direct compare of two captures rather than inferring "cooled down" from a stopped
+//! one-shot counter reading 0.
+
+use super::app_config::DEFAULT_APP_CONFIG;
+use super::clock::{Clock, ManualClock};
+use super::timers::TIMER_TICKS_PER_MS;
+
+/// Constants
+pub const DEFAULT_DEBOUNCE_MS: u32 = DEFAULT_APP_CONFIG.debounce_ms; // presses within this long of the last accepted press are bounces
+
+/// PUBLIC
+/// The debounce decision: is a press captured at capture far enough (cooldown_ticks) after the last accepted press,
+/// captured at last_accept (None if no press has been accepted yet)? Captures are compared with wrap-around.
+pub const fn debounce_elapsed(capture: u32, last_accept: Option<u32>, cooldown_ticks: u32) -> bool {
+    match last_accept {
+        Some(last) => capture.wrapping_sub(last) >= cooldown_ticks,
+        None => true,
+    }
+}
+
+/// What the GPIOTE handler does with an A/B button press
+///
+/// 1. Ignore: nothing, the press is a bounce, half of an A+B chord, made while the soft power is off, or made before
+///    the boot finished
+/// 2. Unblank: only re-light the matrix blanked by the power save
+/// 3. TurnPage: move to the previous (A) or next (B) page
+#[derive(Clone, Copy, PartialEq)]
+pub enum PressAction {
+    Ignore,
+    Unblank,
+    TurnPage,
+}
+
+/// PUBLIC
+/// The press decision of the GPIOTE handler. input_ready is whether the boot has finished (see main.rs): until then
+/// every press is ignored, so a press made while the firmware was still initializing can't switch the page before
+/// the first page is even shown. Otherwise a debounced press of one button, while the other is not held and the soft
+/// power is on, re-lights a blanked matrix or else turns the page.
+pub const fn press_action(
+    input_ready: bool,
+    debounced: bool,
+    other_held: bool,
+    powered_off: bool,
+    blanked: bool,
+) -> PressAction {
+    if !(input_ready && debounced) || other_held || powered_off {
+        PressAction::Ignore
+    } else if blanked {
+        PressAction::Unblank
+    } else {
+        PressAction::TurnPage
+    }
+}
+
+// a press during the boot window is ignored whatever else holds, and afterwards only a clean single-button press acts
+const _: () = {
+    assert!(matches!(
+        press_action(false, true, false, false, false),
+        PressAction::Ignore
+    ));
+    assert!(matches!(
+        press_action(false, true, false, false, true),
+        PressAction::Ignore
+    ));
+    assert!(matches!(
+        press_action(true, true, false, false, false),
+        PressAction::TurnPage
+    ));
+    assert!(matches!(
+        press_action(true, true, false, false, true),
+        PressAction::Unblank
+    ));
+    assert!(matches!(
+        press_action(true, false, false, false, false),
+        PressAction::Ignore
+    ));
+    assert!(matches!(
+        press_action(true, true, true, false, false),
+        PressAction::Ignore
+    ));
+    assert!(matches!(
+        press_action(true, true, false, true, false),
+        PressAction::Ignore
+    ));
+};
+
+/// Debouncer<C> struct declaration: Note all fields are private
+///
+/// <C> template contains the Clock read for the press captures
+///
+/// 1. clock: the Clock read on every press, a free-running 1MHz TimerClock on the device
+/// 2. cooldown_ticks: minimum spacing of accepted presses, in TIMER ticks
+/// 3. last_accept: capture of the last accepted press, None before the first press
+pub struct Debouncer<C>
+where
+    C: Clock,
+{
+    clock: C,
+    cooldown_ticks: u32,
+    last_accept: Option<u32>,
+}
+
+/// Impl Debouncer<C>
+impl<C> Debouncer<C>
+where
+    C: Clock,
+{
+    /// PUBLIC
+    /// Generate a new Debouncer<C> with DEFAULT_DEBOUNCE_MS, capturing presses from clock
+    pub const fn new(clock: C) -> Self {
+        Debouncer {
+            clock,
+            cooldown_ticks: DEFAULT_DEBOUNCE_MS * TIMER_TICKS_PER_MS,
+            last_accept: None,
+        }
+    }
+
+    /// PUBLIC
+    /// Change the cooldown
+    pub fn set_cooldown_ms(&mut self, ms: u32) {
+        self.cooldown_ticks = ms.saturating_mul(TIMER_TICKS_PER_MS);
+    }
+
+    /// PUBLIC
+    /// Capture the clock for a button press and return whether the press is accepted (not a bounce). Called by the
+    /// GPIOTE interrupt handler in main.rs
+    pub fn press(&mut self) -> bool {
+        let capture = self.clock.now_ticks();
+        self.accept(capture)
+    }
+
+    /// PRIVATE
+    /// Decide a press captured at capture, remembering it if accepted
+    const fn accept(&mut self, capture: u32) -> bool {
+        let accepted = debounce_elapsed(capture, self.last_accept, self.cooldown_ticks);
+        if accepted {
+            self.last_accept = Some(capture);
+        }
+        accepted
+    }
+}
+
+// driven by a manual clock: the first press is accepted, bounces within the cooldown of the last accepted press are
+// not (however many there are), and the cooldown is measured across the wrap of the TIMER
+const _: () = {
+    let mut debouncer = Debouncer::new(ManualClock::new(u32::MAX - 5 * TIMER_TICKS_PER_MS));
+    let mut presses = [false; 6];
+    let gaps_ms = [0, 10, 20, DEFAULT_DEBOUNCE_MS - 31, 1, 0];
+    let mut i = 0;
+    while i < presses.len() {
+        debouncer.clock.advance_ms(gaps_ms[i]);
+        presses[i] = debouncer.accept(debouncer.clock.now_ticks());
+        i += 1;
+    }
+    assert!(presses[0], "the first press was rejected");
+    assert!(
+        !presses[1] && !presses[2] && !presses[3],
+        "a bounce was accepted"
+    );
+    assert!(
+        presses[4],
+        "a press a whole cooldown after the last accepted one was rejected"
+    );
+    assert!(!presses[5], "a simultaneous second press was accepted");
+};
@@ -0,0 +1,161 @@
+//! demo_reel.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The demo_reel module contains the DemoReel struct, which shows the device off by stepping through the operating
+//! modes in Mode::ALL order, dwelling DEMO_DWELL_MS on each, and looping back to the first after the last. The manual
+//! and calibration modes are left out (they show nothing moving), as is the ambient light mode without a light sensor
+//! in ADC_CHANNELS.
+//!
+//! The reel is started by holding the left-turning button (A, see button_map.rs) alone for LONG_PRESS_MS, or by the
+//! RTT `demo` command. Any manual input (a pot movement, a button press, or an RTT command) stops it, and the main.rs
+//! event loop returns to the mode in use before the reel started.
+
+use super::adc_channels::LIGHT_CHANNEL;
+use super::long_press::LongPress;
+use super::mode::Mode;
+
+/// Constants
+pub const DEMO_DWELL_MS: u32 = 8_000; // each mode is shown for 8 seconds
+pub const DEMO_FIRST_MODE: Mode = demo_next(Mode::Manual); // the reel always starts at the first demo mode
+
+/// PUBLIC
+/// Whether mode is part of the demo reel
+pub const fn in_demo(mode: Mode) -> bool {
+    match mode {
+        Mode::Manual | Mode::Calibration => false,
+        Mode::Ambient => LIGHT_CHANNEL.is_some(),
+        Mode::Rainbow
+        | Mode::Breathe
+        | Mode::Strobe
+        | Mode::Slideshow
+        | Mode::Shade
+        | Mode::Shimmer => true,
+    }
+}
+
+/// PUBLIC
+/// The demo mode following mode in Mode::ALL order, wrapping from the last back to the first
+pub const fn demo_next(mode: Mode) -> Mode {
+    let mut next = mode.next_mode();
+    while !in_demo(next) {
+        next = next.next_mode();
+    }
+    next
+}
+
+/// DemoReel struct declaration: Note all fields are private
+///
+/// 1. press: the long press detection of the gesture starting the reel
+/// 2. restore: the mode in use before the reel started, None while the reel is not running
+/// 3. current: the demo mode being shown
+/// 4. since_us: SYSTEM_CLOCK timestamp at which the current demo mode was entered
+pub struct DemoReel {
+    press: LongPress,
+    restore: Option<Mode>,
+    current: Mode,
+    since_us: u32,
+}
+
+/// Impl DemoReel
+impl DemoReel {
+    /// PUBLIC
+    /// Generate a new, stopped, DemoReel
+    pub const fn new() -> Self {
+        DemoReel {
+            press: LongPress::new(),
+            restore: None,
+            current: DEMO_FIRST_MODE,
+            since_us: 0,
+        }
+    }
+
+    /// PUBLIC
+    /// return whether the reel is running
+    pub const fn is_running(&self) -> bool {
+        self.restore.is_some()
+    }
+
+    /// PUBLIC
+    /// Advance the start gesture to now_us, held being whether the gesture's button alone is held. Returns true once
+    /// when the gesture completes (see long_press.rs)
+    pub const fn poll_gesture(&mut self, held: bool, now_us: u32) -> bool {
+        self.press.poll(held, now_us)
+    }
+
+    /// PUBLIC
+    /// Start the reel at now_us, remembering mode (the one in use) to return to, and return the first demo mode to
+    /// switch to. Restarting a running reel keeps the mode remembered at the first start.
+    pub const fn start(&mut self, mode: Mode, now_us: u32) -> Mode {
+        if self.restore.is_none() {
+            self.restore = Some(mode);
+        }
+        self.current = DEMO_FIRST_MODE;
+        self.since_us = now_us;
+        DEMO_FIRST_MODE
+    }
+
+    /// PUBLIC
+    /// Stop the reel, returning the mode to go back to, or None if the reel was not running
+    pub const fn stop(&mut self) -> Option<Mode> {
+        self.restore.take()
+    }
+
+    /// PUBLIC
+    /// Advance the reel to now_us. Returns the next demo mode to switch to once the current one has been shown for
+    /// DEMO_DWELL_MS, otherwise (or while stopped) None. Timestamps are compared with wrap-around.
+    pub const fn poll(&mut self, now_us: u32) -> Option<Mode> {
+        if self.restore.is_none() || now_us.wrapping_sub(self.since_us) < DEMO_DWELL_MS * 1000 {
+            return None;
+        }
+        self.current = demo_next(self.current);
+        self.since_us = now_us;
+        Some(self.current)
+    }
+}
+
+// the reel shows every demo mode once, in Mode::ALL order, for DEMO_DWELL_MS each, then returns to the first; stopping
+// hands back the mode in use before the start
+const _: () = {
+    let dwell_us = DEMO_DWELL_MS * 1000;
+    let mut reel = DemoReel::new();
+    assert!(reel.poll(dwell_us).is_none(), "a stopped reel advanced");
+    assert!(reel.start(Mode::Strobe, 0) as u8 == DEMO_FIRST_MODE as u8);
+    assert!(
+        reel.poll(dwell_us - 1).is_none(),
+        "a demo mode was cut short"
+    );
+
+    let mut now_us = 0;
+    let mut last = DEMO_FIRST_MODE as usize;
+    let mut shown = 1;
+    loop {
+        now_us += dwell_us;
+        let mode = match reel.poll(now_us) {
+            Some(mode) => mode,
+            None => panic!("the reel stalled"),
+        };
+        if mode as u8 == DEMO_FIRST_MODE as u8 {
+            break;
+        }
+        assert!(mode as usize > last, "the reel left Mode::ALL order");
+        assert!(in_demo(mode));
+        last = mode as usize;
+        shown += 1;
+    }
+
+    let mut expected = 0;
+    let mut i = 0;
+    while i < Mode::ALL.len() {
+        if in_demo(Mode::ALL[i]) {
+            expected += 1;
+        }
+        i += 1;
+    }
+    assert!(shown == expected, "the reel skipped a demo mode");
+    assert!(!in_demo(Mode::Manual) && !in_demo(Mode::Calibration));
+
+    assert!(matches!(reel.stop(), Some(Mode::Strobe)));
+    assert!(!reel.is_running() && reel.stop().is_none());
+};
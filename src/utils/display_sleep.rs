@@ -0,0 +1,72 @@
+//! display_sleep.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The display_sleep module contains the DisplaySleep struct which implements the optional "color on, matrix off"
+//! power save. The 5x5 matrix draws noticeable current, so once the A/B buttons have not been pressed for the
+//! configured timeout the main.rs event loop blanks it (see HSVDisplay::blank) while the RGB LED carries on as normal.
+//! The next button press only re-lights the current page glyph (without rotating the page), and the matrix blanks
+//! again after another timeout.
+
+use super::inactivity::{MAX_INACTIVITY_TIMEOUT_MS, inactivity_elapsed};
+
+/// DisplaySleep struct declaration: Note all fields are private
+///
+/// 1. timeout_ms: time without a button press before blanking, None when the power save is disabled
+/// 2. last_press_us: SYSTEM_CLOCK timestamp of the most recent button press
+/// 3. asleep: whether the matrix has been blanked since the last button press
+pub struct DisplaySleep {
+    timeout_ms: Option<u32>,
+    last_press_us: u32,
+    asleep: bool,
+}
+
+/// Impl DisplaySleep
+impl DisplaySleep {
+    /// PUBLIC
+    /// Generate a new, disabled, DisplaySleep
+    pub const fn new() -> Self {
+        DisplaySleep {
+            timeout_ms: None,
+            last_press_us: 0,
+            asleep: false,
+        }
+    }
+
+    /// PUBLIC
+    /// Enable the power save with the given timeout (clamped to MAX_INACTIVITY_TIMEOUT_MS), or disable it with None.
+    /// The timer restarts from now_us.
+    pub fn set_timeout(&mut self, timeout_ms: Option<u32>, now_us: u32) {
+        self.timeout_ms = timeout_ms.map(|ms| ms.min(MAX_INACTIVITY_TIMEOUT_MS));
+        self.touch(now_us);
+    }
+
+    /// PUBLIC
+    /// return the timeout, None when the power save is disabled
+    pub fn timeout_ms(&self) -> Option<u32> {
+        self.timeout_ms
+    }
+
+    /// PUBLIC
+    /// Record a button press at now_us, restarting the timer. The press itself re-lights the matrix (see the GPIOTE
+    /// handler in main.rs).
+    pub fn touch(&mut self, now_us: u32) {
+        self.last_press_us = now_us;
+        self.asleep = false;
+    }
+
+    /// PUBLIC
+    /// Returns true once the timeout has elapsed without a button press, i.e. when the matrix should be blanked (only
+    /// once per idle period)
+    pub fn poll(&mut self, now_us: u32) -> bool {
+        let Some(timeout_ms) = self.timeout_ms else {
+            return false;
+        };
+        if self.asleep || !inactivity_elapsed(now_us, self.last_press_us, timeout_ms) {
+            return false;
+        }
+        self.asleep = true;
+        true
+    }
+}
@@ -0,0 +1,185 @@
+//! effects.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The effects module contains the Effect enum and the fixed-capacity EffectStack struct. Each Effect is a small
+//! time-varying transform of an Hsv color, and the EffectStack composes several of them by applying each one, in
+//! the order it was pushed, to the ColorControler base_color once per PWM frame. This allows e.g. a breathing
+//! Value modulation to run at the same time as a slow hue drift rather than the two being mutually exclusive.
+//!
+//! A hue drift is never run faster than the hue rate cap (see hue_rate.rs), whatever period it was pushed with.
+
+use super::hsv_rgb_convert::{Hsv, normalize_hue};
+use super::hue_rate::limit_hue_period_frames;
+
+/// Constants
+pub const EFFECT_CAPACITY: usize = 4; // maximum number of concurrently running effects
+
+/// An animated transform of an Hsv color. All periods are expressed in PWM frames (see ColorControler::FRAME_DURATION_US)
+///
+/// 1. Breathe: triangle-wave modulation of the Value, dimming by up to depth (range [0,1]) at the middle of the period
+/// 2. HueDrift: rotates the hue once around the color wheel every period
+/// 3. Strobe: switches the color fully off for the second half of every period
+/// 4. ReferenceWhite: replaces the color with full-Value white, used by the calibration mode
+/// 5. ShadeCycle: two independent triangle-wave oscillators swinging the Saturation and the Value by up to their depths
+///    above and below their set points (clamped to [0,1]), leaving the hue untouched
+#[derive(Clone, Copy)]
+pub enum Effect {
+    Breathe {
+        period_frames: u32,
+        depth: f32,
+    },
+    HueDrift {
+        period_frames: u32,
+    },
+    Strobe {
+        period_frames: u32,
+    },
+    ReferenceWhite,
+    ShadeCycle {
+        sat_period_frames: u32,
+        sat_depth: f32,
+        value_period_frames: u32,
+        value_depth: f32,
+    },
+}
+
+/// Impl Effect
+impl Effect {
+    /// PRIVATE
+    /// Fraction [0,1) of the way through the current period. A period of 0 is treated as 1 frame.
+    fn phase(frame: u32, period_frames: u32) -> f32 {
+        let period = period_frames.max(1);
+        (frame % period) as f32 / period as f32
+    }
+
+    /// PRIVATE
+    /// Triangle wave -1 -> 1 -> -1 across the period, for modulating around a set point
+    fn swing(frame: u32, period_frames: u32) -> f32 {
+        1.0 - 2.0 * (2.0 * Effect::phase(frame, period_frames) - 1.0).abs()
+    }
+
+    /// PUBLIC
+    /// This effect with any hue animation slowed to at most hue_rate_cap turns of the color wheel per second (see
+    /// hue_rate.rs). Effects which leave the hue alone are returned unchanged
+    pub const fn rate_limited(self, hue_rate_cap: f32) -> Effect {
+        match self {
+            Effect::HueDrift { period_frames } => Effect::HueDrift {
+                period_frames: limit_hue_period_frames(period_frames, hue_rate_cap),
+            },
+            effect => effect,
+        }
+    }
+
+    /// PUBLIC
+    /// Apply this effect to the hsv color for the given frame number, returning the modified color
+    pub fn apply(&self, hsv: Hsv, frame: u32) -> Hsv {
+        let mut out = hsv;
+        match *self {
+            Effect::Breathe {
+                period_frames,
+                depth,
+            } => {
+                // triangle wave: 0 -> 1 -> 0 across the period
+                let triangle = 1.0 - (2.0 * Effect::phase(frame, period_frames) - 1.0).abs();
+                out.v = hsv.v * (1.0 - depth.clamp(0.0, 1.0) * triangle);
+            }
+            Effect::HueDrift { period_frames } => {
+                out.h = normalize_hue(hsv.h + Effect::phase(frame, period_frames));
+            }
+            Effect::Strobe { period_frames } => {
+                if Effect::phase(frame, period_frames) >= 0.5 {
+                    out.v = 0.0;
+                }
+            }
+            Effect::ReferenceWhite => {
+                out.s = 0.0;
+                out.v = 1.0;
+            }
+            Effect::ShadeCycle {
+                sat_period_frames,
+                sat_depth,
+                value_period_frames,
+                value_depth,
+            } => {
+                let sat_swing = sat_depth * Effect::swing(frame, sat_period_frames);
+                let value_swing = value_depth * Effect::swing(frame, value_period_frames);
+                out.s = (hsv.s + sat_swing).clamp(0.0, 1.0);
+                out.v = (hsv.v + value_swing).clamp(0.0, 1.0);
+            }
+        }
+        out
+    }
+}
+
+/// EffectStack struct declaration: Note all fields are private
+///
+/// 1. effects: statically allocated slots, filled from index 0 in push order
+/// 2. len: number of occupied slots
+pub struct EffectStack {
+    effects: [Option<Effect>; EFFECT_CAPACITY],
+    len: usize,
+}
+
+/// Impl EffectStack
+///
+/// Provides methods for adding/removing effects and composing them onto a base color
+impl EffectStack {
+    /// PUBLIC
+    /// Generate a new, empty EffectStack
+    pub const fn new() -> Self {
+        EffectStack {
+            effects: [None; EFFECT_CAPACITY],
+            len: 0,
+        }
+    }
+
+    /// PUBLIC
+    /// Add an effect to the top of the stack. If the stack is full the effect is handed back as the Err value.
+    pub fn push(&mut self, effect: Effect) -> Result<(), Effect> {
+        if self.len == EFFECT_CAPACITY {
+            return Err(effect);
+        }
+        self.effects[self.len] = Some(effect);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// PUBLIC
+    /// Remove all effects
+    pub fn clear(&mut self) {
+        self.effects = [None; EFFECT_CAPACITY];
+        self.len = 0;
+    }
+
+    /// PUBLIC
+    /// Compose every effect onto the base color in push order (the first pushed effect is applied first), any hue
+    /// animation limited to hue_rate_cap turns per second
+    pub fn apply(&self, base: Hsv, frame: u32, hue_rate_cap: f32) -> Hsv {
+        self.effects.iter().flatten().fold(base, |hsv, effect| {
+            effect.rate_limited(hue_rate_cap).apply(hsv, frame)
+        })
+    }
+}
+
+// the hue drift is the only effect the hue rate cap slows down: a 50ms drift is stretched to the one second cap while
+// a 10 second one and a fast strobe are left alone
+const _: () = {
+    use super::hue_rate::DEFAULT_HUE_RATE_CAP;
+
+    let fast = Effect::HueDrift { period_frames: 5 }.rate_limited(DEFAULT_HUE_RATE_CAP);
+    assert!(matches!(fast, Effect::HueDrift { period_frames: 100 }));
+    let slow = Effect::HueDrift {
+        period_frames: 1_000,
+    }
+    .rate_limited(DEFAULT_HUE_RATE_CAP);
+    assert!(matches!(
+        slow,
+        Effect::HueDrift {
+            period_frames: 1_000
+        }
+    ));
+    let strobe = Effect::Strobe { period_frames: 2 }.rate_limited(DEFAULT_HUE_RATE_CAP);
+    assert!(matches!(strobe, Effect::Strobe { period_frames: 2 }));
+};
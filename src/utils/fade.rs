@@ -0,0 +1,117 @@
+//! fade.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The fade module contains the Fade struct, a smooth transition of the ColorControler base_color from one Hsv color
+//! to another over a number of PWM frames. Saturation and Value move linearly while the hue follows the chosen
+//! HueDirection around the color wheel: by default the shorter way, so e.g. a fade from magenta to red never sweeps
+//! through green, or always clockwise/counter-clockwise to deliberately sweep through the spectrum.
+
+use super::hsv_rgb_convert::{Hsv, hue_delta, normalize_hue};
+
+/// The way the hue travels around the color wheel during a fade
+///
+/// 1. Shortest: whichever way is shorter (counter-clockwise when both are equal, see hue_delta())
+/// 2. Clockwise: always increasing hue (red -> yellow -> green -> blue), wrapping from 1.0 back to 0.0
+/// 3. CounterClockwise: always decreasing hue (red -> magenta -> blue -> green), wrapping from 0.0 up to 1.0
+#[derive(Clone, Copy, PartialEq)]
+pub enum HueDirection {
+    Shortest,
+    Clockwise,
+    CounterClockwise,
+}
+
+/// Impl HueDirection
+impl HueDirection {
+    /// PUBLIC
+    /// Look up a direction by its RTT name (short, cw, or ccw)
+    pub fn from_name(name: &str) -> Option<HueDirection> {
+        match name {
+            "short" => Some(HueDirection::Shortest),
+            "cw" => Some(HueDirection::Clockwise),
+            "ccw" => Some(HueDirection::CounterClockwise),
+            _ => None,
+        }
+    }
+
+    /// PUBLIC
+    /// The signed hue change, in (-1,1), taking the from hue to the to hue in this direction. Equal hues never turn.
+    pub const fn hue_delta(&self, from: f32, to: f32) -> f32 {
+        let dh = to - from;
+        match self {
+            HueDirection::Shortest => hue_delta(from, to),
+            HueDirection::Clockwise if dh < 0.0 => dh + 1.0,
+            HueDirection::CounterClockwise if dh > 0.0 => dh - 1.0,
+            _ => dh,
+        }
+    }
+}
+
+/// PUBLIC
+/// Interpolate between the from and to colors, t in [0,1] (clamped). The hue travels around the color wheel in the
+/// given direction and is normalized into [0,1).
+pub const fn lerp_hsv(from: Hsv, to: Hsv, t: f32, direction: HueDirection) -> Hsv {
+    let t = t.clamp(0.0, 1.0);
+    let dh = direction.hue_delta(from.h, to.h);
+
+    Hsv {
+        h: normalize_hue(from.h + dh * t),
+        s: from.s + (to.s - from.s) * t,
+        v: from.v + (to.v - from.v) * t,
+    }
+}
+
+/// Fade struct declaration: Note all fields are private
+///
+/// 1. from: the color at the start of the fade
+/// 2. to: the color at the end of the fade
+/// 3. frames: length of the fade in PWM frames (minimum of 1)
+/// 4. elapsed: number of frames stepped so far
+/// 5. direction: the way the hue travels around the color wheel
+pub struct Fade {
+    from: Hsv,
+    to: Hsv,
+    frames: u32,
+    elapsed: u32,
+    direction: HueDirection,
+}
+
+/// Impl Fade
+impl Fade {
+    /// PUBLIC
+    /// Generate a new Fade from one color to another over the given number of frames, the hue travelling in direction
+    pub const fn new(from: Hsv, to: Hsv, frames: u32, direction: HueDirection) -> Self {
+        Fade {
+            from,
+            to,
+            frames: if frames == 0 { 1 } else { frames },
+            elapsed: 0,
+            direction,
+        }
+    }
+
+    /// PUBLIC
+    /// Advance the fade by one frame, returning the color for that frame. The final step returns exactly the to color.
+    pub const fn step(&mut self) -> Hsv {
+        if self.elapsed < self.frames {
+            self.elapsed += 1;
+        }
+        if self.is_done() {
+            self.to
+        } else {
+            lerp_hsv(
+                self.from,
+                self.to,
+                self.elapsed as f32 / self.frames as f32,
+                self.direction,
+            )
+        }
+    }
+
+    /// PUBLIC
+    /// return whether the fade has reached its to color
+    pub const fn is_done(&self) -> bool {
+        self.elapsed >= self.frames
+    }
+}
@@ -0,0 +1,60 @@
+//! fmt_buf.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The fmt_buf module contains FmtBuf, a fixed-capacity string buffer for building text without a heap in no_std.
+//! It implements core::fmt::Write, so it can be filled with write!() like a String, e.g. to format a message before
+//! it is printed over RTT or to keep an owned copy of a line (see HistoryLine in rtt_command.rs).
+
+use core::fmt;
+
+/// FmtBuf<N> struct declaration: Note all fields are private
+///
+/// <N> is the capacity in bytes.
+///
+/// 1. buf: statically allocated text bytes, always valid UTF-8 up to len
+/// 2. len: number of valid bytes in buf
+#[derive(Clone, Copy)]
+pub struct FmtBuf<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+/// Impl FmtBuf<N>
+impl<const N: usize> FmtBuf<N> {
+    /// PUBLIC
+    /// Generate a new, empty FmtBuf
+    pub const fn new() -> Self {
+        FmtBuf {
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    /// PUBLIC
+    /// return the text written so far
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or("")
+    }
+}
+
+/// Impl fmt::Write for FmtBuf<N>
+///
+/// Text past the capacity is truncated at a character boundary (so the buffer always stays valid UTF-8) and reported
+/// as a fmt::Error. Everything that fitted is kept, so a truncated write!() still leaves a usable prefix.
+impl<const N: usize> fmt::Write for FmtBuf<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut len = s.len().min(N - self.len);
+        while !s.is_char_boundary(len) {
+            len -= 1;
+        }
+        self.buf[self.len..self.len + len].copy_from_slice(&s.as_bytes()[..len]);
+        self.len += len;
+        if len == s.len() {
+            Ok(())
+        } else {
+            Err(fmt::Error)
+        }
+    }
+}
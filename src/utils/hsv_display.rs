@@ -5,18 +5,668 @@
 //!
 //! The hsv_display module contains the HSVDisplay<T> struct which is a wrapper around the
 //! microbit::display::nonblocking::Display module and is used to control the MB2 5x5 LED array
-//! display (displays either an H, S, or V depending upon the A/B button presses). HSVDisplay<T>
+//! display (displays either an H, saturation rings, or V depending upon the A/B button presses). HSVDisplay<T>
 //! also maintains state of the HSV display option (as an HSVPage enum) and provides getters to
 //! retrieve the current page (used by main.rs event loop)
+//!
+//! Without the `display` cargo feature (on by default) the matrix is never driven and TIMER0 is left free: HSVDisplay
+//! only tracks the selected page, so page selection with the A/B buttons keeps working with no matrix output.
+//!
+//! Tearing: the nonblocking Display scans the matrix one row at a time (5 rows of 3ms each), reading its frame afresh
+//! at every row switch. show() and the TIMER0 handler never interleave (both run under the DISPLAY lock), but a show()
+//! landing mid-scan would still light the top rows with the old glyph and the bottom rows with the new one for that
+//! scan. render() therefore only stages the new image, and FrameSwap hands it to the Display at the next frame boundary.
+//!
+//! Page changes can optionally be animated (see PageTransition): the glyphs are blended (crossfade) or swept in column
+//! by column (wipe) over TRANSITION_FRAMES display frames, one step per frame boundary.
+//!
+//! With the pages editing the R, G, and B channels instead (see color_space.rs) the pages show R, G, and B letters.
+//!
+//! Instead of the page glyph the matrix can also show a swatch mirroring the LED color: since the matrix can't show
+//! hue, every LED glows at a brightness tracking the Value and pulses at a rate tracking the Saturation.
+//!
+//! Another alternative view is the overview, showing H, S, and V at once as three bar graphs (see axes_overview) while
+//! the A/B buttons keep paging between the axes the pot edits underneath.
+//!
+//! The greyscale looks non-linear (a level of 5 looks much more than half as bright as 9), so the logical levels can
+//! optionally be mapped through the perceptual DISPLAY_GAMMA curve as each image is built for the Display.
+//!
+//! Scan rate: the Display lights one row per 375 tick cycle of TIMER0, so the TIMER0 prescaler sets how often the
+//! whole matrix is refreshed (see set_scan_rate). The default ~67Hz can flicker under a camera or in peripheral
+//! vision. TIMER0 is the only TIMER not at the HAL's 1MHz (see timers.rs), and nothing but the scan depends on its
+//! rate. Faster scans flicker less and keep the same brightness ratios, but take proportionally more TIMER0
+//! interrupts and shorten the TRANSITION_FRAMES page animations. Slower scans free CPU time but flicker visibly.
+//!
+//! The page glyph also shows who owns its axis (see AxisState): it is dimmed while RTT holds the axis against the pot
+//! (see arbitration.rs) and blinks while the pot has to be turned to the axis value before it writes again (a pickup,
+//! see pot_mapping.rs, or an expired hold waiting for the pot to move).
+//!
+//! For a precise readout a number (e.g. the selected axis value as a percentage) can be scrolled across the matrix in a
+//! 3x5 digit font, entering on the right and leaving on the left one column per tick, before the glyph returns.
+//!
+//! The color code view shows the color as a dot pattern a camera app can read off the matrix (see color_code). It is
+//! not a QR code, just the 8-bit R, G, and B bytes (see Rgb::to_u8) laid out one bit per LED:
+//!
+//! 1. LEDs 0-23, counted row by row from the top left: the 24 bits of 0xRRGGBB, most significant first, lit for a 1
+//! 2. LED 24 (bottom right): parity, lit when the 24 data bits hold an even number of ones
+//!
+//! So every code lights an odd number of LEDs: even black shows a dot, and any single misread LED fails the parity.
+//! All lit LEDs are at MAX_GREYSCALE. color_code_bytes is the matching decoder.
+//!
+//! The static glyph bitmaps are checked at compile time to only hold valid greyscale levels (0 to MAX_GREYSCALE).
 
+#[cfg(not(feature = "display"))]
+use core::marker::PhantomData;
+use microbit::hal::timer::Instance;
+#[cfg(feature = "display")]
 use microbit::{
     display::nonblocking::{Display, GreyscaleImage},
-    hal::timer::Instance,
+    pac::{Interrupt, TIMER0, TIMER1, TIMER2, TIMER3, TIMER4, timer0::RegisterBlock},
 };
 
+use super::arbitration::AXIS_COUNT;
+use super::color_space::ColorSpace;
+use super::hsv_rgb_convert::Hsv;
+#[cfg(feature = "display")]
+use super::timers::timer_ticks_per_second;
+
 /// Constants
+#[cfg(feature = "display")]
 pub const LED_SIZE: usize = 5; // MB2 LED is 5x5 grid
+#[cfg(feature = "display")]
 pub type LEDState = [[u8; LED_SIZE]; LED_SIZE]; // convenience typedef
+#[cfg(feature = "display")]
+pub const CALIBRATION_GREY: u8 = 5; // mid-point of the 0-9 greyscale, used for the calibration fill
+#[cfg(feature = "display")]
+pub const FLASH_ON_TICKS: u32 = 3; // each flash of an error code lights the matrix for 3 ticks (300ms)
+#[cfg(feature = "display")]
+pub const FLASH_OFF_TICKS: u32 = 3; // followed by 3 dark ticks (300ms)
+#[cfg(feature = "display")]
+pub const MAX_GREYSCALE: u8 = 9; // brightest level of the matrix greyscale, 0 is off
+#[cfg(feature = "display")]
+pub const TRANSITION_FRAMES: u32 = 10; // an animated page change lasts 10 display frames (150ms)
+#[cfg(feature = "display")]
+pub const SWATCH_SLOW_TICKS: u32 = 30; // a grey swatch pulses once every 30 ticks (3s)
+#[cfg(feature = "display")]
+pub const SWATCH_FAST_TICKS: u32 = 6; // a fully saturated swatch pulses once every 6 ticks (600ms)
+#[cfg(feature = "display")]
+pub const LOCKED_GLYPH_LEVEL: u8 = 3; // brightest LED of the glyph of an axis RTT holds against the pot
+#[cfg(feature = "display")]
+pub const PICKUP_BLINK_TICKS: u32 = 6; // the glyph of an axis awaiting pickup blinks once every 6 ticks (600ms)
+#[cfg(feature = "display")]
+pub const DISPLAY_GAMMA: [u8; MAX_GREYSCALE as usize + 1] = [0, 1, 1, 1, 2, 2, 4, 5, 7, 9]; // logical level -> shown level: 9 * (level / 9)^2.2, but at least 1 so no lit level goes dark
+#[cfg(feature = "display")]
+const SCAN_CYCLE_TICKS: u32 = 375; // the Display lights each row for one fixed 375 tick cycle
+#[cfg(feature = "display")]
+const FASTEST_SCAN_PRESCALER: u32 = 4; // 1MHz ticks, ~533Hz, 8 times the default TIMER0 interrupt load
+#[cfg(feature = "display")]
+const SLOWEST_SCAN_PRESCALER: u32 = 8; // 62.5kHz ticks, ~33Hz, visibly flickering
+#[cfg(feature = "display")]
+pub const DEFAULT_SCAN_PRESCALER: u32 = 7; // the Display's own 125kHz ticks, ~67Hz
+#[cfg(feature = "display")]
+pub const OVERVIEW_COLUMNS: [usize; 3] = [0, 2, 4]; // matrix columns of the H, S, and V overview bars
+#[cfg(feature = "display")]
+pub const DIGIT_WIDTH: usize = 3; // columns of each DIGIT_FONT digit
+#[cfg(feature = "display")]
+pub const DIGIT_PITCH: usize = DIGIT_WIDTH + 1; // a scrolled digit is followed by one blank column
+#[cfg(feature = "display")]
+const DIGIT_FONT: [[u8; LED_SIZE]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111], // 0
+    [0b010, 0b110, 0b010, 0b010, 0b111], // 1
+    [0b111, 0b001, 0b111, 0b100, 0b111], // 2
+    [0b111, 0b001, 0b111, 0b001, 0b111], // 3
+    [0b101, 0b101, 0b111, 0b001, 0b001], // 4
+    [0b111, 0b100, 0b111, 0b001, 0b111], // 5
+    [0b111, 0b100, 0b111, 0b101, 0b111], // 6
+    [0b111, 0b001, 0b010, 0b010, 0b010], // 7
+    [0b111, 0b101, 0b111, 0b101, 0b111], // 8
+    [0b111, 0b101, 0b111, 0b001, 0b111], // 9
+]; // each digit's rows top to bottom, the most significant of the DIGIT_WIDTH bits being the leftmost column
+#[cfg(feature = "display")]
+const GLYPH_H: LEDState = [
+    [9, 0, 0, 0, 9],
+    [9, 0, 0, 0, 9],
+    [9, 9, 9, 9, 9],
+    [9, 0, 0, 0, 9],
+    [9, 0, 0, 0, 9],
+]; // letter H
+#[cfg(feature = "display")]
+const GLYPH_H_BOLD: LEDState = [
+    [9, 9, 0, 9, 9],
+    [9, 9, 0, 9, 9],
+    [9, 9, 9, 9, 9],
+    [9, 9, 0, 9, 9],
+    [9, 9, 0, 9, 9],
+]; // bold letter H
+#[cfg(feature = "display")]
+const GLYPH_V: LEDState = [
+    [9, 0, 0, 0, 9],
+    [9, 0, 0, 0, 9],
+    [9, 0, 0, 0, 9],
+    [0, 9, 0, 9, 0],
+    [0, 0, 9, 0, 0],
+]; // letter V
+#[cfg(feature = "display")]
+const GLYPH_V_BOLD: LEDState = [
+    [9, 9, 0, 9, 9],
+    [9, 9, 0, 9, 9],
+    [9, 9, 0, 9, 9],
+    [0, 9, 9, 9, 0],
+    [0, 0, 9, 0, 0],
+]; // bold letter V
+#[cfg(feature = "display")]
+const GLYPH_S_BOLD: LEDState = [
+    [0, 9, 9, 9, 9],
+    [9, 9, 0, 0, 0],
+    [0, 9, 9, 9, 0],
+    [0, 0, 0, 9, 9],
+    [9, 9, 9, 9, 0],
+]; // bold letter S
+#[cfg(feature = "display")]
+const GLYPH_R: LEDState = [
+    [9, 9, 9, 9, 0],
+    [9, 0, 0, 0, 9],
+    [9, 9, 9, 9, 0],
+    [9, 0, 0, 9, 0],
+    [9, 0, 0, 0, 9],
+]; // letter R
+#[cfg(feature = "display")]
+const GLYPH_G: LEDState = [
+    [0, 9, 9, 9, 9],
+    [9, 0, 0, 0, 0],
+    [9, 0, 0, 9, 9],
+    [9, 0, 0, 0, 9],
+    [0, 9, 9, 9, 0],
+]; // letter G
+#[cfg(feature = "display")]
+const GLYPH_B: LEDState = [
+    [9, 9, 9, 9, 0],
+    [9, 0, 0, 0, 9],
+    [9, 9, 9, 9, 0],
+    [9, 0, 0, 0, 9],
+    [9, 9, 9, 9, 0],
+]; // letter B
+
+/// PRIVATE
+/// Whether every LED of glyph is a valid greyscale level (0 to MAX_GREYSCALE)
+#[cfg(feature = "display")]
+const fn glyph_in_range(glyph: &LEDState) -> bool {
+    let mut row = 0;
+    while row < LED_SIZE {
+        let mut col = 0;
+        while col < LED_SIZE {
+            if glyph[row][col] > MAX_GREYSCALE {
+                return false;
+            }
+            col += 1;
+        }
+        row += 1;
+    }
+    true
+}
+
+// every static glyph is checked at compile time, so a typo (e.g. a 10) fails the build instead of rendering oddly
+#[cfg(feature = "display")]
+const _: () = {
+    let glyphs = [
+        &GLYPH_H,
+        &GLYPH_H_BOLD,
+        &GLYPH_V,
+        &GLYPH_V_BOLD,
+        &GLYPH_S_BOLD,
+        &GLYPH_R,
+        &GLYPH_G,
+        &GLYPH_B,
+    ];
+    let mut i = 0;
+    while i < glyphs.len() {
+        assert!(
+            glyph_in_range(glyphs[i]),
+            "glyph LED outside the matrix greyscale"
+        );
+        i += 1;
+    }
+    assert!(
+        CALIBRATION_GREY <= MAX_GREYSCALE,
+        "calibration grey outside the matrix greyscale"
+    );
+
+    // the gamma curve keeps black and full brightness, never darkens a lit level, and never reorders levels
+    assert!(
+        DISPLAY_GAMMA[0] == 0 && DISPLAY_GAMMA[MAX_GREYSCALE as usize] == MAX_GREYSCALE,
+        "display gamma moves black or full brightness"
+    );
+    let mut level = 1;
+    while level <= MAX_GREYSCALE as usize {
+        assert!(
+            DISPLAY_GAMMA[level] >= 1 && DISPLAY_GAMMA[level] >= DISPLAY_GAMMA[level - 1],
+            "display gamma darkens or reorders a level"
+        );
+        level += 1;
+    }
+};
+
+/// The animation shown when the page changes
+///
+/// 1. Instant: the new glyph replaces the old one at the next frame (default)
+/// 2. Crossfade: the old glyph dims while the new one brightens, using the matrix greyscale
+/// 3. Wipe: the new glyph sweeps in column by column from the left
+#[derive(Clone, Copy, PartialEq)]
+pub enum PageTransition {
+    Instant,
+    Crossfade,
+    Wipe,
+}
+
+/// Impl PageTransition
+impl PageTransition {
+    /// PUBLIC
+    /// Look up a transition by its RTT name (instant, crossfade, or wipe)
+    pub fn from_name(name: &str) -> Option<PageTransition> {
+        match name {
+            "instant" => Some(PageTransition::Instant),
+            "crossfade" => Some(PageTransition::Crossfade),
+            "wipe" => Some(PageTransition::Wipe),
+            _ => None,
+        }
+    }
+}
+
+/// PUBLIC
+/// The frame shown at step (of steps) of an animated change from the from glyph to the to glyph. Step 0 is the from
+/// glyph and step steps (or later) the to glyph. Instant jumps straight to the to glyph.
+#[cfg(feature = "display")]
+pub fn transition_frame(
+    from: &LEDState,
+    to: &LEDState,
+    transition: PageTransition,
+    step: u32,
+    steps: u32,
+) -> LEDState {
+    let steps = steps.max(1);
+    let step = step.min(steps);
+    let mut leds = *to;
+    for (row, line) in leds.iter_mut().enumerate() {
+        for (col, led) in line.iter_mut().enumerate() {
+            *led = match transition {
+                PageTransition::Instant => to[row][col],
+                PageTransition::Crossfade => {
+                    let blend = from[row][col] as u32 * (steps - step) + to[row][col] as u32 * step;
+                    ((blend + steps / 2) / steps) as u8
+                }
+                PageTransition::Wipe if (col as u32) * steps < step * LED_SIZE as u32 => {
+                    to[row][col]
+                }
+                PageTransition::Wipe => from[row][col],
+            };
+        }
+    }
+    leds
+}
+
+/// PUBLIC
+/// Number of concentric rings (1-3, center-out) lit on the Saturation page for saturation s: one ring below 1/3, two
+/// below 2/3, and all three above
+#[cfg(feature = "display")]
+pub fn sat_ring_count(s: f32) -> usize {
+    (1 + (s.clamp(0.0, 1.0) * 3.0) as usize).min(3)
+}
+
+/// PUBLIC
+/// The concentric ring (0 = center LED, 1 = the 3x3 square around it, 2 = the outer border) an LED belongs to
+#[cfg(feature = "display")]
+pub fn ring_of(row: usize, col: usize) -> usize {
+    let center = LED_SIZE / 2;
+    row.abs_diff(center).max(col.abs_diff(center))
+}
+
+/// PUBLIC
+/// The error code flash sequence: whether the matrix is lit (Some(true)) or dark (Some(false)) at the given tick of
+/// blinking code, or None once all code flashes have completed. Each flash is FLASH_ON_TICKS lit followed by
+/// FLASH_OFF_TICKS dark.
+#[cfg(feature = "display")]
+pub fn flash_frame(code: u8, tick: u32) -> Option<bool> {
+    let period = FLASH_ON_TICKS + FLASH_OFF_TICKS;
+    if tick >= code as u32 * period {
+        None
+    } else {
+        Some(tick % period < FLASH_ON_TICKS)
+    }
+}
+
+/// PUBLIC
+/// The swatch parameters mirroring hsv: its brightness, the Value scaled onto the 0-MAX_GREYSCALE greyscale, and its
+/// pulse period in ticks, from SWATCH_SLOW_TICKS for a grey down to SWATCH_FAST_TICKS for a fully saturated color
+#[cfg(feature = "display")]
+pub fn swatch_params(hsv: &Hsv) -> (u8, u32) {
+    let brightness = (hsv.v.clamp(0.0, 1.0) * MAX_GREYSCALE as f32 + 0.5) as u8;
+    let speedup = hsv.s.clamp(0.0, 1.0) * (SWATCH_SLOW_TICKS - SWATCH_FAST_TICKS) as f32 + 0.5;
+    (brightness, SWATCH_SLOW_TICKS - speedup as u32)
+}
+
+/// PUBLIC
+/// The level of every swatch LED at tick: brightness at the start of each period, dimming linearly to half of it
+/// mid-period and back
+#[cfg(feature = "display")]
+pub fn swatch_level(brightness: u8, period_ticks: u32, tick: u32) -> u8 {
+    let period = period_ticks.max(1);
+    let phase = (tick % period) as f32 / period as f32;
+    let dip = 1.0 - (2.0 * phase - 1.0).abs(); // 0 -> 1 -> 0 across the period
+    (brightness as f32 * (1.0 - 0.5 * dip) + 0.5) as u8
+}
+
+/// PUBLIC
+/// The full-matrix refresh rate (in Hz, rounded down) of a TIMER0 prescaler: every row is lit for one
+/// SCAN_CYCLE_TICKS cycle of TIMER0 ticks at timer_ticks_per_second(prescaler)
+#[cfg(feature = "display")]
+pub const fn scan_rate_hz(prescaler: u32) -> u32 {
+    timer_ticks_per_second(prescaler) / (SCAN_CYCLE_TICKS * LED_SIZE as u32)
+}
+
+/// PUBLIC
+/// The TIMER0 prescaler scanning the matrix at the slowest supported rate of at least hz, the fastest supported rate
+/// for anything faster. The rates step by factors of 2, from scan_rate_hz(SLOWEST_SCAN_PRESCALER) to
+/// scan_rate_hz(FASTEST_SCAN_PRESCALER)
+#[cfg(feature = "display")]
+pub const fn scan_prescaler(hz: u32) -> u32 {
+    let mut prescaler = SLOWEST_SCAN_PRESCALER;
+    while prescaler > FASTEST_SCAN_PRESCALER && scan_rate_hz(prescaler) < hz {
+        prescaler -= 1;
+    }
+    prescaler
+}
+
+// the default is the Display's own scan, requests round up to the next rate, and out of range requests clamp
+#[cfg(feature = "display")]
+const _: () = {
+    assert!(timer_ticks_per_second(DEFAULT_SCAN_PRESCALER) == 125_000);
+    assert!(scan_rate_hz(DEFAULT_SCAN_PRESCALER) == 66);
+    assert!(scan_prescaler(scan_rate_hz(DEFAULT_SCAN_PRESCALER)) == DEFAULT_SCAN_PRESCALER);
+    assert!(scan_prescaler(100) == 6 && scan_rate_hz(6) == 133);
+    assert!(scan_prescaler(0) == SLOWEST_SCAN_PRESCALER);
+    assert!(scan_prescaler(u32::MAX) == FASTEST_SCAN_PRESCALER);
+};
+
+/// PRIVATE
+/// The registers of the TIMER instance T driving the Display
+#[cfg(feature = "display")]
+fn scan_timer<T: Instance>() -> &'static RegisterBlock {
+    // SAFETY: only the start/stop/clear tasks and the prescaler are written (see set_scan_rate), which the Display
+    // driver owning the timer never changes after its initialisation
+    unsafe {
+        match T::INTERRUPT {
+            Interrupt::TIMER1 => &*TIMER1::ptr(),
+            Interrupt::TIMER2 => &*TIMER2::ptr(),
+            Interrupt::TIMER3 => &*TIMER3::ptr(),
+            Interrupt::TIMER4 => &*TIMER4::ptr(),
+            _ => &*TIMER0::ptr(),
+        }
+    }
+}
+
+/// PUBLIC
+/// leds with every logical greyscale level mapped through DISPLAY_GAMMA to the level shown
+#[cfg(feature = "display")]
+pub fn gamma_leds(leds: &LEDState) -> LEDState {
+    leds.map(|row| row.map(|level| DISPLAY_GAMMA[level.min(MAX_GREYSCALE) as usize]))
+}
+
+/// PUBLIC
+/// The overview of hsv: the H, S, and V components as bars rising from the bottom of the OVERVIEW_COLUMNS, each of the
+/// 5 LEDs of a bar standing for a fifth of the component. The LED the bar ends in is lit in proportion to how much of
+/// its fifth is covered, so small changes still show
+#[cfg(feature = "display")]
+pub fn axes_overview(hsv: &Hsv) -> LEDState {
+    let mut leds = [[0; LED_SIZE]; LED_SIZE];
+    for (component, col) in [hsv.h, hsv.s, hsv.v].into_iter().zip(OVERVIEW_COLUMNS) {
+        let height = component.clamp(0.0, 1.0) * LED_SIZE as f32;
+        for (from_bottom, row) in leds.iter_mut().rev().enumerate() {
+            let covered = (height - from_bottom as f32).clamp(0.0, 1.0);
+            row[col] = (covered * MAX_GREYSCALE as f32 + 0.5) as u8;
+        }
+    }
+    leds
+}
+
+/// PUBLIC
+/// The decimal digits of n, most significant first, and how many of them there are (no leading zeros, 0 is one digit)
+#[cfg(feature = "display")]
+pub fn number_digits(n: u8) -> ([u8; 3], usize) {
+    let digits = [n / 100, n / 10 % 10, n % 10];
+    let count = if n >= 100 {
+        3
+    } else if n >= 10 {
+        2
+    } else {
+        1
+    };
+    let mut leading = [0; 3];
+    leading[..count].copy_from_slice(&digits[3 - count..]);
+    (leading, count)
+}
+
+/// PUBLIC
+/// Frames in the scroll of n: from the first column of its digits entering on the right edge of the matrix to the last
+/// column leaving on the left edge
+#[cfg(feature = "display")]
+pub fn number_frame_count(n: u8) -> usize {
+    let (_, count) = number_digits(n);
+    count * DIGIT_PITCH - 1 + LED_SIZE - 1
+}
+
+/// PUBLIC
+/// Frame number frame of the scroll of n (see number_frame_count), its lit LEDs at level
+#[cfg(feature = "display")]
+pub fn number_frame(n: u8, frame: usize, level: u8) -> LEDState {
+    let (digits, count) = number_digits(n);
+    let mut leds = [[0; LED_SIZE]; LED_SIZE];
+    for (row, row_leds) in leds.iter_mut().enumerate() {
+        for (col, led) in row_leds.iter_mut().enumerate() {
+            // column of the digits shown in this matrix column, the first one starting on the right edge at frame 0
+            let Some(strip_col) = (frame + col).checked_sub(LED_SIZE - 1) else {
+                continue;
+            };
+            let (digit, digit_col) = (strip_col / DIGIT_PITCH, strip_col % DIGIT_PITCH);
+            if digit < count
+                && digit_col < DIGIT_WIDTH
+                && DIGIT_FONT[digits[digit] as usize][row] & (1 << (DIGIT_WIDTH - 1 - digit_col))
+                    != 0
+            {
+                *led = level;
+            }
+        }
+    }
+    leds
+}
+
+/// PUBLIC
+/// The color code (see the module docs) of the 8-bit R, G, and B bytes rgb
+#[cfg(feature = "display")]
+pub const fn color_code(rgb: [u8; 3]) -> LEDState {
+    let bits = (rgb[0] as u32) << 16 | (rgb[1] as u32) << 8 | rgb[2] as u32;
+    let mut leds = [[0; LED_SIZE]; LED_SIZE];
+    let mut led = 0;
+    while led < 24 {
+        if bits & (1 << (23 - led)) != 0 {
+            leds[led / LED_SIZE][led % LED_SIZE] = MAX_GREYSCALE;
+        }
+        led += 1;
+    }
+    if bits.count_ones().is_multiple_of(2) {
+        leds[LED_SIZE - 1][LED_SIZE - 1] = MAX_GREYSCALE;
+    }
+    leds
+}
+
+/// PUBLIC
+/// Decode a color code read off the matrix (any non-zero level counting as lit) back into its R, G, and B bytes, None
+/// when the parity fails
+#[cfg(feature = "display")]
+pub const fn color_code_bytes(leds: &LEDState) -> Option<[u8; 3]> {
+    let mut bits = 0u32;
+    let mut led = 0;
+    while led < 24 {
+        bits = bits << 1 | (leds[led / LED_SIZE][led % LED_SIZE] != 0) as u32;
+        led += 1;
+    }
+    let parity = leds[LED_SIZE - 1][LED_SIZE - 1] != 0;
+    if (bits.count_ones().is_multiple_of(2)) != parity {
+        return None;
+    }
+    Some([(bits >> 16) as u8, (bits >> 8) as u8, bits as u8])
+}
+
+// the same color always gives the same code, which decodes back to it; colors differing in any single bit give
+// different codes; and every code lights at least one LED
+#[cfg(feature = "display")]
+const _: () = {
+    let rgb = [0xD4, 0x2A, 0x7F];
+    let code = color_code(rgb);
+    let again = color_code(rgb);
+    let mut row = 0;
+    while row < LED_SIZE {
+        let mut col = 0;
+        while col < LED_SIZE {
+            assert!(
+                code[row][col] == again[row][col],
+                "a color gave two different codes"
+            );
+            col += 1;
+        }
+        row += 1;
+    }
+    assert!(matches!(color_code_bytes(&code), Some([0xD4, 0x2A, 0x7F])));
+
+    let mut bit = 0;
+    while bit < 24 {
+        let mut other = rgb;
+        other[bit / 8] ^= 1 << (bit % 8);
+        let other_code = color_code(other);
+        let mut differs = false;
+        let mut row = 0;
+        while row < LED_SIZE {
+            let mut col = 0;
+            while col < LED_SIZE {
+                differs |= other_code[row][col] != code[row][col];
+                col += 1;
+            }
+            row += 1;
+        }
+        assert!(differs, "two colors gave the same code");
+
+        // a single misread LED fails the parity
+        let mut misread = code;
+        misread[bit / LED_SIZE][bit % LED_SIZE] ^= MAX_GREYSCALE;
+        assert!(color_code_bytes(&misread).is_none());
+        bit += 1;
+    }
+
+    let black = color_code([0, 0, 0]);
+    assert!(
+        black[LED_SIZE - 1][LED_SIZE - 1] == MAX_GREYSCALE,
+        "black shows a dark matrix"
+    );
+    assert!(matches!(color_code_bytes(&black), Some([0, 0, 0])));
+};
+
+/// FrameSwap struct declaration: Note all fields are private
+///
+/// Follows the nonblocking Display's row scan so a staged image is only shown at the start of a frame. The Display
+/// lights row 1 on its first row switch and wraps from the last row back to row 0, so every LED_SIZE row switches it
+/// is about to start a new frame with row 0.
+///
+/// It also holds a deferred render: a caller which must not hold the DISPLAY lock for a whole render() (the GPIOTE
+/// button handler) only requests one, and the TIMER0 handler renders at the next frame boundary, just before a staged
+/// image would be shown anyway, so the deferral adds no latency.
+///
+/// 1. row: the matrix row currently lit
+/// 2. pending: whether an image has been staged since the last swap
+/// 3. render_requested: whether a render has been requested since the last frame boundary
+#[cfg(feature = "display")]
+pub struct FrameSwap {
+    row: usize,
+    pending: bool,
+    render_requested: bool,
+}
+
+/// Impl FrameSwap
+#[cfg(feature = "display")]
+impl FrameSwap {
+    /// PUBLIC
+    /// Generate a new FrameSwap, matching a newly created Display (row 0, nothing staged)
+    pub const fn new() -> Self {
+        FrameSwap {
+            row: 0,
+            pending: false,
+            render_requested: false,
+        }
+    }
+
+    /// PUBLIC
+    /// Record that a new image is waiting to be shown
+    pub const fn stage(&mut self) {
+        self.pending = true;
+    }
+
+    /// PUBLIC
+    /// Record that the image must be rendered again at the next frame boundary (see take_render_request)
+    pub const fn request_render(&mut self) {
+        self.render_requested = true;
+    }
+
+    /// PUBLIC
+    /// At a frame boundary: returns whether a render was requested (and must be done now, before take_pending),
+    /// clearing the request
+    pub const fn take_render_request(&mut self) -> bool {
+        let requested = self.render_requested;
+        self.render_requested = false;
+        requested
+    }
+
+    /// PUBLIC
+    /// Called before every Display event with whether that event is a row switch. Returns true when the event is about
+    /// to start a new frame, the only time a staged image may be shown (see take_pending).
+    pub const fn on_event(&mut self, row_switched: bool) -> bool {
+        if !row_switched {
+            return false;
+        }
+        self.row = (self.row + 1) % LED_SIZE;
+        self.row == 0
+    }
+
+    /// PUBLIC
+    /// At a frame boundary: returns whether an image was staged (and must be shown now), clearing it
+    pub const fn take_pending(&mut self) -> bool {
+        let pending = self.pending;
+        self.pending = false;
+        pending
+    }
+}
+
+// a requested render waits through the rows of the current frame and is done once, at the next frame boundary, where
+// the image it stages is shown straight away
+#[cfg(feature = "display")]
+const _: () = {
+    let mut swap = FrameSwap::new();
+    swap.request_render();
+    let mut row = 1;
+    while row < LED_SIZE {
+        assert!(!swap.on_event(true), "a frame boundary came early");
+        assert!(
+            !swap.on_event(false),
+            "a greyscale step was taken for a frame boundary"
+        );
+        row += 1;
+    }
+    assert!(swap.on_event(true));
+    assert!(
+        swap.take_render_request(),
+        "the requested render was dropped"
+    );
+    swap.stage();
+    assert!(swap.take_pending());
+    assert!(!swap.take_render_request(), "one request rendered twice");
+};
 
 /// C-style enum which tracks whether we are currently on the Hue, Saturation, or
 /// Value setting.
@@ -27,6 +677,107 @@ pub enum HSVPage {
     V = 2,
 }
 
+/// Who currently writes an axis, as shown on its page glyph
+///
+/// 1. Free: the pot writes the axis, the glyph is shown as is
+/// 2. Locked: RTT holds the axis and the pot is ignored for it (see arbitration.rs), the glyph is dimmed
+/// 3. Pickup: the pot only writes again once it is turned to (or, after a hold, away from) the axis value, the glyph
+///    blinks
+#[derive(Clone, Copy, PartialEq)]
+pub enum AxisState {
+    Free,
+    Locked,
+    Pickup,
+}
+
+/// PUBLIC
+/// The brightest level the glyph of an axis in state shows at tick: MAX_GREYSCALE while Free, LOCKED_GLYPH_LEVEL
+/// while Locked, and while awaiting a Pickup MAX_GREYSCALE for the first half of every PICKUP_BLINK_TICKS and dark for
+/// the second
+#[cfg(feature = "display")]
+pub const fn axis_glyph_level(state: AxisState, tick: u32) -> u8 {
+    match state {
+        AxisState::Free => MAX_GREYSCALE,
+        AxisState::Locked => LOCKED_GLYPH_LEVEL,
+        AxisState::Pickup if tick % PICKUP_BLINK_TICKS < PICKUP_BLINK_TICKS / 2 => MAX_GREYSCALE,
+        AxisState::Pickup => 0,
+    }
+}
+
+/// PUBLIC
+/// glyph scaled so its brightest possible LED (MAX_GREYSCALE) shows at level, rounding up so no lit LED goes dark
+/// unless level is 0
+#[cfg(feature = "display")]
+pub const fn glyph_at_level(glyph: &LEDState, level: u8) -> LEDState {
+    let mut leds = *glyph;
+    let mut row = 0;
+    while row < LED_SIZE {
+        let mut col = 0;
+        while col < LED_SIZE {
+            let scaled = leds[row][col] as u32 * level as u32;
+            leds[row][col] = scaled.div_ceil(MAX_GREYSCALE as u32) as u8;
+            col += 1;
+        }
+        row += 1;
+    }
+    leds
+}
+
+// a free axis shows its glyph as is, a locked one dimmed at every tick, and one awaiting pickup blinks between full
+// and dark
+#[cfg(feature = "display")]
+const _: () = {
+    let mut tick = 0;
+    while tick < 2 * PICKUP_BLINK_TICKS {
+        assert!(axis_glyph_level(AxisState::Free, tick) == MAX_GREYSCALE);
+        assert!(
+            axis_glyph_level(AxisState::Locked, tick) == LOCKED_GLYPH_LEVEL,
+            "a locked glyph blinks"
+        );
+        let lit = tick % PICKUP_BLINK_TICKS < PICKUP_BLINK_TICKS / 2;
+        assert!(
+            axis_glyph_level(AxisState::Pickup, tick) == if lit { MAX_GREYSCALE } else { 0 },
+            "a pickup glyph does not blink"
+        );
+        tick += 1;
+    }
+    assert!(LOCKED_GLYPH_LEVEL > 0 && LOCKED_GLYPH_LEVEL < MAX_GREYSCALE);
+
+    let full = glyph_at_level(&GLYPH_H, MAX_GREYSCALE);
+    let dim = glyph_at_level(&GLYPH_H, LOCKED_GLYPH_LEVEL);
+    let dark = glyph_at_level(&GLYPH_H, 0);
+    let mut row = 0;
+    while row < LED_SIZE {
+        let mut col = 0;
+        while col < LED_SIZE {
+            assert!(
+                full[row][col] == GLYPH_H[row][col],
+                "a free glyph is changed"
+            );
+            assert!(
+                dim[row][col] <= LOCKED_GLYPH_LEVEL
+                    && (dim[row][col] > 0) == (GLYPH_H[row][col] > 0)
+            );
+            assert!(dark[row][col] == 0);
+            col += 1;
+        }
+        row += 1;
+    }
+};
+
+/// Impl HSVPage
+impl HSVPage {
+    /// PUBLIC
+    /// return the component of hsv edited on this page
+    pub fn component(&self, hsv: &Hsv) -> f32 {
+        match self {
+            HSVPage::H => hsv.h,
+            HSVPage::S => hsv.s,
+            HSVPage::V => hsv.v,
+        }
+    }
+}
+
 /// HSVDisplay<T> struct declaration: Note all fields are private
 ///
 /// <T> template contains the TIMER instance used by the nonblocking Display.
@@ -34,15 +785,81 @@ pub enum HSVPage {
 /// both within the TIMER peripheral and via the NVIC
 ///
 /// 1. page: HSVPage enum representing the state of the current HSV setting
-/// 2. display: display::nonblocking::Display struct containing TIMER peripheral <T>
-/// 3. image: the current GreyscaleImage being rendered by the nonblocking display
+/// 2. previous_page: the page selected before the last left/right rotation (see revert_page)
+/// 3. hue_locked: when true, the H page is skipped so the buttons only pick between S and V (the shade mode)
+/// 4. display: display::nonblocking::Display struct containing TIMER peripheral <T>
+/// 5. image: the most recently rendered GreyscaleImage, shown by the nonblocking display from the next frame boundary
+/// 6. calibration: when true, render() shows the uniform mid-grey calibration fill instead of the page glyph
+/// 7. flash: the error code being blinked and the current tick of its sequence, None when not flashing
+/// 8. saturation: the saturation shown by the rings on the Saturation page
+/// 9. swap: defers showing image (and any requested render) until the Display starts a new frame
+/// 10. high_contrast: when true, the page glyphs are drawn with the bold (2 LED wide) strokes
+/// 11. blanked: when true, the page glyph is hidden to save power (see display_sleep.rs)
+/// 12. transition: the animation used when the page changes
+/// 13. leds: the 5x5 array currently held in image (mid-animation, the current blended frame)
+/// 14. rendered_page: the page whose glyph was last rendered, None when something else (e.g. a flash) was
+/// 15. animation: the glyph being animated to and the current step, None when no page change is animating
+/// 16. color_space: whether the pages show the H, S, and V or the R, G, and B glyphs
+/// 17. swatch: the swatch brightness, pulse period, and current tick while the swatch is shown instead of the page
+///     glyph, None otherwise
+/// 18. scroll: the number being scrolled across the matrix and its current frame, None when no number is scrolling
+/// 19. overview: the H, S, and V bars (see axes_overview) while the overview is shown instead of the page glyph, None
+///     otherwise
+/// 20. display_gamma: whether the logical greyscale levels are mapped through DISPLAY_GAMMA before being shown
+/// 21. axis_states: who writes each axis (indexed by HSVPage), shown on that axis's page glyph
+/// 22. axis_tick: ticks since axis_states last changed, the time base of a pickup blink
+/// 23. color_code: the color code (see color_code) while it is shown instead of the page glyph, None otherwise
+///
+/// Without the `display` feature fields 4-23 are replaced by a PhantomData<T> timer marker.
 pub struct HSVDisplay<T>
 where
     T: Instance,
 {
     page: HSVPage,
+    previous_page: HSVPage,
+    hue_locked: bool,
+    #[cfg(feature = "display")]
     display: Display<T>,
+    #[cfg(feature = "display")]
     image: GreyscaleImage,
+    #[cfg(feature = "display")]
+    calibration: bool,
+    #[cfg(feature = "display")]
+    flash: Option<(u8, u32)>,
+    #[cfg(feature = "display")]
+    saturation: f32,
+    #[cfg(feature = "display")]
+    swap: FrameSwap,
+    #[cfg(feature = "display")]
+    high_contrast: bool,
+    #[cfg(feature = "display")]
+    blanked: bool,
+    #[cfg(feature = "display")]
+    transition: PageTransition,
+    #[cfg(feature = "display")]
+    leds: LEDState,
+    #[cfg(feature = "display")]
+    rendered_page: Option<HSVPage>,
+    #[cfg(feature = "display")]
+    animation: Option<(LEDState, LEDState, u32)>,
+    #[cfg(feature = "display")]
+    color_space: ColorSpace,
+    #[cfg(feature = "display")]
+    swatch: Option<(u8, u32, u32)>,
+    #[cfg(feature = "display")]
+    scroll: Option<(u8, usize)>,
+    #[cfg(feature = "display")]
+    overview: Option<LEDState>,
+    #[cfg(feature = "display")]
+    display_gamma: bool,
+    #[cfg(feature = "display")]
+    axis_states: [AxisState; AXIS_COUNT],
+    #[cfg(feature = "display")]
+    axis_tick: u32,
+    #[cfg(feature = "display")]
+    color_code: Option<LEDState>,
+    #[cfg(not(feature = "display"))]
+    timer: PhantomData<T>,
 }
 
 /// Impl HSVDisplay<T>
@@ -58,91 +875,705 @@ where
     /// Generate a new HSVDisplay<T> instance. the display field should be a display::nonblocking::Display struct
     /// instance where <T> is the TIMER peripheral used to initialize the nonblocking Display. The HSV dispaly is
     /// initialized to the Hue (H) setting.
+    #[cfg(feature = "display")]
     pub fn new(display: Display<T>) -> Self {
         HSVDisplay {
             page: HSVPage::H,
+            previous_page: HSVPage::H,
+            hue_locked: false,
             display,
             image: GreyscaleImage::new(HSVDisplay::<T>::render_h()),
+            calibration: false,
+            flash: None,
+            saturation: 0.0,
+            swap: FrameSwap::new(),
+            high_contrast: false,
+            blanked: false,
+            transition: PageTransition::Instant,
+            leds: *HSVDisplay::<T>::render_h(),
+            rendered_page: None,
+            animation: None,
+            color_space: ColorSpace::Hsv,
+            swatch: None,
+            scroll: None,
+            overview: None,
+            display_gamma: false,
+            axis_states: [AxisState::Free; AXIS_COUNT],
+            axis_tick: 0,
+            color_code: None,
         }
     }
 
     /// PUBLIC
-    /// Rotate the displayed HSV page to the left, with wrap-around.
-    /// This function is called by GPIOTE interrupt in main.rs - A button click
+    /// Generate a new page-tracking-only HSVDisplay<T> instance (no `display` feature), initialized to the Hue (H) setting.
+    #[cfg(not(feature = "display"))]
+    pub fn new() -> Self {
+        HSVDisplay {
+            page: HSVPage::H,
+            previous_page: HSVPage::H,
+            hue_locked: false,
+            timer: PhantomData,
+        }
+    }
+
+    /// PUBLIC
+    /// Rotate the displayed HSV page to the left, with wrap-around (skipping H while the hue is locked).
+    /// This function is called by GPIOTE interrupt in main.rs - A button click (B with swap-buttons, see button_map.rs)
     pub fn left(&mut self) {
-        match &self.page {
-            HSVPage::H => self.page = HSVPage::V,
-            HSVPage::S => self.page = HSVPage::H,
-            HSVPage::V => self.page = HSVPage::S,
+        self.previous_page = self.page;
+        match (&self.page, self.hue_locked) {
+            (HSVPage::H, _) => self.page = HSVPage::V,
+            (HSVPage::S, false) => self.page = HSVPage::H,
+            (HSVPage::S, true) => self.page = HSVPage::V,
+            (HSVPage::V, _) => self.page = HSVPage::S,
         };
     }
 
     /// PUBLIC
-    /// Rotate the displayed HSV page to the right, with wrap-around.
-    /// This function is called by GPIOTE interrupt in main.rs - B button click
+    /// Rotate the displayed HSV page to the right, with wrap-around (skipping H while the hue is locked).
+    /// This function is called by GPIOTE interrupt in main.rs - B button click (A with swap-buttons, see button_map.rs)
     pub fn right(&mut self) {
-        match &self.page {
-            HSVPage::H => self.page = HSVPage::S,
-            HSVPage::S => self.page = HSVPage::V,
-            HSVPage::V => self.page = HSVPage::H,
+        self.previous_page = self.page;
+        match (&self.page, self.hue_locked) {
+            (HSVPage::H, _) => self.page = HSVPage::S,
+            (HSVPage::S, _) => self.page = HSVPage::V,
+            (HSVPage::V, false) => self.page = HSVPage::H,
+            (HSVPage::V, true) => self.page = HSVPage::S,
         };
     }
 
+    /// PUBLIC
+    /// Lock (or unlock) the H page out of the left/right rotation. Locking while on the H page moves to the S page.
+    /// Takes effect on the next render()
+    pub fn set_hue_locked(&mut self, locked: bool) {
+        self.hue_locked = locked;
+        if locked && matches!(self.page, HSVPage::H) {
+            self.page = HSVPage::S;
+            self.previous_page = HSVPage::S;
+        }
+    }
+
+    /// PUBLIC
+    /// Undo the last left/right rotation. Called by main.rs when a button press turns out to be the start of an A+B chord
+    pub fn revert_page(&mut self) {
+        self.page = self.previous_page;
+    }
+
     /// PRIVATE
     /// statically allocated 5x5 array letter H
+    #[cfg(feature = "display")]
     fn render_h() -> &'static LEDState {
-        &[
-            [9, 0, 0, 0, 9],
-            [9, 0, 0, 0, 9],
-            [9, 9, 9, 9, 9],
-            [9, 0, 0, 0, 9],
-            [9, 0, 0, 0, 9],
-        ]
+        &GLYPH_H
     }
 
     /// PRIVATE
-    /// statically allocated 5x5 array letter S
-    fn render_s() -> &'static LEDState {
-        &[
-            [9, 9, 9, 9, 9],
-            [9, 0, 0, 0, 0],
-            [9, 9, 9, 9, 9],
-            [0, 0, 0, 0, 9],
-            [9, 9, 9, 9, 9],
-        ]
+    /// statically allocated 5x5 array letter H with bold 2 LED wide uprights, for the high-contrast glyph set. The
+    /// crossbar keeps a clear dark gap above and below it so the matrix bleed doesn't merge the strokes.
+    #[cfg(feature = "display")]
+    fn render_h_bold() -> &'static LEDState {
+        &GLYPH_H_BOLD
+    }
+
+    /// PUBLIC
+    /// 5x5 array for the Saturation page: 1-3 concentric rings lit center-out as saturation s increases (see
+    /// sat_ring_count), an at-a-glance "how saturated" indicator
+    #[cfg(feature = "display")]
+    pub fn render_sat_rings(s: f32) -> LEDState {
+        let rings = sat_ring_count(s);
+        let mut leds = [[0; LED_SIZE]; LED_SIZE];
+        for (row, line) in leds.iter_mut().enumerate() {
+            for (col, led) in line.iter_mut().enumerate() {
+                if ring_of(row, col) < rings {
+                    *led = 9;
+                }
+            }
+        }
+        leds
+    }
+
+    /// PUBLIC
+    /// Update the saturation shown on the Saturation page, re-rendering only when that page is showing and the number
+    /// of lit rings changes. Called by the main.rs event loop at the 100ms refresh rate
+    #[cfg(feature = "display")]
+    pub fn set_saturation(&mut self, s: f32) {
+        let changed = sat_ring_count(s) != sat_ring_count(self.saturation);
+        self.saturation = s;
+        if changed
+            && matches!(self.page, HSVPage::S)
+            && !self.high_contrast
+            && self.glyph_space() == ColorSpace::Hsv
+        {
+            self.render();
+        }
     }
 
     /// PRIVATE
     /// statically allocated 5x5 array letter V
+    #[cfg(feature = "display")]
     fn render_v() -> &'static LEDState {
-        &[
-            [9, 0, 0, 0, 9],
-            [9, 0, 0, 0, 9],
-            [9, 0, 0, 0, 9],
-            [0, 9, 0, 9, 0],
-            [0, 0, 9, 0, 0],
-        ]
+        &GLYPH_V
+    }
+
+    /// PRIVATE
+    /// statically allocated 5x5 array letter V with bold 2 LED wide arms, for the high-contrast glyph set
+    #[cfg(feature = "display")]
+    fn render_v_bold() -> &'static LEDState {
+        &GLYPH_V_BOLD
+    }
+
+    /// PRIVATE
+    /// statically allocated 5x5 array letter S with bold 2 LED wide ends, for the high-contrast glyph set. This
+    /// replaces the saturation rings, whose single lit center LED at low saturation is the hardest glyph to make out.
+    #[cfg(feature = "display")]
+    fn render_s_bold() -> &'static LEDState {
+        &GLYPH_S_BOLD
+    }
+
+    /// PRIVATE
+    /// statically allocated 5x5 array letter R
+    #[cfg(feature = "display")]
+    fn render_r() -> &'static LEDState {
+        &GLYPH_R
+    }
+
+    /// PRIVATE
+    /// statically allocated 5x5 array letter G
+    #[cfg(feature = "display")]
+    fn render_g() -> &'static LEDState {
+        &GLYPH_G
+    }
+
+    /// PRIVATE
+    /// statically allocated 5x5 array letter B
+    #[cfg(feature = "display")]
+    fn render_b() -> &'static LEDState {
+        &GLYPH_B
+    }
+
+    /// PUBLIC
+    /// The glyph shown for page: in the HSV color space the standard or the high-contrast (bold) bitmap, with s the
+    /// saturation shown by the standard Saturation page rings, and in the RGB color space the R, G, or B letter
+    #[cfg(feature = "display")]
+    pub fn page_glyph(
+        page: HSVPage,
+        color_space: ColorSpace,
+        high_contrast: bool,
+        s: f32,
+    ) -> LEDState {
+        match (color_space, page, high_contrast) {
+            (ColorSpace::Hsv, HSVPage::H, false) => *HSVDisplay::<T>::render_h(),
+            (ColorSpace::Hsv, HSVPage::H, true) => *HSVDisplay::<T>::render_h_bold(),
+            (ColorSpace::Hsv, HSVPage::S, false) => HSVDisplay::<T>::render_sat_rings(s),
+            (ColorSpace::Hsv, HSVPage::S, true) => *HSVDisplay::<T>::render_s_bold(),
+            (ColorSpace::Hsv, HSVPage::V, false) => *HSVDisplay::<T>::render_v(),
+            (ColorSpace::Hsv, HSVPage::V, true) => *HSVDisplay::<T>::render_v_bold(),
+            (ColorSpace::Rgb, HSVPage::H, _) => *HSVDisplay::<T>::render_r(),
+            (ColorSpace::Rgb, HSVPage::S, _) => *HSVDisplay::<T>::render_g(),
+            (ColorSpace::Rgb, HSVPage::V, _) => *HSVDisplay::<T>::render_b(),
+        }
+    }
+
+    /// PUBLIC
+    /// Switch the pages between the H, S, and V and the R, G, and B glyphs, re-rendering the matrix
+    #[cfg(feature = "display")]
+    pub fn set_color_space(&mut self, color_space: ColorSpace) {
+        self.color_space = color_space;
+        self.render();
+    }
+
+    /// PRIVATE
+    /// The color space whose glyphs are shown: always HSV while the hue is locked, since the shade mode only edits S
+    /// and V
+    #[cfg(feature = "display")]
+    fn glyph_space(&self) -> ColorSpace {
+        if self.hue_locked {
+            ColorSpace::Hsv
+        } else {
+            self.color_space
+        }
+    }
+
+    /// PRIVATE
+    /// statically allocated 5x5 array of uniform mid-grey, shown in the calibration mode as a brightness
+    /// reference to compare the RGB LED's white point against by eye
+    #[cfg(feature = "display")]
+    fn render_calibration() -> &'static LEDState {
+        &[[CALIBRATION_GREY; LED_SIZE]; LED_SIZE]
+    }
+
+    /// PRIVATE
+    /// 5x5 array of the swatch at its current tick, every LED at the same level
+    #[cfg(feature = "display")]
+    fn render_swatch_leds(&self) -> LEDState {
+        let (brightness, period, tick) = self.swatch.unwrap_or((0, SWATCH_SLOW_TICKS, 0));
+        [[swatch_level(brightness, period, tick); LED_SIZE]; LED_SIZE]
+    }
+
+    /// PRIVATE
+    /// statically allocated 5x5 array of every LED fully lit, the on frame of an error code flash
+    #[cfg(feature = "display")]
+    fn render_flash() -> &'static LEDState {
+        &[[MAX_GREYSCALE; LED_SIZE]; LED_SIZE]
+    }
+
+    /// PRIVATE
+    /// statically allocated 5x5 array of every LED off, the off frame of an error code flash and the blanked matrix
+    #[cfg(feature = "display")]
+    fn render_blank() -> &'static LEDState {
+        &[[0; LED_SIZE]; LED_SIZE]
+    }
+
+    /// PUBLIC
+    /// Blink an error code on the whole matrix as code flashes (see flash_frame), advanced by tick(), then return to
+    /// the normal page display. Used for diagnostics without RTT. A code requested while another is still flashing is
+    /// ignored, so a repeating error doesn't keep restarting the sequence.
+    #[cfg(feature = "display")]
+    pub fn flash_code(&mut self, code: u8) {
+        if self.flash.is_none() {
+            self.flash = Some((code, 0));
+            self.render();
+        }
+    }
+
+    /// PUBLIC
+    /// Advance any error code flash (or else any scrolling number, or the swatch pulse) by one tick. Called by the
+    /// main.rs event loop at the 100ms refresh rate
+    #[cfg(feature = "display")]
+    pub fn tick(&mut self) {
+        if let Some((code, tick)) = self.flash {
+            self.flash = Some((code, tick + 1));
+            self.render();
+        } else if let Some((n, frame)) = self.scroll {
+            self.scroll = Some((n, frame + 1)).filter(|&(n, frame)| frame < number_frame_count(n));
+            self.render();
+        } else if let Some((brightness, period, tick)) = self.swatch {
+            self.swatch = Some((brightness, period, tick.wrapping_add(1)));
+            self.render();
+        } else if self.axis_states[self.page as usize] == AxisState::Pickup {
+            self.axis_tick = self.axis_tick.wrapping_add(1);
+            self.render();
+        }
+    }
+
+    /// PUBLIC
+    /// Update who writes each axis (indexed by HSVPage), re-rendering only when any state changes so a pickup blink
+    /// starts lit. Called by the main.rs event loop at the 100ms refresh rate
+    #[cfg(feature = "display")]
+    pub fn set_axis_states(&mut self, states: [AxisState; AXIS_COUNT]) {
+        if states != self.axis_states {
+            self.axis_states = states;
+            self.axis_tick = 0;
+            self.render();
+        }
+    }
+
+    /// PUBLIC
+    /// Scroll n across the matrix one column per tick (see number_frame), then return to the glyph. Replaces any number
+    /// already scrolling
+    #[cfg(feature = "display")]
+    pub fn render_number(&mut self, n: u8) {
+        self.scroll = Some((n, 0));
+        self.render();
+    }
+
+    /// PUBLIC
+    /// Show the swatch mirroring the LED color instead of the page glyph, or go back to the page glyph. The swatch
+    /// starts dark until the first render_swatch()
+    #[cfg(feature = "display")]
+    pub fn set_swatch(&mut self, enabled: bool) {
+        self.swatch = enabled.then_some((0, SWATCH_SLOW_TICKS, 0));
+        if enabled {
+            self.overview = None;
+            self.color_code = None;
+        }
+        self.render();
+    }
+
+    /// PUBLIC
+    /// Show the overview of all three axes instead of the page glyph (replacing any swatch or color code), or go back
+    /// to the page glyph. The overview starts dark until the first render_all_axes()
+    #[cfg(feature = "display")]
+    pub fn set_overview(&mut self, enabled: bool) {
+        self.overview = enabled.then_some([[0; LED_SIZE]; LED_SIZE]);
+        if enabled {
+            self.swatch = None;
+            self.color_code = None;
+        }
+        self.render();
+    }
+
+    /// PUBLIC
+    /// Show the color code instead of the page glyph (replacing any swatch or overview), or go back to the page glyph.
+    /// The code starts dark until the first render_color_code()
+    #[cfg(feature = "display")]
+    pub fn set_color_code(&mut self, enabled: bool) {
+        self.color_code = enabled.then_some([[0; LED_SIZE]; LED_SIZE]);
+        if enabled {
+            self.swatch = None;
+            self.overview = None;
+        }
+        self.render();
+    }
+
+    /// PUBLIC
+    /// Update the color code to encode hsv, quantized to its 8-bit R, G, and B bytes, re-rendering only when any LED
+    /// changes. Does nothing unless the color code is shown. Called by the main.rs event loop at the 100ms refresh rate
+    #[cfg(feature = "display")]
+    pub fn render_color_code(&mut self, hsv: &Hsv) {
+        if let Some(shown) = self.color_code {
+            let leds = color_code(hsv.to_rgb().to_u8());
+            if leds != shown {
+                self.color_code = Some(leds);
+                self.render();
+            }
+        }
+    }
+
+    /// PUBLIC
+    /// Update the overview to show hsv (see axes_overview), re-rendering only when any LED changes. Does nothing unless
+    /// the overview is shown. Called by the main.rs event loop at the 100ms refresh rate
+    #[cfg(feature = "display")]
+    pub fn render_all_axes(&mut self, hsv: &Hsv) {
+        if let Some(shown) = self.overview {
+            let leds = axes_overview(hsv);
+            if leds != shown {
+                self.overview = Some(leds);
+                self.render();
+            }
+        }
+    }
+
+    /// PUBLIC
+    /// Update the swatch to mirror hsv (see swatch_params), re-rendering only when its brightness or pulse rate
+    /// changes. Does nothing unless the swatch is shown. Called by the main.rs event loop at the 100ms refresh rate
+    #[cfg(feature = "display")]
+    pub fn render_swatch(&mut self, hsv: &Hsv) {
+        if let Some((brightness, period, tick)) = self.swatch {
+            let params = swatch_params(hsv);
+            if params != (brightness, period) {
+                self.swatch = Some((params.0, params.1, tick));
+                self.render();
+            }
+        }
+    }
+
+    /// PUBLIC
+    /// Switch between the standard and the high-contrast (bold stroke) glyph set, re-rendering the matrix
+    #[cfg(feature = "display")]
+    pub fn set_high_contrast(&mut self, high_contrast: bool) {
+        self.high_contrast = high_contrast;
+        self.render();
+    }
+
+    /// PUBLIC
+    /// Blank the matrix to save power while the RGB LED carries on. Error code flashes and the calibration fill are
+    /// still shown, only the page glyph is hidden.
+    #[cfg(feature = "display")]
+    pub fn blank(&mut self) {
+        self.blanked = true;
+        self.render();
+    }
+
+    /// PUBLIC
+    /// Re-light the page glyph after blank(). The press doing so does not rotate the page, so if it turns out to be the
+    /// start of an A+B chord there is no rotation for revert_page() to undo. Called from the GPIOTE button handler, so
+    /// the glyph is only rendered at the next frame boundary (see request_render)
+    #[cfg(feature = "display")]
+    pub fn unblank(&mut self) {
+        self.blanked = false;
+        self.previous_page = self.page;
+        self.request_render();
+    }
+
+    /// PUBLIC
+    /// return whether the page glyph is blanked
+    #[cfg(feature = "display")]
+    pub fn is_blanked(&self) -> bool {
+        self.blanked
     }
 
     /// PUBLIC
-    /// Updates the self.image (GreyscaleImage) with a new H, S, or V 5x5 array and passes
-    /// the new GreyscaleImage to the nonblocking Display.show() method for rendering
+    /// Turn the calibration fill on or off. Takes effect on the next render()
+    #[cfg(feature = "display")]
+    pub fn set_calibration(&mut self, calibration: bool) {
+        self.calibration = calibration;
+    }
+
+    /// PUBLIC
+    /// Updates the self.image (GreyscaleImage) with a new H, S, or V 5x5 array (or the calibration fill, or the current
+    /// error code flash frame) and stages it for the nonblocking Display.show() method at the next frame boundary
+    #[cfg(feature = "display")]
     pub fn render(&mut self) {
-        let leds = match &self.page {
-            HSVPage::H => GreyscaleImage::new(HSVDisplay::<T>::render_h()),
-            HSVPage::S => GreyscaleImage::new(HSVDisplay::<T>::render_s()),
-            HSVPage::V => GreyscaleImage::new(HSVDisplay::<T>::render_v()),
+        // an error code flash takes over the whole matrix until its sequence completes
+        let mut flash = None;
+        if let Some((code, tick)) = self.flash {
+            flash = flash_frame(code, tick);
+            if flash.is_none() {
+                self.flash = None;
+            }
+        }
+
+        let (leds, glyph_page) = match flash {
+            Some(true) => (*HSVDisplay::<T>::render_flash(), None),
+            Some(false) => (*HSVDisplay::<T>::render_blank(), None),
+            None if let Some((n, frame)) = self.scroll => {
+                (number_frame(n, frame, MAX_GREYSCALE), None)
+            }
+            None if self.calibration => (*HSVDisplay::<T>::render_calibration(), None),
+            None if self.blanked => (*HSVDisplay::<T>::render_blank(), None),
+            None if let Some(leds) = self.color_code => (leds, None),
+            None if let Some(leds) = self.overview => (leds, None),
+            None if self.swatch.is_some() => (self.render_swatch_leds(), None),
+            None => (
+                glyph_at_level(
+                    &HSVDisplay::<T>::page_glyph(
+                        self.page,
+                        self.glyph_space(),
+                        self.high_contrast,
+                        self.saturation,
+                    ),
+                    axis_glyph_level(self.axis_states[self.page as usize], self.axis_tick),
+                ),
+                Some(self.page),
+            ),
         };
 
-        self.image = leds;
+        // a change from one page glyph to another is animated from whatever is currently shown, a re-render of the
+        // same page mid-animation retargets it, and anything else is shown at once
+        let page_changed = matches!((self.rendered_page, glyph_page),
+            (Some(from), Some(to)) if from as usize != to as usize);
+        self.rendered_page = glyph_page;
+        if page_changed && self.transition != PageTransition::Instant {
+            self.animation = Some((self.leds, leds, 0));
+        } else if let (Some((from, _, step)), Some(_)) = (self.animation, glyph_page) {
+            self.animation = Some((from, leds, step));
+        } else {
+            self.animation = None;
+            self.leds = leds;
+            self.image = self.build_image();
+            self.swap.stage();
+        }
+    }
+
+    /// PUBLIC
+    /// Request a render() at the next frame boundary, done by the TIMER0 interrupt through handle_display_event(). For
+    /// callers which must keep the DISPLAY lock short: the GPIOTE button handler
+    #[cfg(feature = "display")]
+    pub fn request_render(&mut self) {
+        self.swap.request_render();
+    }
+
+    /// PUBLIC
+    /// Bring the matrix back to a known-good state, e.g. after waking: any page change animation is dropped and the
+    /// current page (or flash, calibration fill, or blank) is shown at once rather than at the next frame boundary,
+    /// since the row tracking can't be trusted after the Display has been stopped. Safe to call any number of times
+    #[cfg(feature = "display")]
+    pub fn reinit(&mut self) {
+        self.animation = None;
+        self.rendered_page = None;
+        self.render();
+        self.swap.take_pending();
         self.display.show(&self.image);
     }
 
+    /// PUBLIC
+    /// Choose the animation used when the page changes. Takes effect from the next page change
+    #[cfg(feature = "display")]
+    pub fn set_page_transition(&mut self, transition: PageTransition) {
+        self.transition = transition;
+    }
+
+    /// PUBLIC
+    /// Map the matrix greyscale through the perceptual DISPLAY_GAMMA curve, or show the logical levels raw. The image
+    /// currently shown is rebuilt and re-shown from the next frame boundary
+    #[cfg(feature = "display")]
+    pub fn set_display_gamma(&mut self, enabled: bool) {
+        self.display_gamma = enabled;
+        self.image = self.build_image();
+        self.swap.stage();
+    }
+
+    /// PUBLIC
+    /// Scan the matrix at the slowest supported rate of at least hz (see scan_prescaler), returning the rate applied.
+    /// Faster scans flicker less but cost more TIMER0 interrupts (see the module notes). The timer is briefly stopped
+    /// to change its prescaler, so one row may be cut short
+    #[cfg(feature = "display")]
+    pub fn set_scan_rate(&mut self, hz: u32) -> Option<u32> {
+        let prescaler = scan_prescaler(hz);
+        let timer = scan_timer::<T>();
+        timer.tasks_stop.write(|w| unsafe { w.bits(1) });
+        timer
+            .prescaler
+            .write(|w| unsafe { w.prescaler().bits(prescaler as u8) });
+        timer.tasks_clear.write(|w| unsafe { w.bits(1) });
+        timer.tasks_start.write(|w| unsafe { w.bits(1) });
+        Some(scan_rate_hz(prescaler))
+    }
+
+    /// PRIVATE
+    /// The GreyscaleImage of self.leds, through DISPLAY_GAMMA if enabled
+    #[cfg(feature = "display")]
+    fn build_image(&self) -> GreyscaleImage {
+        if self.display_gamma {
+            GreyscaleImage::new(&gamma_leds(&self.leds))
+        } else {
+            GreyscaleImage::new(&self.leds)
+        }
+    }
+
+    /// PRIVATE
+    /// Advance a running page change animation by one display frame, staging the next frame of it
+    #[cfg(feature = "display")]
+    fn step_animation(&mut self) {
+        if let Some((from, to, step)) = self.animation {
+            let step = step + 1;
+            self.leds = transition_frame(&from, &to, self.transition, step, TRANSITION_FRAMES);
+            self.animation = if step >= TRANSITION_FRAMES {
+                None
+            } else {
+                Some((from, to, step))
+            };
+            self.image = self.build_image();
+            self.swap.stage();
+        }
+    }
+
+    /// PUBLIC
+    /// Without the `display` feature there is no calibration fill to show
+    #[cfg(not(feature = "display"))]
+    pub fn set_calibration(&mut self, _calibration: bool) {}
+
+    /// PUBLIC
+    /// Without the `display` feature the matrix is never lit, so there is nothing to blank
+    #[cfg(not(feature = "display"))]
+    pub fn blank(&mut self) {}
+
+    /// PUBLIC
+    /// Without the `display` feature there is nothing to re-light
+    #[cfg(not(feature = "display"))]
+    pub fn unblank(&mut self) {}
+
+    /// PUBLIC
+    /// Without the `display` feature the matrix is never blanked
+    #[cfg(not(feature = "display"))]
+    pub fn is_blanked(&self) -> bool {
+        false
+    }
+
+    /// PUBLIC
+    /// Without the `display` feature there is no matrix to bring back
+    #[cfg(not(feature = "display"))]
+    pub fn reinit(&mut self) {}
+
+    /// PUBLIC
+    /// Without the `display` feature there are no glyphs to switch
+    #[cfg(not(feature = "display"))]
+    pub fn set_high_contrast(&mut self, _high_contrast: bool) {}
+
+    /// PUBLIC
+    /// Without the `display` feature there are no glyphs to switch
+    #[cfg(not(feature = "display"))]
+    pub fn set_color_space(&mut self, _color_space: ColorSpace) {}
+
+    /// PUBLIC
+    /// Without the `display` feature there are no page changes to animate
+    #[cfg(not(feature = "display"))]
+    pub fn set_page_transition(&mut self, _transition: PageTransition) {}
+
+    /// PUBLIC
+    /// Without the `display` feature there is no matrix to update
+    #[cfg(not(feature = "display"))]
+    pub fn render(&mut self) {}
+
+    /// PUBLIC
+    /// Without the `display` feature there is no matrix to update
+    #[cfg(not(feature = "display"))]
+    pub fn request_render(&mut self) {}
+
+    /// PUBLIC
+    /// Without the `display` feature there are no saturation rings to update
+    #[cfg(not(feature = "display"))]
+    pub fn set_saturation(&mut self, _s: f32) {}
+
+    /// PUBLIC
+    /// Without the `display` feature there is no matrix to blink error codes on
+    #[cfg(not(feature = "display"))]
+    pub fn flash_code(&mut self, _code: u8) {}
+
+    /// PUBLIC
+    /// Without the `display` feature there is no error code flash to advance
+    #[cfg(not(feature = "display"))]
+    pub fn tick(&mut self) {}
+
+    /// PUBLIC
+    /// Without the `display` feature there is no matrix to scroll a number across
+    #[cfg(not(feature = "display"))]
+    pub fn render_number(&mut self, _n: u8) {}
+
+    /// PUBLIC
+    /// Without the `display` feature there is no matrix to show a swatch on
+    #[cfg(not(feature = "display"))]
+    pub fn set_swatch(&mut self, _enabled: bool) {}
+
+    /// PUBLIC
+    /// Without the `display` feature there is no swatch to update
+    #[cfg(not(feature = "display"))]
+    pub fn render_swatch(&mut self, _hsv: &Hsv) {}
+
+    /// PUBLIC
+    /// Without the `display` feature there is no matrix to scan, so None
+    #[cfg(not(feature = "display"))]
+    pub fn set_scan_rate(&mut self, _hz: u32) -> Option<u32> {
+        None
+    }
+
+    /// PUBLIC
+    /// Without the `display` feature there is no matrix greyscale to correct
+    #[cfg(not(feature = "display"))]
+    pub fn set_display_gamma(&mut self, _enabled: bool) {}
+
+    /// PUBLIC
+    /// Without the `display` feature there is no matrix to show the overview on
+    #[cfg(not(feature = "display"))]
+    pub fn set_overview(&mut self, _enabled: bool) {}
+
+    /// PUBLIC
+    /// Without the `display` feature there is no overview to update
+    #[cfg(not(feature = "display"))]
+    pub fn render_all_axes(&mut self, _hsv: &Hsv) {}
+
+    /// PUBLIC
+    /// Without the `display` feature there is no matrix to show the color code on
+    #[cfg(not(feature = "display"))]
+    pub fn set_color_code(&mut self, _enabled: bool) {}
+
+    /// PUBLIC
+    /// Without the `display` feature there is no color code to update
+    #[cfg(not(feature = "display"))]
+    pub fn render_color_code(&mut self, _hsv: &Hsv) {}
+
+    /// PUBLIC
+    /// Without the `display` feature there is no glyph to show the axis states on
+    #[cfg(not(feature = "display"))]
+    pub fn set_axis_states(&mut self, _states: [AxisState; AXIS_COUNT]) {}
+
     /// PUBLIC
     /// Thin wrapper around the nonblocking Display::handle_display_event() method which must be
     /// called on the nonblocking Display timer interrupt to physically updated the LED pin voltage states
-    /// and display the image.
-    pub fn handle_display_event(&mut self) {
+    /// and display the image. row_switched is whether the pending timer event is the Display's row switch
+    /// (its TIMER CC[0] compare), so a staged image (or the next step of a page change animation) can be shown just
+    /// before the first row of a new frame.
+    #[cfg(feature = "display")]
+    pub fn handle_display_event(&mut self, row_switched: bool) {
+        if self.swap.on_event(row_switched) {
+            if self.swap.take_render_request() {
+                self.render();
+            }
+            self.step_animation();
+            if self.swap.take_pending() {
+                self.display.show(&self.image);
+            }
+        }
         self.display.handle_display_event();
     }
 
@@ -26,9 +26,65 @@ pub struct Rgb {
     pub b: f32,
 }
 
+/// HSL coordinates (with minimal semantics).
+#[derive(Clone, Copy)]
+pub struct Hsl {
+    /// Hue [0..1)
+    pub h: f32,
+    /// Saturation [0..1]
+    pub s: f32,
+    /// Lightness [0..1]
+    pub l: f32,
+}
+
+/// Wrap a hue (a unit angle) into the canonical half-open range [0..1).
+/// Whole turns are removed, negative hues wrap from the top, exactly 1.0 maps
+/// to 0.0, and a NaN hue maps to 0.0 rather than propagating into to_rgb.
+pub const fn normalize_hue(h: f32) -> f32 {
+    let mut wrapped = h - (h as i32) as f32;
+    if wrapped < 0.0 {
+        wrapped += 1.0;
+    }
+    // -tiny + 1.0 can round up to exactly 1.0
+    if wrapped >= 0.0 && wrapped < 1.0 {
+        wrapped
+    } else {
+        0.0
+    }
+}
+
+/// The signed shortest change taking hue `from` to hue `to` around the color
+/// wheel, in [-0.5..0.5): positive is clockwise (increasing hue, wrapping from
+/// 1.0 to 0.0). Hues outside [0..1) are wrapped first. Opposite hues are a tie
+/// between the two ways round, resolved counter-clockwise to -0.5, whichever
+/// hue is the larger.
+pub const fn hue_delta(from: f32, to: f32) -> f32 {
+    let dh = normalize_hue(to - from);
+    if dh >= 0.5 { dh - 1.0 } else { dh }
+}
+
+// the shortest way across the seam of the wheel is taken both ways, and an
+// opposite hue always resolves to -0.5
+const _: () = {
+    const fn near(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-6
+    }
+    assert!(near(hue_delta(0.9, 0.1), 0.2));
+    assert!(near(hue_delta(0.1, 0.9), -0.2));
+    assert!(hue_delta(0.0, 0.5) == -0.5 && hue_delta(0.5, 0.0) == -0.5);
+    assert!(hue_delta(0.25, 0.25) == 0.0);
+    assert!(near(hue_delta(0.2, 0.45), 0.25) && near(hue_delta(0.45, 0.2), -0.25));
+    assert!(
+        near(hue_delta(1.0, 0.1), 0.1),
+        "an unwrapped hue was not wrapped"
+    );
+};
+
 impl Hsv {
-    /// Convert HSV to sRGB. H is a unit angle in [0..1).
-    pub fn to_rgb(self) -> Rgb {
+    /// Convert HSV to sRGB. H is a unit angle in [0..1). With s = 0 the chroma and
+    /// x are exactly 0 in every sector, so a grey comes out as exactly (v, v, v)
+    /// whatever its hue (pinned at compile time in color_control.rs).
+    pub const fn to_rgb(self) -> Rgb {
         let c = self.s * self.v;
         let h6 = self.h * 6.0;
         let sector = h6 as u32;
@@ -62,8 +118,117 @@ impl Hsv {
     }
 }
 
+impl Hsv {
+    /// Convert HSV to HSL. The hue is shared; black and white have an
+    /// HSL saturation of 0.
+    pub fn to_hsl(self) -> Hsl {
+        let l = self.v * (1.0 - self.s / 2.0);
+        let m = l.min(1.0 - l);
+        let s = if m > 0.0 { (self.v - l) / m } else { 0.0 };
+        Hsl { h: self.h, s, l }
+    }
+}
+
+impl Rgb {
+    /// Convert sRGB to HSV, the inverse of Hsv::to_rgb. Greys (including
+    /// black) have a hue of 0, and black a saturation of 0.
+    pub const fn to_hsv(self) -> Hsv {
+        let max = self.r.max(self.g).max(self.b);
+        let c = max - self.r.min(self.g).min(self.b);
+
+        let h6 = if c <= 0.0 {
+            0.0
+        } else if max == self.r {
+            let h6 = (self.g - self.b) / c;
+            if h6 < 0.0 { h6 + 6.0 } else { h6 }
+        } else if max == self.g {
+            (self.b - self.r) / c + 2.0
+        } else {
+            (self.r - self.g) / c + 4.0
+        };
+        let s = if max > 0.0 { c / max } else { 0.0 };
+
+        Hsv {
+            h: normalize_hue(h6 / 6.0),
+            s,
+            v: max,
+        }
+    }
+
+    /// Convert from 8-bit channels in [0..255], the inverse of to_u8.
+    pub const fn from_u8(rgb: [u8; 3]) -> Rgb {
+        Rgb {
+            r: rgb[0] as f32 / 255.0,
+            g: rgb[1] as f32 / 255.0,
+            b: rgb[2] as f32 / 255.0,
+        }
+    }
+
+    /// Convert to 8-bit channels in [0..255], rounding to nearest.
+    pub const fn to_u8(self) -> [u8; 3] {
+        const fn scale(c: f32) -> u8 {
+            (c.clamp(0.0, 1.0) * 255.0 + 0.5) as u8
+        }
+        [scale(self.r), scale(self.g), scale(self.b)]
+    }
+}
+
 impl From<Hsv> for Rgb {
     fn from(value: Hsv) -> Self {
         value.to_rgb()
     }
 }
+
+impl From<Rgb> for Hsv {
+    fn from(value: Rgb) -> Self {
+        value.to_hsv()
+    }
+}
+
+// to_hsv inverts to_rgb to within a small epsilon in every hue sector and
+// across the seam of the wheel, and an achromatic color comes back as h = 0,
+// s = 0
+const _: () = {
+    const EPSILON: f32 = 1e-5;
+    const fn near(a: f32, b: f32) -> bool {
+        (a - b).abs() < EPSILON
+    }
+    let mut i = 0;
+    while i < 24 {
+        let hsv = Hsv {
+            h: i as f32 / 24.0,
+            s: 0.75,
+            v: 0.5,
+        };
+        let back = hsv.to_rgb().to_hsv();
+        assert!(
+            near(hue_delta(hsv.h, back.h), 0.0),
+            "hue lost in a round trip"
+        );
+        assert!(near(back.s, hsv.s) && near(back.v, hsv.v));
+        i += 1;
+    }
+    let seam = Hsv {
+        h: 0.999,
+        s: 1.0,
+        v: 1.0,
+    }
+    .to_rgb()
+    .to_hsv();
+    assert!(near(seam.h, 0.999), "hue wrapped the wrong way at the seam");
+
+    let grey = Rgb {
+        r: 0.4,
+        g: 0.4,
+        b: 0.4,
+    }
+    .to_hsv();
+    assert!(grey.h == 0.0 && grey.s == 0.0 && grey.v == 0.4);
+    let black = Rgb {
+        r: 0.0,
+        g: 0.0,
+        b: 0.0,
+    }
+    .to_hsv();
+    assert!(black.h == 0.0 && black.s == 0.0 && black.v == 0.0);
+};
@@ -0,0 +1,81 @@
+//! hue_rate.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The hue_rate module caps how fast any animation may carry the hue around the color wheel. A hue cycling many times
+//! a second no longer reads as a rainbow but as a strobing flicker, which is uncomfortable to look at, so every hue
+//! animation (the rainbow mode and `drift` effect, see effects.rs, and the standby shimmer, see standby.rs) limits its
+//! period through here. The cap is set in turns of the color wheel per second with the RTT `huecap` command.
+//!
+//! Hue fades and the pot are not animations and are never limited.
+
+use super::color_control::ColorControler;
+
+/// Constants
+pub const DEFAULT_HUE_RATE_CAP: f32 = 1.0; // at most one trip around the color wheel per second
+pub const MIN_HUE_RATE_CAP: f32 = 0.01; // the slowest cap, one trip every 100 seconds
+pub const MAX_HUE_RATE_CAP: f32 = 10.0; // faster hue cycles strobe however comfortable the user finds them
+
+/// PUBLIC
+/// Clamp a requested cap (turns per second) into [MIN_HUE_RATE_CAP,MAX_HUE_RATE_CAP], NaN giving the default
+pub const fn clamp_hue_rate_cap(cap: f32) -> f32 {
+    if cap.is_nan() {
+        DEFAULT_HUE_RATE_CAP
+    } else {
+        cap.clamp(MIN_HUE_RATE_CAP, MAX_HUE_RATE_CAP)
+    }
+}
+
+/// PRIVATE
+/// The shortest hue cycle period the cap allows, in units of unit_us (rounded up, so the limited period never cycles
+/// faster than the cap)
+const fn min_period(cap: f32, unit_us: u32) -> u32 {
+    let period = 1_000_000.0 / (clamp_hue_rate_cap(cap) * unit_us as f32);
+    let whole = period as u32;
+    if (whole as f32) < period {
+        whole + 1
+    } else {
+        whole
+    }
+}
+
+/// PUBLIC
+/// Limit a hue cycle period in msecs to at most cap turns per second. A slower period passes through unchanged
+pub const fn limit_hue_period_ms(period_ms: u32, cap: f32) -> u32 {
+    let min_ms = min_period(cap, 1_000);
+    if period_ms < min_ms {
+        min_ms
+    } else {
+        period_ms
+    }
+}
+
+/// PUBLIC
+/// limit_hue_period_ms() for a period in PWM frames (see ColorControler::FRAME_DURATION_US)
+pub const fn limit_hue_period_frames(period_frames: u32, cap: f32) -> u32 {
+    let min_frames = min_period(cap, ColorControler::FRAME_DURATION_US);
+    if period_frames < min_frames {
+        min_frames
+    } else {
+        period_frames
+    }
+}
+
+// a hue cycle faster than the cap is slowed to exactly the cap, while a slower one passes through untouched
+const _: () = {
+    // 10 turns per second requested, 1 allowed
+    assert!(limit_hue_period_ms(100, DEFAULT_HUE_RATE_CAP) == 1_000);
+    assert!(limit_hue_period_frames(10, DEFAULT_HUE_RATE_CAP) == 100);
+    // the 10 second rainbow is well below the cap
+    assert!(limit_hue_period_ms(10_000, DEFAULT_HUE_RATE_CAP) == 10_000);
+    assert!(limit_hue_period_frames(1_000, DEFAULT_HUE_RATE_CAP) == 1_000);
+    // a cap that doesn't divide evenly rounds the period up: 3 turns per second is 333.3ms, so 334ms / 34 frames
+    assert!(limit_hue_period_ms(1, 3.0) == 334);
+    assert!(limit_hue_period_frames(1, 3.0) == 34);
+    assert!(
+        limit_hue_period_ms(0, 1_000.0) == 100,
+        "the cap is not clamped"
+    );
+    assert!(clamp_hue_rate_cap(f32::NAN) == DEFAULT_HUE_RATE_CAP);
+};
@@ -0,0 +1,209 @@
+//! hue_scroll.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The hue_scroll module contains the HueScroll struct which implements the optional hue auto-scroll: on the Hue page
+//! the pot only turns so far, so holding it pinned at an extreme for HUE_SCROLL_DELAY_MS starts the hue scrolling
+//! around the color wheel (forward at the top of the travel, backward at the bottom), wrapping, for as long as the pot
+//! stays there. Any hue can then be reached without running out of rotation.
+//!
+//! Moving the pot off the extreme stops the scroll. The hue is then wherever the scroll left it rather than where the
+//! pot points, so the main.rs event loop has the pot pick up the hue again (see pot_mapping.rs) before it writes.
+//!
+//! The scroll is limited by the hue rate cap like every other hue animation (see hue_rate.rs).
+
+use super::hue_rate::limit_hue_period_ms;
+
+/// Constants
+const HUE_SCROLL_MARGIN: f32 = 0.01; // pot within 1% of either end of its travel counts as pinned there
+pub const HUE_SCROLL_DELAY_MS: u32 = 1_000; // the pot has to be pinned for a second before the hue starts scrolling
+pub const DEFAULT_HUE_SCROLL_PERIOD_MS: u32 = 10_000; // one trip around the color wheel every 10 seconds
+const MIN_HUE_SCROLL_PERIOD_MS: u32 = 2_000; // faster scrolls overshoot the hue before the pot can be released
+
+/// The end of its travel the pot is pinned at
+///
+/// 1. Top: scroll the hue forward
+/// 2. Bottom: scroll the hue backward
+#[derive(Clone, Copy)]
+pub enum Extreme {
+    Top,
+    Bottom,
+}
+
+/// PUBLIC
+/// The pinned-extreme detection: the end of its travel the pot percentage [0,1] is within HUE_SCROLL_MARGIN of, None
+/// anywhere in between
+pub const fn pinned_extreme(pot: f32) -> Option<Extreme> {
+    if pot >= 1.0 - HUE_SCROLL_MARGIN {
+        Some(Extreme::Top)
+    } else if pot <= HUE_SCROLL_MARGIN {
+        Some(Extreme::Bottom)
+    } else {
+        None
+    }
+}
+
+/// What the hue should do for the auto-scroll
+///
+/// 1. Step: move the hue by the given (signed) turns of the color wheel, wrapping
+/// 2. Released: the scroll has ended, the pot has to pick up the hue again before it writes
+#[derive(Clone, Copy)]
+pub enum ScrollUpdate {
+    Step(f32),
+    Released,
+}
+
+/// HueScroll struct declaration: Note all fields are private
+///
+/// 1. period_ms: time one trip around the color wheel takes, None while the auto-scroll is off
+/// 2. pinned: the extreme the pot is pinned at and the SYSTEM_CLOCK timestamp it got there, None while it is not
+/// 3. stepped_us: SYSTEM_CLOCK timestamp up to which the hue has been scrolled, None until the scroll starts
+pub struct HueScroll {
+    period_ms: Option<u32>,
+    pinned: Option<(Extreme, u32)>,
+    stepped_us: Option<u32>,
+}
+
+/// Impl HueScroll
+impl HueScroll {
+    /// PUBLIC
+    /// Generate a new HueScroll with the auto-scroll off
+    pub const fn new() -> Self {
+        HueScroll {
+            period_ms: None,
+            pinned: None,
+            stepped_us: None,
+        }
+    }
+
+    /// PUBLIC
+    /// Turn the auto-scroll on with one trip around the color wheel every period_ms (at least
+    /// MIN_HUE_SCROLL_PERIOD_MS), or off for None. A running scroll is released at the next poll() once off
+    pub fn set_period_ms(&mut self, period_ms: Option<u32>) {
+        self.period_ms = period_ms.map(|period_ms| period_ms.max(MIN_HUE_SCROLL_PERIOD_MS));
+    }
+
+    /// PUBLIC
+    /// return the time one trip around the color wheel takes, None while the auto-scroll is off
+    pub fn period_ms(&self) -> Option<u32> {
+        self.period_ms
+    }
+
+    /// PUBLIC
+    /// Advance the auto-scroll to now_us, extreme being the pinned_extreme() of the pot (None whenever the pot may not
+    /// write the hue, e.g. on another page). Returns the hue Step since the last poll once the pot has been pinned for
+    /// HUE_SCROLL_DELAY_MS (scrolling no faster than hue_rate_cap turns per second), Released once when a scroll ends,
+    /// and None otherwise. Timestamps are compared with wrap-around.
+    pub const fn poll(
+        &mut self,
+        extreme: Option<Extreme>,
+        now_us: u32,
+        hue_rate_cap: f32,
+    ) -> Option<ScrollUpdate> {
+        let (Some(period_ms), Some(extreme)) = (self.period_ms, extreme) else {
+            self.pinned = None;
+            return self.release();
+        };
+        let since_us = match self.pinned {
+            Some((pinned, since_us))
+                if matches!(
+                    (pinned, extreme),
+                    (Extreme::Top, Extreme::Top) | (Extreme::Bottom, Extreme::Bottom)
+                ) =>
+            {
+                since_us
+            }
+            // newly pinned, or straight across to the other extreme
+            _ => {
+                self.pinned = Some((extreme, now_us));
+                return self.release();
+            }
+        };
+        if now_us.wrapping_sub(since_us) < HUE_SCROLL_DELAY_MS * 1000 {
+            return None;
+        }
+
+        let from_us = match self.stepped_us {
+            Some(stepped_us) => stepped_us,
+            None => since_us.wrapping_add(HUE_SCROLL_DELAY_MS * 1000),
+        };
+        self.stepped_us = Some(now_us);
+        let period_us = limit_hue_period_ms(period_ms, hue_rate_cap) as f32 * 1000.0;
+        let turns = now_us.wrapping_sub(from_us) as f32 / period_us;
+        Some(ScrollUpdate::Step(match extreme {
+            Extreme::Top => turns,
+            Extreme::Bottom => -turns,
+        }))
+    }
+
+    /// PRIVATE
+    /// End any running scroll: Released if one was running, None otherwise
+    const fn release(&mut self) -> Option<ScrollUpdate> {
+        match self.stepped_us.take() {
+            Some(_) => Some(ScrollUpdate::Released),
+            None => None,
+        }
+    }
+}
+
+// only the ends of the pot travel count as pinned
+const _: () = {
+    assert!(matches!(pinned_extreme(1.0), Some(Extreme::Top)));
+    assert!(matches!(pinned_extreme(0.995), Some(Extreme::Top)));
+    assert!(matches!(pinned_extreme(0.0), Some(Extreme::Bottom)));
+    assert!(
+        pinned_extreme(0.5).is_none() && pinned_extreme(0.95).is_none(),
+        "a pot short of its end counted as pinned"
+    );
+};
+
+// a pinned pot scrolls the hue forward (backward at the bottom) only after the delay, at one turn per period, and
+// releasing it ends the scroll once
+const _: () = {
+    let cap = super::hue_rate::DEFAULT_HUE_RATE_CAP;
+    let mut scroll = HueScroll::new();
+    assert!(
+        scroll.poll(Some(Extreme::Top), 0, cap).is_none(),
+        "scrolled while off"
+    );
+
+    scroll.period_ms = Some(DEFAULT_HUE_SCROLL_PERIOD_MS);
+    let pinned_us = u32::MAX - 500_000; // across the clock wrap
+    assert!(scroll.poll(Some(Extreme::Top), pinned_us, cap).is_none());
+    let start_us = pinned_us.wrapping_add(HUE_SCROLL_DELAY_MS * 1000);
+    assert!(
+        scroll
+            .poll(Some(Extreme::Top), start_us.wrapping_sub(1), cap)
+            .is_none(),
+        "scrolled before the delay"
+    );
+    assert!(matches!(
+        scroll.poll(Some(Extreme::Top), start_us, cap),
+        Some(ScrollUpdate::Step(0.0))
+    ));
+    let quarter_us = start_us.wrapping_add(DEFAULT_HUE_SCROLL_PERIOD_MS / 4 * 1000);
+    assert!(matches!(
+        scroll.poll(Some(Extreme::Top), quarter_us, cap),
+        Some(ScrollUpdate::Step(0.25))
+    ));
+    assert!(matches!(
+        scroll.poll(None, quarter_us + 100_000, cap),
+        Some(ScrollUpdate::Released)
+    ));
+    assert!(
+        scroll.poll(None, quarter_us + 200_000, cap).is_none(),
+        "one scroll released twice"
+    );
+
+    let bottom_us = 10_000_000;
+    assert!(scroll.poll(Some(Extreme::Bottom), bottom_us, cap).is_none());
+    let half_us = bottom_us + HUE_SCROLL_DELAY_MS * 1000 + DEFAULT_HUE_SCROLL_PERIOD_MS / 2 * 1000;
+    assert!(
+        matches!(
+            scroll.poll(Some(Extreme::Bottom), half_us, cap),
+            Some(ScrollUpdate::Step(-0.5))
+        ),
+        "the bottom of the pot does not scroll backward"
+    );
+};
@@ -0,0 +1,64 @@
+//! hue_warp.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The hue_warp module corrects for an RGB LED whose primaries are off-spec (e.g. a "red" die leaning orange), so that
+//! a requested hue looks right. The color wheel is warped piecewise linearly: the requested primaries (red 0, green
+//! 1/3, blue 2/3) land on the calibrated hues of a PrimaryCalibration, and the hues between two primaries are spread
+//! evenly between their calibrated hues. The ColorControler applies the warp to the rendered color only, base_color
+//! keeps the requested hue.
+
+use super::hsv_rgb_convert::normalize_hue;
+
+/// Constants
+const PRIMARY_COUNT: usize = 3; // red, green, blue
+const SEGMENT: f32 = 1.0 / PRIMARY_COUNT as f32; // requested hue span between two neighboring primaries
+
+/// PrimaryCalibration struct declaration: Note all fields are private
+///
+/// 1. hues: the calibrated hue [0,1) of the red, green, and blue primaries, in that order around the color wheel
+#[derive(Clone, Copy)]
+pub struct PrimaryCalibration {
+    hues: [f32; PRIMARY_COUNT],
+}
+
+/// Impl PrimaryCalibration
+impl PrimaryCalibration {
+    /// PUBLIC
+    /// Generate a new PrimaryCalibration from the hues the red, green, and blue primaries should land on (each wrapped
+    /// into [0,1)). Returns None unless they are distinct and go red -> green -> blue increasing around the color wheel,
+    /// since otherwise the warp would fold the color wheel back on itself.
+    pub fn new(r_hue: f32, g_hue: f32, b_hue: f32) -> Option<Self> {
+        let calibration = PrimaryCalibration {
+            hues: [
+                normalize_hue(r_hue),
+                normalize_hue(g_hue),
+                normalize_hue(b_hue),
+            ],
+        };
+        let total: f32 = (0..PRIMARY_COUNT).map(|i| calibration.span(i)).sum();
+        if (total - 1.0).abs() < 1e-4 {
+            Some(calibration)
+        } else {
+            None
+        }
+    }
+
+    /// PRIVATE
+    /// The calibrated hue span, in (0,1], from primary i to the primary after it (wrapping from blue back to red)
+    fn span(&self, i: usize) -> f32 {
+        let span = self.hues[(i + 1) % PRIMARY_COUNT] - self.hues[i];
+        if span <= 0.0 { span + 1.0 } else { span }
+    }
+
+    /// PUBLIC
+    /// Warp a requested hue onto the calibrated color wheel. A requested primary maps exactly onto its calibrated hue
+    /// and the hues between two primaries are interpolated linearly between theirs. Returned in [0,1)
+    pub fn warp_hue(&self, h: f32) -> f32 {
+        let h = normalize_hue(h);
+        let i = ((h / SEGMENT) as usize).min(PRIMARY_COUNT - 1);
+        let t = (h - i as f32 * SEGMENT) / SEGMENT;
+        normalize_hue(self.hues[i] + t * self.span(i))
+    }
+}
@@ -0,0 +1,99 @@
+//! inactivity.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The inactivity module contains the Inactivity struct which implements the optional "return to default after
+//! inactivity" behavior for shared/demo devices. Every interaction (pot movement, button press, or RTT command)
+//! restarts the inactivity timer, and once no interaction has happened for the configured timeout the main.rs event
+//! loop fades the color back to the configured default color. The revert happens once per idle period.
+
+use super::hsv_rgb_convert::Hsv;
+
+/// Constants
+pub const MAX_INACTIVITY_TIMEOUT_MS: u32 = 60 * 60 * 1000; // 1 hour, safely below the ~71 minute SYSTEM_CLOCK wrap
+pub const INACTIVITY_FADE_MS: u32 = 2000; // the revert fades to the default color over 2 seconds
+
+/// PUBLIC
+/// The inactivity decision: has at least timeout_ms passed between last_activity_us and now_us? Timestamps are
+/// compared with wrap-around.
+pub fn inactivity_elapsed(now_us: u32, last_activity_us: u32, timeout_ms: u32) -> bool {
+    now_us.wrapping_sub(last_activity_us) >= timeout_ms.saturating_mul(1000)
+}
+
+/// Inactivity struct declaration: Note all fields are private
+///
+/// 1. timeout_ms: time without interaction before reverting, None when the option is disabled
+/// 2. default: the color reverted to
+/// 3. last_activity_us: SYSTEM_CLOCK timestamp of the most recent interaction
+/// 4. reverted: whether the revert for the current idle period has already happened
+pub struct Inactivity {
+    timeout_ms: Option<u32>,
+    default: Hsv,
+    last_activity_us: u32,
+    reverted: bool,
+}
+
+/// Impl Inactivity
+impl Inactivity {
+    /// PUBLIC
+    /// Generate a new, disabled, Inactivity reverting to the given default color
+    pub const fn new(default: Hsv) -> Self {
+        Inactivity {
+            timeout_ms: None,
+            default,
+            last_activity_us: 0,
+            reverted: false,
+        }
+    }
+
+    /// PUBLIC
+    /// Enable the option with the given timeout (clamped to MAX_INACTIVITY_TIMEOUT_MS), or disable it with None.
+    /// The timer restarts from now_us.
+    pub fn set_timeout(&mut self, timeout_ms: Option<u32>, now_us: u32) {
+        self.timeout_ms = timeout_ms.map(|ms| ms.min(MAX_INACTIVITY_TIMEOUT_MS));
+        self.touch(now_us);
+    }
+
+    /// PUBLIC
+    /// return the timeout, None when the option is disabled
+    pub fn timeout_ms(&self) -> Option<u32> {
+        self.timeout_ms
+    }
+
+    /// PUBLIC
+    /// Set the color reverted to
+    pub fn set_default(&mut self, default: Hsv) {
+        self.default = default;
+    }
+
+    /// PUBLIC
+    /// return the color reverted to
+    pub fn default_color(&self) -> Hsv {
+        self.default
+    }
+
+    /// PUBLIC
+    /// return the SYSTEM_CLOCK timestamp of the most recent interaction
+    pub fn last_activity_us(&self) -> u32 {
+        self.last_activity_us
+    }
+
+    /// PUBLIC
+    /// Record an interaction at now_us, restarting the timer
+    pub fn touch(&mut self, now_us: u32) {
+        self.last_activity_us = now_us;
+        self.reverted = false;
+    }
+
+    /// PUBLIC
+    /// Returns the default color once the timeout has elapsed without interaction (only once per idle period), or None
+    pub fn poll(&mut self, now_us: u32) -> Option<Hsv> {
+        let timeout_ms = self.timeout_ms?;
+        if self.reverted || !inactivity_elapsed(now_us, self.last_activity_us, timeout_ms) {
+            return None;
+        }
+        self.reverted = true;
+        Some(self.default)
+    }
+}
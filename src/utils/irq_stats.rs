@@ -0,0 +1,81 @@
+//! irq_stats.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The irq_stats module counts how often each interrupt handler runs, for diagnosing the real-time behavior (e.g. the
+//! PWM interrupt frequency, or the ADC refresh and display scan timing). Every handler increments its counter with a
+//! single atomic add, and the main.rs event loop turns the counts into per-second rates when asked over RTT.
+
+use core::sync::atomic::{AtomicU32, Ordering::SeqCst};
+
+/// Constants
+pub const IRQ_COUNT: usize = 4; // number of counted interrupts
+
+/// C-style enum of the counted interrupts
+///
+/// 1. Timer0: the nonblocking display row scan (`display` feature only)
+/// 2. Pwm: the RGB LED PWM, TIMER2 (or PWM0 with the `hw-pwm` feature)
+/// 3. Timer3: the ADC accumulator refresh
+/// 4. Gpiote: the A/B button presses
+#[derive(Clone, Copy)]
+pub enum Irq {
+    Timer0 = 0,
+    Pwm = 1,
+    Timer3 = 2,
+    Gpiote = 3,
+}
+
+/// Impl Irq
+impl Irq {
+    /// Every counted interrupt, in report order
+    pub const ALL: [Irq; IRQ_COUNT] = [Irq::Timer0, Irq::Pwm, Irq::Timer3, Irq::Gpiote];
+
+    /// PUBLIC
+    /// Name of the interrupt, as printed over RTT
+    pub fn name(&self) -> &'static str {
+        match self {
+            Irq::Timer0 => "TIMER0",
+            Irq::Pwm if cfg!(feature = "hw-pwm") => "PWM0",
+            Irq::Pwm => "TIMER2",
+            Irq::Timer3 => "TIMER3",
+            Irq::Gpiote => "GPIOTE",
+        }
+    }
+}
+
+/// PUBLIC
+/// The rate, in interrupts per second, of count interrupts over elapsed_us (an elapsed time of 0 counts as 1us)
+pub fn rate_per_second(count: u32, elapsed_us: u32) -> f32 {
+    count as f32 * 1_000_000.0 / elapsed_us.max(1) as f32
+}
+
+/// IrqCounters struct declaration: Note all fields are private
+///
+/// 1. counts: interrupts handled since the last take(), indexed by Irq
+pub struct IrqCounters {
+    counts: [AtomicU32; IRQ_COUNT],
+}
+
+/// Impl IrqCounters
+impl IrqCounters {
+    /// PUBLIC
+    /// Generate a new IrqCounters with every count at 0. Const so it can be a static shared with the handlers
+    pub const fn new() -> Self {
+        IrqCounters {
+            counts: [const { AtomicU32::new(0) }; IRQ_COUNT],
+        }
+    }
+
+    /// PUBLIC
+    /// Count one run of the irq handler. Called at the top of each interrupt handler in main.rs
+    pub fn record(&self, irq: Irq) {
+        self.counts[irq as usize].fetch_add(1, SeqCst);
+    }
+
+    /// PUBLIC
+    /// return the number of irq interrupts since the last take(), resetting it to 0
+    pub fn take(&self, irq: Irq) -> u32 {
+        self.counts[irq as usize].swap(0, SeqCst)
+    }
+}
@@ -0,0 +1,97 @@
+//! kelvin.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The kelvin module converts a color temperature in Kelvin into the RGB tint of a blackbody (e.g. a warm 2700K bulb
+//! or cool 6500K daylight) for the RTT `kelvin` command, which sets the LED's white point through
+//! ColorControler::set_white_point_kelvin. The conversion interpolates linearly in the KELVIN_TABLE of sRGB blackbody
+//! colors (every 500K from MIN_KELVIN to MAX_KELVIN, brightest channel at full scale), since the firmware has no
+//! logarithm or power functions for the usual curve fit. Temperatures outside the table clamp to its ends.
+
+use super::hsv_rgb_convert::Rgb;
+
+/// Constants
+pub const MIN_KELVIN: f32 = 1_000.0; // candle light, the warmest white point
+pub const MAX_KELVIN: f32 = 10_000.0; // clear blue sky, the coolest white point
+pub const NEUTRAL_KELVIN: f32 = 6_500.0; // D65, the sRGB white: no tint
+const KELVIN_STEP: f32 = 500.0; // spacing of the KELVIN_TABLE entries
+const KELVIN_TABLE: [[u8; 3]; 19] = [
+    [255, 56, 0],    // 1000K
+    [255, 109, 0],   // 1500K
+    [255, 137, 18],  // 2000K
+    [255, 161, 72],  // 2500K
+    [255, 180, 107], // 3000K
+    [255, 196, 137], // 3500K
+    [255, 209, 163], // 4000K
+    [255, 219, 186], // 4500K
+    [255, 228, 206], // 5000K
+    [255, 236, 224], // 5500K
+    [255, 243, 239], // 6000K
+    [255, 249, 253], // 6500K
+    [245, 243, 255], // 7000K
+    [235, 238, 255], // 7500K
+    [227, 233, 255], // 8000K
+    [220, 229, 255], // 8500K
+    [214, 225, 255], // 9000K
+    [208, 222, 255], // 9500K
+    [204, 219, 255], // 10000K
+]; // sRGB color of a blackbody, from MIN_KELVIN in KELVIN_STEP steps to MAX_KELVIN
+
+/// PUBLIC
+/// Clamp a color temperature into [MIN_KELVIN,MAX_KELVIN], NaN giving NEUTRAL_KELVIN
+pub const fn clamp_kelvin(kelvin: f32) -> f32 {
+    if kelvin.is_nan() {
+        NEUTRAL_KELVIN
+    } else {
+        kelvin.clamp(MIN_KELVIN, MAX_KELVIN)
+    }
+}
+
+/// PUBLIC
+/// The RGB tint [0,1] of a blackbody at kelvin (clamped with clamp_kelvin()), its brightest channel at 1
+pub const fn kelvin_to_rgb(kelvin: f32) -> Rgb {
+    let position = (clamp_kelvin(kelvin) - MIN_KELVIN) / KELVIN_STEP;
+    let mut index = position as usize;
+    if index >= KELVIN_TABLE.len() - 1 {
+        index = KELVIN_TABLE.len() - 2;
+    }
+    let frac = position - index as f32;
+
+    const fn lerp(low: u8, high: u8, frac: f32) -> f32 {
+        (low as f32 + (high as f32 - low as f32) * frac) / 255.0
+    }
+    let [low, high] = [KELVIN_TABLE[index], KELVIN_TABLE[index + 1]];
+    Rgb {
+        r: lerp(low[0], high[0], frac),
+        g: lerp(low[1], high[1], frac),
+        b: lerp(low[2], high[2], frac),
+    }
+}
+
+// warm white points lean red and cool ones blue, 6500K is (almost) neutral, and targets outside [MIN_KELVIN,MAX_KELVIN]
+// clamp to the nearest end
+const _: () = {
+    let warm = kelvin_to_rgb(2_700.0);
+    assert!(
+        warm.r == 1.0 && warm.r > warm.g && warm.g > warm.b,
+        "2700K is not warm"
+    );
+    let neutral = kelvin_to_rgb(NEUTRAL_KELVIN);
+    assert!(
+        neutral.r > 0.95 && neutral.g > 0.95 && neutral.b > 0.95,
+        "6500K is tinted"
+    );
+    let cool = kelvin_to_rgb(9_000.0);
+    assert!(
+        cool.b == 1.0 && cool.b > cool.g && cool.g > cool.r,
+        "9000K is not cool"
+    );
+
+    let below = kelvin_to_rgb(500.0);
+    let warmest = kelvin_to_rgb(MIN_KELVIN);
+    assert!(below.r == warmest.r && below.g == warmest.g && below.b == warmest.b);
+    let above = kelvin_to_rgb(40_000.0);
+    assert!(above.r == 204.0 / 255.0 && above.g == 219.0 / 255.0 && above.b == 1.0);
+    assert!(clamp_kelvin(f32::NAN) == NEUTRAL_KELVIN);
+};
@@ -0,0 +1,86 @@
+//! latency.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The latency module contains the LatencyProbe struct which measures the delay between a significant pot change
+//! being applied to the ColorControler (timestamped by the main.rs event loop) and the start of the first PWM frame
+//! which renders it (timestamped by the TIMER2 interrupt). All timestamps are in usecs from the free-running
+//! system clock and are compared with wrap-around so the 32-bit counter rolling over (~71 minutes) is harmless.
+
+/// PUBLIC
+/// Compute the latency between applied_us and rendered_us, taking counter wrap-around into account. Returns None
+/// if rendered_us was taken before applied_us (i.e. the frame started before the change was applied).
+pub fn latency_us(applied_us: u32, rendered_us: u32) -> Option<u32> {
+    let elapsed = rendered_us.wrapping_sub(applied_us);
+    if elapsed <= u32::MAX / 2 {
+        Some(elapsed)
+    } else {
+        None
+    }
+}
+
+/// LatencyProbe struct declaration: Note all fields are private
+///
+/// 1. pending: timestamp of the last applied change that has not yet been seen rendered
+/// 2. last: most recent latency measurement
+/// 3. min: smallest latency measured since the last reset
+/// 4. max: largest latency measured since the last reset
+/// 5. count: number of measurements since the last reset
+pub struct LatencyProbe {
+    pending: Option<u32>,
+    last: u32,
+    min: u32,
+    max: u32,
+    count: u32,
+}
+
+/// Impl LatencyProbe
+impl LatencyProbe {
+    /// PUBLIC
+    /// Generate a new LatencyProbe with no measurements
+    pub const fn new() -> Self {
+        LatencyProbe {
+            pending: None,
+            last: 0,
+            min: u32::MAX,
+            max: 0,
+            count: 0,
+        }
+    }
+
+    /// PUBLIC
+    /// Record that a significant change was applied at applied_us. A still-pending change is superseded.
+    pub fn start(&mut self, applied_us: u32) {
+        self.pending = Some(applied_us);
+    }
+
+    /// PUBLIC
+    /// Offer the start time of the most recently rendered frame. If it started after the pending change was applied,
+    /// the latency is recorded and returned, otherwise None.
+    pub fn observe(&mut self, frame_start_us: u32) -> Option<u32> {
+        let latency = latency_us(self.pending?, frame_start_us)?;
+        self.pending = None;
+        self.last = latency;
+        self.min = self.min.min(latency);
+        self.max = self.max.max(latency);
+        self.count += 1;
+        Some(latency)
+    }
+
+    /// PUBLIC
+    /// Returns (last, min, max, count) of the measurements, or None if nothing has been measured yet
+    pub fn stats(&self) -> Option<(u32, u32, u32, u32)> {
+        if self.count == 0 {
+            None
+        } else {
+            Some((self.last, self.min, self.max, self.count))
+        }
+    }
+
+    /// PUBLIC
+    /// Discard all measurements
+    pub fn reset(&mut self) {
+        *self = LatencyProbe::new();
+    }
+}
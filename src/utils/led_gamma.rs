@@ -0,0 +1,127 @@
+//! led_gamma.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The led_gamma module contains the gamma curve the RGB LED's duty cycles are shaped by. The eye perceives brightness
+//! roughly logarithmically, so driving each channel's on-time linearly from the color makes the low Values look washed
+//! out and crowds the visible steps into the dark end. ColorControler maps every channel level through
+//! level^gamma once per frame, as the frame's duty cycles are computed, while the HSV math before it stays linear.
+//! The exponent is set with the RTT `ledgamma` command, PERCEPTUAL_LED_GAMMA by default and 1 giving the linear mapping.
+//!
+//! The curve pushes the dim levels below the PWM's resolution: with the software PWM's BRIGHTNESS_STEPS duty steps any
+//! level below ~0.09 lands below half a step at PERCEPTUAL_LED_GAMMA. The PFM fallback (see pfm_level in
+//! color_control.rs) therefore runs by default with a minimum pulse of one duty step, lighting such a level for a
+//! whole step in some frames instead of rounding it to off, so the bottom of the Value pot, the standby shimmer, and
+//! the minimum glow stay lit.
+//!
+//! core has no powf without std, so the curve is computed as 2^(gamma * log2(level)) from short series, accurate to
+//! well within one BRIGHTNESS_STEPS bin (see color_control.rs).
+
+/// Constants
+pub const PERCEPTUAL_LED_GAMMA: f32 = 2.2; // the usual perceptual exponent
+pub const DEFAULT_LED_GAMMA: f32 = PERCEPTUAL_LED_GAMMA; // evenly spaced Value steps to the eye
+pub const MIN_LED_GAMMA: f32 = 1.0; // linear, the duty cycles follow the color exactly
+pub const MAX_LED_GAMMA: f32 = 3.0; // steeper curves leave most of the Value axis near dark
+const LN_2: f32 = core::f32::consts::LN_2;
+
+/// PUBLIC
+/// Clamp a requested exponent into [MIN_LED_GAMMA,MAX_LED_GAMMA], NaN giving the default
+pub const fn clamp_led_gamma(gamma: f32) -> f32 {
+    if gamma.is_nan() {
+        DEFAULT_LED_GAMMA
+    } else {
+        gamma.clamp(MIN_LED_GAMMA, MAX_LED_GAMMA)
+    }
+}
+
+/// PRIVATE
+/// log2 of a positive, normal x: the exponent from the bits, plus the mantissa's log from the atanh series
+/// ln(m) = 2 (t + t^3/3 + t^5/5 + t^7/7 + t^9/9) with t = (m - 1) / (m + 1) in [0,1/3)
+const fn log2(x: f32) -> f32 {
+    let bits = x.to_bits();
+    let exponent = ((bits >> 23) & 0xff) as i32 - 127;
+    let mantissa = f32::from_bits((bits & 0x007f_ffff) | 0x3f80_0000);
+    let t = (mantissa - 1.0) / (mantissa + 1.0);
+    let t2 = t * t;
+    let ln = 2.0 * t * (1.0 + t2 * (1.0 / 3.0 + t2 * (1.0 / 5.0 + t2 * (1.0 / 7.0 + t2 / 9.0))));
+    exponent as f32 + ln / LN_2
+}
+
+/// PRIVATE
+/// 2^y for y <= 0: a power of two built from the bits for the whole part, times the Taylor series of e^(f ln 2) for
+/// the fraction f in [0,1). Underflows to 0
+const fn exp2(y: f32) -> f32 {
+    let mut whole = y as i32;
+    if whole as f32 > y {
+        whole -= 1;
+    }
+    if whole < -126 {
+        return 0.0;
+    }
+    let x = (y - whole as f32) * LN_2;
+    let fraction = 1.0
+        + x * (1.0
+            + x / 2.0
+                * (1.0
+                    + x / 3.0
+                        * (1.0 + x / 4.0 * (1.0 + x / 5.0 * (1.0 + x / 6.0 * (1.0 + x / 7.0))))));
+    f32::from_bits(((whole + 127) as u32) << 23) * fraction
+}
+
+/// PUBLIC
+/// Shape one channel's level [0,1] by the gamma curve, returning level^gamma in [0,1]. Off and full stay exactly off
+/// and full, and a gamma of 1 passes the level through unchanged
+pub const fn gamma_curve(level: f32, gamma: f32) -> f32 {
+    if level <= 0.0 {
+        return 0.0;
+    }
+    if level >= 1.0 {
+        return 1.0;
+    }
+    if gamma == 1.0 {
+        return level;
+    }
+    exp2(gamma * log2(level)).clamp(0.0, 1.0)
+}
+
+// the curve keeps off and full, matches level^2.2 closely enough never to move a level by a tenth of a
+// BRIGHTNESS_STEPS bin, and never reorders two levels
+const _: () = {
+    assert!(gamma_curve(0.0, PERCEPTUAL_LED_GAMMA) == 0.0);
+    assert!(gamma_curve(1.0, PERCEPTUAL_LED_GAMMA) == 1.0);
+    assert!(gamma_curve(0.37, 1.0) == 0.37, "a gamma of 1 is not linear");
+
+    // (level, level^2.2)
+    let cases: [(f32, f32); 6] = [
+        (0.01, 0.000_039_8),
+        (0.1, 0.006_309_6),
+        (0.25, 0.047_366),
+        (0.5, 0.217_638),
+        (0.75, 0.530_941),
+        (0.99, 0.978_132),
+    ];
+    let mut i = 0;
+    while i < cases.len() {
+        let (level, expected) = cases[i];
+        let shaped = gamma_curve(level, PERCEPTUAL_LED_GAMMA);
+        assert!(
+            shaped - expected < 0.001 && expected - shaped < 0.001,
+            "gamma curve strays from level^2.2"
+        );
+        i += 1;
+    }
+
+    let mut step = 1;
+    while step <= 100 {
+        assert!(
+            gamma_curve(step as f32 / 100.0, PERCEPTUAL_LED_GAMMA)
+                >= gamma_curve((step - 1) as f32 / 100.0, PERCEPTUAL_LED_GAMMA),
+            "gamma curve reorders levels"
+        );
+        step += 1;
+    }
+
+    assert!(clamp_led_gamma(0.5) == MIN_LED_GAMMA && clamp_led_gamma(9.0) == MAX_LED_GAMMA);
+    assert!(clamp_led_gamma(f32::NAN) == DEFAULT_LED_GAMMA);
+};
@@ -0,0 +1,74 @@
+//! long_press.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The long_press module contains the LongPress struct, the hold detection shared by the button gestures: the soft
+//! power toggle (see power_toggle.rs) and the demo reel (see demo_reel.rs). The main.rs event loop polls it every
+//! refresh with whether the gesture's button is held, and it fires once the hold has lasted LONG_PRESS_MS, only once
+//! per hold however long it lasts.
+
+/// Constants
+pub const LONG_PRESS_MS: u32 = 1_500; // hold a button this long for a long press gesture
+
+/// LongPress struct declaration: Note all fields are private
+///
+/// 1. pressed_us: SYSTEM_CLOCK timestamp at which the current hold of the button began, None while released
+/// 2. fired: whether the current hold has already completed a long press
+pub struct LongPress {
+    pressed_us: Option<u32>,
+    fired: bool,
+}
+
+/// Impl LongPress
+impl LongPress {
+    /// PUBLIC
+    /// Generate a new LongPress with the button released
+    pub const fn new() -> Self {
+        LongPress {
+            pressed_us: None,
+            fired: false,
+        }
+    }
+
+    /// PUBLIC
+    /// Advance the hold detection to now_us, held being whether the gesture's button is held. Returns true once the
+    /// button has been held for LONG_PRESS_MS (only once per hold). Timestamps are compared with wrap-around.
+    pub const fn poll(&mut self, held: bool, now_us: u32) -> bool {
+        let pressed_us = match (held, self.pressed_us) {
+            (false, _) => {
+                self.pressed_us = None;
+                self.fired = false;
+                return false;
+            }
+            (true, Some(pressed_us)) => pressed_us,
+            (true, None) => {
+                self.pressed_us = Some(now_us);
+                now_us
+            }
+        };
+        if self.fired || now_us.wrapping_sub(pressed_us) < LONG_PRESS_MS * 1000 {
+            return false;
+        }
+        self.fired = true;
+        true
+    }
+}
+
+// a short hold never fires, a long hold fires exactly once, and releasing arms the next hold
+const _: () = {
+    let mut press = LongPress::new();
+    assert!(!press.poll(true, 0));
+    assert!(!press.poll(true, LONG_PRESS_MS * 1000 - 1));
+    assert!(!press.poll(false, LONG_PRESS_MS * 1000));
+
+    assert!(!press.poll(true, 10_000_000));
+    assert!(press.poll(true, 10_000_000 + LONG_PRESS_MS * 1000));
+    assert!(
+        !press.poll(true, 20_000_000),
+        "one hold completed two long presses"
+    );
+    assert!(!press.poll(false, 21_000_000));
+    assert!(!press.poll(true, 22_000_000));
+    assert!(press.poll(true, 22_000_000 + LONG_PRESS_MS * 1000));
+};
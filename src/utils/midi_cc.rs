@@ -0,0 +1,144 @@
+//! midi_cc.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The midi_cc module turns the board into a simple hardware color controller for a DAW or lighting desk: in the MIDI
+//! mode (RTT `midi on`) the UART link (see uart_link.rs) sends the H, S, and V axes as MIDI control change messages at
+//! the MIDI baud rate instead of the daisy-chain packets, one message per axis whose 7-bit value has changed.
+//!
+//! Message layout (CC_MESSAGE_SIZE bytes), the standard MIDI control change without running status:
+//! 1. byte 0: CC_STATUS with the channel (0-15, shown to users as 1-16) in the low nibble
+//! 2. byte 1: the controller number of the axis (0-127)
+//! 3. byte 2: the axis value [0,1] mapped to 0-127
+//!
+//! The e15 TX pin needs the usual MIDI OUT circuit (two 220 ohm resistors into a DIN socket, or a USB MIDI adapter
+//! accepting 3.3V TTL serial).
+
+use super::hsv_rgb_convert::Hsv;
+
+/// Constants
+pub const CC_MESSAGE_SIZE: usize = 3; // status, controller, and value bytes
+const CC_STATUS: u8 = 0xB0; // control change status byte, channel in the low nibble
+pub const MIDI_CHANNEL_COUNT: u8 = 16; // channels 1-16
+pub const MAX_CC_NUMBER: u8 = 127; // controller numbers are 7 bit
+pub const DEFAULT_MIDI_CHANNEL: u8 = 1; // first MIDI channel
+pub const DEFAULT_CC_NUMBERS: [u8; 3] = [20, 21, 22]; // H, S, and V on the first undefined general purpose controllers
+
+/// PUBLIC
+/// Map an axis value [0,1] to a 7-bit control change value 0-127, rounding to nearest
+pub const fn cc_value(component: f32) -> u8 {
+    (component.clamp(0.0, 1.0) * MAX_CC_NUMBER as f32 + 0.5) as u8
+}
+
+/// PUBLIC
+/// Frame a control change message: channel 1-16 (as shown to users), controller and value 0-127. Out of range
+/// arguments are masked so the message always stays valid MIDI
+pub const fn cc_message(channel: u8, controller: u8, value: u8) -> [u8; CC_MESSAGE_SIZE] {
+    [
+        CC_STATUS | (channel.wrapping_sub(1) & 0x0F),
+        controller & 0x7F,
+        value & 0x7F,
+    ]
+}
+
+/// MidiCc struct declaration: Note all fields are private
+///
+/// 1. channel: MIDI channel 1-16 the messages are sent on
+/// 2. controllers: controller numbers of the H, S, and V axes
+/// 3. last_sent: 7-bit value of each axis last sent, None before its first message
+#[derive(Clone, Copy)]
+pub struct MidiCc {
+    channel: u8,
+    controllers: [u8; 3],
+    last_sent: [Option<u8>; 3],
+}
+
+/// Impl MidiCc
+impl MidiCc {
+    /// PUBLIC
+    /// Generate a new MidiCc sending the H, S, and V axes as controllers on channel, None unless the channel is 1-16
+    /// and every controller 0-127
+    pub const fn new(channel: u8, controllers: [u8; 3]) -> Option<Self> {
+        if channel == 0
+            || channel > MIDI_CHANNEL_COUNT
+            || controllers[0] > MAX_CC_NUMBER
+            || controllers[1] > MAX_CC_NUMBER
+            || controllers[2] > MAX_CC_NUMBER
+        {
+            return None;
+        }
+        Some(MidiCc {
+            channel,
+            controllers,
+            last_sent: [None; 3],
+        })
+    }
+
+    /// PUBLIC
+    /// The messages for hsv: one per axis whose 7-bit value differs from the last one sent, in H, S, V order, with
+    /// their count. The values are remembered as sent, so call mark_unsent() if transmitting them failed
+    pub const fn messages(&mut self, hsv: Hsv) -> ([[u8; CC_MESSAGE_SIZE]; 3], usize) {
+        let values = [cc_value(hsv.h), cc_value(hsv.s), cc_value(hsv.v)];
+        let mut messages = [[0; CC_MESSAGE_SIZE]; 3];
+        let mut count = 0;
+        let mut axis = 0;
+        while axis < 3 {
+            let changed = match self.last_sent[axis] {
+                Some(sent) => sent != values[axis],
+                None => true,
+            };
+            if changed {
+                messages[count] = cc_message(self.channel, self.controllers[axis], values[axis]);
+                self.last_sent[axis] = Some(values[axis]);
+                count += 1;
+            }
+            axis += 1;
+        }
+        (messages, count)
+    }
+
+    /// PUBLIC
+    /// Forget the values sent, so every axis is sent again with the next messages()
+    pub const fn mark_unsent(&mut self) {
+        self.last_sent = [None; 3];
+    }
+}
+
+// the framing: 0-1 spans 0-127, channel 1 is status 0xB0 and channel 16 0xBF, and only changed axes are re-sent
+const _: () = {
+    assert!(cc_value(0.0) == 0 && cc_value(1.0) == 127 && cc_value(0.5) == 64);
+    assert!(
+        cc_value(-1.0) == 0 && cc_value(2.0) == 127,
+        "cc_value() is not clamped"
+    );
+
+    let message = cc_message(1, 20, 64);
+    assert!(message[0] == 0xB0 && message[1] == 20 && message[2] == 64);
+    assert!(cc_message(16, 127, 127)[0] == 0xBF);
+    assert!(MidiCc::new(0, DEFAULT_CC_NUMBERS).is_none());
+    assert!(MidiCc::new(17, DEFAULT_CC_NUMBERS).is_none());
+    assert!(MidiCc::new(1, [20, 21, 128]).is_none());
+
+    let mut midi = match MidiCc::new(2, [1, 2, 3]) {
+        Some(midi) => midi,
+        None => panic!("valid MIDI settings rejected"),
+    };
+    let color = Hsv {
+        h: 0.0,
+        s: 0.5,
+        v: 1.0,
+    };
+    let (messages, count) = midi.messages(color);
+    assert!(count == 3, "the first messages() must send every axis");
+    assert!(messages[0][0] == 0xB1 && messages[0][1] == 1 && messages[0][2] == 0);
+    assert!(messages[1][1] == 2 && messages[1][2] == 64);
+    assert!(messages[2][1] == 3 && messages[2][2] == 127);
+    assert!(midi.messages(color).1 == 0, "unchanged axes were re-sent");
+
+    // a change within one 7-bit step sends nothing, a larger one only its own axis
+    let (_, count) = midi.messages(Hsv { s: 0.501, ..color });
+    assert!(count == 0);
+    let (messages, count) = midi.messages(Hsv { v: 0.0, ..color });
+    assert!(count == 1 && messages[0][1] == 3 && messages[0][2] == 0);
+};
@@ -1,3 +1,50 @@
+pub mod adc_channels;
+pub mod adc_filter;
+pub mod adc_scaling;
+pub mod ambient;
+pub mod app_config;
+pub mod arbitration;
+pub mod auto_balance;
+pub mod build_info;
+pub mod button_map;
+pub mod clock;
 pub mod color_control;
+pub mod color_match;
+pub mod color_rng;
+pub mod color_space;
+pub mod color_watch;
+pub mod commit_debounce;
+pub mod config;
+pub mod curve;
+pub mod debounce;
+pub mod demo_reel;
+pub mod display_sleep;
+pub mod effects;
+pub mod fade;
+pub mod fmt_buf;
 pub mod hsv_display;
-mod hsv_rgb_convert;
+pub mod hsv_rgb_convert;
+pub mod hue_rate;
+pub mod hue_scroll;
+pub mod hue_warp;
+pub mod inactivity;
+pub mod irq_stats;
+pub mod kelvin;
+pub mod latency;
+pub mod led_gamma;
+pub mod long_press;
+pub mod midi_cc;
+pub mod mode;
+pub mod persist;
+pub mod pot_mapping;
+pub mod power_toggle;
+pub mod presets;
+pub mod rtt_command;
+pub mod scheduler;
+pub mod serial_control;
+pub mod slideshow;
+pub mod standby;
+pub mod storage;
+pub mod timers;
+pub mod uart_link;
+pub mod value_floor;
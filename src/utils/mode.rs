@@ -0,0 +1,135 @@
+//! mode.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The mode module contains the Mode enum which organizes the growing set of operating modes into one predictable,
+//! enumerable list. Each Mode has a short name (used by the RTT `mode` command), a human readable description (used
+//! to print the RTT menu), and a defined next mode so the firmware can cycle through all of them in order.
+
+use super::color_control::ColorControler;
+use super::effects::Effect;
+
+/// Constants
+const RAINBOW_PERIOD_MS: u32 = 10_000; // one trip around the color wheel every 10 seconds
+const BREATHE_PERIOD_MS: u32 = 4_000; // one breath every 4 seconds
+const BREATHE_DEPTH: f32 = 0.8; // breathing dims down to 20% of the set Value
+const STROBE_PERIOD_MS: u32 = 200; // 5Hz strobe
+const SHIMMER_SAT_PERIOD_MS: u32 = 17_000; // saturation swings once every 17 seconds
+const SHIMMER_SAT_DEPTH: f32 = 0.15; // by up to 0.15 either side of its set point
+const SHIMMER_VALUE_PERIOD_MS: u32 = 7_000; // value swings once every 7 seconds, out of step with the saturation
+const SHIMMER_VALUE_DEPTH: f32 = 0.2; // by up to 0.2 either side of its set point
+
+/// C-style enum of the operating modes
+///
+/// 1. Manual: the pot/RTT set color, unmodified
+/// 2. Rainbow: the hue continuously cycles around the color wheel
+/// 3. Breathe: the Value slowly pulses
+/// 4. Strobe: the color flashes on and off
+/// 5. Ambient: the Value follows the ambient light level
+/// 6. Calibration: the LED shows its reference white while the matrix shows a uniform mid-grey for comparison
+/// 7. Slideshow: the color slowly fades through the preset colors, dwelling on each
+/// 8. Shade: the hue is locked while the pot explores the saturation and value of that one color
+/// 9. Shimmer: the hue holds while the saturation and value slowly swing around their set points
+#[derive(Clone, Copy, PartialEq)]
+pub enum Mode {
+    Manual = 0,
+    Rainbow = 1,
+    Breathe = 2,
+    Strobe = 3,
+    Ambient = 4,
+    Calibration = 5,
+    Slideshow = 6,
+    Shade = 7,
+    Shimmer = 8,
+}
+
+/// Impl Mode
+impl Mode {
+    /// Every mode, in cycling order
+    pub const ALL: [Mode; 9] = [
+        Mode::Manual,
+        Mode::Rainbow,
+        Mode::Breathe,
+        Mode::Strobe,
+        Mode::Ambient,
+        Mode::Calibration,
+        Mode::Slideshow,
+        Mode::Shade,
+        Mode::Shimmer,
+    ];
+
+    /// PUBLIC
+    /// Short name of the mode, as typed in the RTT `mode <name>` command
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::Manual => "manual",
+            Mode::Rainbow => "rainbow",
+            Mode::Breathe => "breathe",
+            Mode::Strobe => "strobe",
+            Mode::Ambient => "ambient",
+            Mode::Calibration => "calibrate",
+            Mode::Slideshow => "slideshow",
+            Mode::Shade => "shade",
+            Mode::Shimmer => "shimmer",
+        }
+    }
+
+    /// PUBLIC
+    /// One line description of the mode, for the RTT menu
+    pub fn description(&self) -> &'static str {
+        match self {
+            Mode::Manual => "color set by the pot and RTT, unmodified",
+            Mode::Rainbow => "hue cycles continuously around the color wheel",
+            Mode::Breathe => "value slowly pulses like breathing",
+            Mode::Strobe => "color flashes on and off",
+            Mode::Ambient => "value follows the ambient light level (photoresistor on e01)",
+            Mode::Calibration => {
+                "LED at reference white, matrix at mid-grey, for channel balance tuning"
+            }
+            Mode::Slideshow => "fades through the preset colors, the pot sets the dwell time",
+            Mode::Shade => "hue locked, A/B pick saturation or value and the pot sets it",
+            Mode::Shimmer => {
+                "hue held while saturation and value slowly swing around their settings"
+            }
+        }
+    }
+
+    /// PUBLIC
+    /// Look up a mode by its short name
+    pub fn from_name(name: &str) -> Option<Mode> {
+        Mode::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+
+    /// PUBLIC
+    /// The mode after this one in Mode::ALL, wrapping from the last back to the first
+    pub const fn next_mode(&self) -> Mode {
+        Mode::ALL[(*self as usize + 1) % Mode::ALL.len()]
+    }
+
+    /// PUBLIC
+    /// The animated effect this mode runs on the ColorControler, if any. The shimmer mode's effect is the default one,
+    /// main.rs runs its own tunable copy instead
+    pub fn effect(&self) -> Option<Effect> {
+        match self {
+            Mode::Rainbow => Some(Effect::HueDrift {
+                period_frames: ColorControler::ms_to_frames(RAINBOW_PERIOD_MS),
+            }),
+            Mode::Breathe => Some(Effect::Breathe {
+                period_frames: ColorControler::ms_to_frames(BREATHE_PERIOD_MS),
+                depth: BREATHE_DEPTH,
+            }),
+            Mode::Strobe => Some(Effect::Strobe {
+                period_frames: ColorControler::ms_to_frames(STROBE_PERIOD_MS),
+            }),
+            Mode::Calibration => Some(Effect::ReferenceWhite),
+            Mode::Shimmer => Some(Effect::ShadeCycle {
+                sat_period_frames: ColorControler::ms_to_frames(SHIMMER_SAT_PERIOD_MS),
+                sat_depth: SHIMMER_SAT_DEPTH,
+                value_period_frames: ColorControler::ms_to_frames(SHIMMER_VALUE_PERIOD_MS),
+                value_depth: SHIMMER_VALUE_DEPTH,
+            }),
+            Mode::Manual | Mode::Ambient | Mode::Slideshow | Mode::Shade => None,
+        }
+    }
+}
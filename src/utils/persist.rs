@@ -0,0 +1,198 @@
+//! persist.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The persist module keeps the color across resets: the base color is saved to its own flash page (see the
+//! ConfigStore save_hsv and load_hsv in storage.rs) at soft power-off (see power_toggle.rs) and once the pots have
+//! been left alone for HSV_SAVE_DELAY_MS, and restored at boot instead of the starting color, so a board simply
+//! unplugged keeps its color too. Only a settled pot triggers a save, and save_hsv skips a color already saved, so an
+//! adjustment costs a single page erase however long the pot is turned for.
+//!
+//! The erase stalls the CPU for up to 85ms. With the software PWM (neither `ppi-pwm` nor `hw-pwm`) the TIMER2 interrupt
+//! is held off meanwhile, so the LED visibly holds one PWM step, once, a few seconds after an adjustment. The hardware
+//! backends keep the pins toggling through the stall.
+//!
+//! Blob layout (HSV_BLOB_SIZE bytes):
+//! 1. bytes 0-3: HSV_MAGIC
+//! 2. bytes 4-15: the H, S, and V components as little endian f32
+//! 3. bytes 16-17: CRC-16 (see config.rs) of bytes 0-15, little endian
+//! 4. bytes 18-19: zero padding to a whole number of flash words
+//!
+//! An erased (all 0xFF) page, a wrong magic or CRC, or a component outside [0,1] (NaN included) all read as no saved
+//! color, so the starting color is used.
+
+use super::commit_debounce::commit_due;
+use super::config::crc16;
+use super::hsv_rgb_convert::Hsv;
+
+/// Constants
+pub const HSV_BLOB_SIZE: usize = 20; // serialized color size in bytes, a multiple of the 4 byte flash word
+const HSV_MAGIC: [u8; 4] = *b"HSVL"; // marks a flash page holding the last color
+const HSV_CRC_OFFSET: usize = 16; // the CRC follows the magic and the three components
+pub const HSV_SAVE_DELAY_MS: u32 = 3_000; // save the color once the pots have been left alone for 3 seconds
+
+/// PUBLIC
+/// Serialize hsv into the blob described in the module docs
+pub const fn hsv_to_bytes(hsv: &Hsv) -> [u8; HSV_BLOB_SIZE] {
+    let mut bytes = [0u8; HSV_BLOB_SIZE];
+    let components = [hsv.h, hsv.s, hsv.v];
+    let mut i = 0;
+    while i < 4 {
+        bytes[i] = HSV_MAGIC[i];
+        i += 1;
+    }
+    let mut c = 0;
+    while c < 3 {
+        let le = components[c].to_le_bytes();
+        let mut i = 0;
+        while i < 4 {
+            bytes[4 + 4 * c + i] = le[i];
+            i += 1;
+        }
+        c += 1;
+    }
+    let crc = crc16(bytes.split_at(HSV_CRC_OFFSET).0).to_le_bytes();
+    bytes[HSV_CRC_OFFSET] = crc[0];
+    bytes[HSV_CRC_OFFSET + 1] = crc[1];
+    bytes
+}
+
+/// PUBLIC
+/// Deserialize a blob written by hsv_to_bytes(), None for an erased page, a wrong magic or CRC, or a component
+/// outside [0,1]
+pub const fn hsv_from_bytes(bytes: &[u8; HSV_BLOB_SIZE]) -> Option<Hsv> {
+    let (covered, rest) = bytes.split_at(HSV_CRC_OFFSET);
+    if bytes[0] != HSV_MAGIC[0]
+        || bytes[1] != HSV_MAGIC[1]
+        || bytes[2] != HSV_MAGIC[2]
+        || bytes[3] != HSV_MAGIC[3]
+        || u16::from_le_bytes([rest[0], rest[1]]) != crc16(covered)
+    {
+        return None;
+    }
+
+    let mut components = [0.0; 3];
+    let mut c = 0;
+    while c < 3 {
+        let at = 4 + 4 * c;
+        let component =
+            f32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]);
+        if !(component >= 0.0 && component <= 1.0) {
+            return None;
+        }
+        components[c] = component;
+        c += 1;
+    }
+    Some(Hsv {
+        h: components[0],
+        s: components[1],
+        v: components[2],
+    })
+}
+
+/// HsvSaver struct declaration: Note all fields are private
+///
+/// 1. moved_us: SYSTEM_CLOCK timestamp of the latest pot movement not yet followed by a save, None when the saved
+///    color is up to date
+pub struct HsvSaver {
+    moved_us: Option<u32>,
+}
+
+/// Impl HsvSaver
+impl HsvSaver {
+    /// PUBLIC
+    /// Generate a new HsvSaver with nothing to save
+    pub const fn new() -> Self {
+        HsvSaver { moved_us: None }
+    }
+
+    /// PUBLIC
+    /// Record a pot movement at now_us, restarting the wait for the pot to settle
+    pub const fn touch(&mut self, now_us: u32) {
+        self.moved_us = Some(now_us);
+    }
+
+    /// PUBLIC
+    /// Returns true once the pot has been left alone for HSV_SAVE_DELAY_MS since its last movement (only once per
+    /// movement), i.e. when the color should be saved. Timestamps are compared with wrap-around.
+    pub const fn poll(&mut self, now_us: u32) -> bool {
+        match self.moved_us {
+            Some(moved_us) if commit_due(now_us, moved_us, HSV_SAVE_DELAY_MS) => {
+                self.moved_us = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+// a color survives the round trip bit for bit, while an erased page, a damaged blob, or an out of range component
+// reads as no saved color
+const _: () = {
+    let color = Hsv {
+        h: 0.83,
+        s: 0.5,
+        v: 1.0,
+    };
+    let blob = hsv_to_bytes(&color);
+    match hsv_from_bytes(&blob) {
+        Some(loaded) => assert!(
+            loaded.h == color.h && loaded.s == color.s && loaded.v == color.v,
+            "the saved color came back changed"
+        ),
+        None => panic!("a saved color did not load"),
+    }
+    assert!(
+        hsv_from_bytes(&[0xFF; HSV_BLOB_SIZE]).is_none(),
+        "an erased page loaded as a color"
+    );
+
+    let mut byte = 0;
+    while byte < HSV_CRC_OFFSET + 2 {
+        let mut damaged = blob;
+        damaged[byte] ^= 0x10;
+        assert!(hsv_from_bytes(&damaged).is_none(), "a damaged blob loaded");
+        byte += 1;
+    }
+
+    let out_of_range = [
+        Hsv {
+            h: 1.5,
+            s: 0.5,
+            v: 0.5,
+        },
+        Hsv {
+            h: 0.5,
+            s: -0.1,
+            v: 0.5,
+        },
+        Hsv {
+            h: 0.5,
+            s: 0.5,
+            v: f32::NAN,
+        },
+    ];
+    let mut i = 0;
+    while i < out_of_range.len() {
+        assert!(
+            hsv_from_bytes(&hsv_to_bytes(&out_of_range[i])).is_none(),
+            "an out of range color loaded"
+        );
+        i += 1;
+    }
+};
+
+// a save is due once per pot movement, only after the pot has settled for the delay, and across the clock wrap
+const _: () = {
+    let mut saver = HsvSaver::new();
+    assert!(!saver.poll(u32::MAX), "saved without a pot movement");
+    let moved_us = u32::MAX - 1_000;
+    saver.touch(moved_us);
+    assert!(!saver.poll(moved_us.wrapping_add(HSV_SAVE_DELAY_MS * 1000 - 1)));
+    assert!(saver.poll(moved_us.wrapping_add(HSV_SAVE_DELAY_MS * 1000)));
+    assert!(
+        !saver.poll(moved_us.wrapping_add(2 * HSV_SAVE_DELAY_MS * 1000)),
+        "saved twice for one movement"
+    );
+};
@@ -0,0 +1,140 @@
+//! pot_mapping.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The pot_mapping module contains the PotMapper struct which converts the averaged pot percentage into the value
+//! written to the selected H, S, or V axis. In coarse mode (the default) the pot spans the full [0,1] range. In fine
+//! mode the full pot travel only spans a narrow window (±FINE_WINDOW) centered on the axis value at the moment fine
+//! mode was engaged, allowing precise tweaks.
+//!
+//! Toggling never makes the color jump: fine mode is anchored at the current pot position and axis value, and when
+//! returning to coarse mode the pot has to "pick up" the axis (reach or cross its current value) before it writes again.
+
+use super::hsv_rgb_convert::hue_delta;
+
+/// Constants
+pub const FINE_WINDOW: f32 = 0.05; // fine mode spans ±5% around the anchored axis value
+const PICKUP_THRESHOLD: f32 = 0.02; // pot within 2% of the axis value counts as picked up
+
+/// Fine mode anchor: the pot position and axis value when fine mode was engaged for an axis
+///
+/// 1. pot: pot percentage [0,1] at the anchor
+/// 2. value: axis value [0,1] at the anchor
+/// 3. axis: the anchored axis (HSVPage as usize)
+#[derive(Clone, Copy)]
+pub struct Anchor {
+    pub pot: f32,
+    pub value: f32,
+    pub axis: usize,
+}
+
+/// PUBLIC
+/// Fine mode mapping: moving the pot across its full [0,1] travel moves the axis across 2 * window, with the anchor
+/// pot position mapping exactly onto the anchor value. The result is clamped to [0,1].
+pub fn fine_map(pot: f32, anchor: &Anchor, window: f32) -> f32 {
+    (anchor.value + (pot - anchor.pot) * 2.0 * window).clamp(0.0, 1.0)
+}
+
+/// PUBLIC
+/// Coarse mode pickup test: has the pot reached (or crossed, since last_pot) the axis value? For the hue the distance
+/// is measured around the color wheel, so a pot at the top of its travel picks up a hue just past red
+pub const fn picked_up(pot: f32, last_pot: f32, value: f32, hue: bool) -> bool {
+    let crossed = (last_pot - value) * (pot - value) <= 0.0;
+    let distance = if hue {
+        hue_delta(pot, value).abs()
+    } else {
+        (pot - value).abs()
+    };
+    crossed || distance <= PICKUP_THRESHOLD
+}
+
+// a pot just below the top picks up a hue just above 0, which is only close around the color wheel
+const _: () = {
+    assert!(picked_up(0.99, 0.9, 0.005, true));
+    assert!(!picked_up(0.99, 0.9, 0.005, false));
+    assert!(!picked_up(0.6, 0.7, 0.1, true));
+};
+
+/// PotMapper struct declaration: Note all fields are private
+///
+/// 1. fine: the fine mode anchor, None in coarse mode
+/// 2. pickup: true while coarse mode waits for the pot to pick up the axis value
+/// 3. last_pot: previous pot percentage, used to detect the pot crossing the axis value
+pub struct PotMapper {
+    fine: Option<Anchor>,
+    pickup: bool,
+    last_pot: f32,
+}
+
+/// Impl PotMapper
+impl PotMapper {
+    /// PUBLIC
+    /// Generate a new PotMapper in coarse mode
+    pub const fn new() -> Self {
+        PotMapper {
+            fine: None,
+            pickup: false,
+            last_pot: 0.0,
+        }
+    }
+
+    /// PUBLIC
+    /// return whether fine mode is engaged
+    pub fn is_fine(&self) -> bool {
+        self.fine.is_some()
+    }
+
+    /// PUBLIC
+    /// return whether coarse mode waits for the pot to pick up the selected axis value before it writes
+    pub fn awaiting_pickup(&self) -> bool {
+        self.pickup
+    }
+
+    /// PUBLIC
+    /// Have coarse mode wait for the pot to pick up the selected axis value again before it writes, e.g. after the axis
+    /// was moved away from the pot position by something else (see hue_scroll.rs)
+    pub fn await_pickup(&mut self) {
+        self.pickup = true;
+    }
+
+    /// PUBLIC
+    /// Engage or release fine mode. pot and value are the current pot percentage and axis value.
+    pub fn set_fine(&mut self, enabled: bool, pot: f32, value: f32, axis: usize) {
+        if enabled {
+            self.fine = Some(Anchor { pot, value, axis });
+            self.pickup = false;
+        } else if self.fine.is_some() {
+            self.fine = None;
+            self.pickup = true;
+        }
+        self.last_pot = pot;
+    }
+
+    /// PUBLIC
+    /// Map the pot percentage onto the axis whose current value is value. Returns the new axis value, or None if the
+    /// axis should not be written (coarse mode still waiting for the pot to pick up the axis). In fine mode, switching
+    /// to a different axis re-anchors on that axis so the page change doesn't cause a jump either. hue is whether the
+    /// axis is the hue, whose pickup is measured around the color wheel (see picked_up()).
+    pub fn map(&mut self, pot: f32, value: f32, axis: usize, hue: bool) -> Option<f32> {
+        let last_pot = self.last_pot;
+        self.last_pot = pot;
+
+        match self.fine {
+            Some(anchor) if anchor.axis == axis => Some(fine_map(pot, &anchor, FINE_WINDOW)),
+            Some(_) => {
+                self.fine = Some(Anchor { pot, value, axis });
+                None
+            }
+            None if self.pickup => {
+                if picked_up(pot, last_pot, value, hue) {
+                    self.pickup = false;
+                    Some(pot)
+                } else {
+                    None
+                }
+            }
+            None => Some(pot),
+        }
+    }
+}
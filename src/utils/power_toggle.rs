@@ -0,0 +1,215 @@
+//! power_toggle.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The power_toggle module contains the PowerToggle struct, the state machine behind the soft power button: holding B
+//! alone for LONG_PRESS_MS (see long_press.rs) toggles the whole output. Switching off is a shutdown sequence, one step
+//! per poll, each step only handed out once the step before it has been carried out:
+//!
+//! 1. Persist: save the color in use (and any config change still waiting for its commit delay) to flash, so the
+//!    exact state survives even if the board is unplugged while off. The main.rs event loop saves synchronously,
+//!    waiting out the flash busy periods (see storage.rs), before it polls again
+//! 2. FadeOut: fade the LED out over POWER_FADE_MS
+//! 3. Sleep: once the fade is over, blank the matrix and idle until the next long press
+//!
+//! The same long press then fades the persisted color back in and re-lights the matrix.
+//!
+//! The short press starting the long press still rotates the page as usual (the main.rs event loop undoes that when
+//! the long press completes), and one hold only ever toggles once, however long it lasts. From the start of the
+//! shutdown until the power is back on the pots and the other button presses are ignored.
+
+use super::hsv_rgb_convert::Hsv;
+use super::long_press::{LONG_PRESS_MS, LongPress};
+
+/// Constants
+pub const POWER_FADE_MS: u32 = 500; // the LED fades out (or back in) over half a second
+
+/// What the soft power asks for next
+///
+/// 1. Persist: save the given color (and any pending config) to flash, the first step of the shutdown
+/// 2. FadeOut: fade the output out
+/// 3. Sleep: the fade is over, blank the matrix
+/// 4. On: restore the persisted color (the given one if flash holds none), fade the output back in, and re-light the
+///    matrix
+#[derive(Clone, Copy)]
+pub enum PowerAction {
+    Persist(Hsv),
+    FadeOut,
+    Sleep,
+    On(Hsv),
+}
+
+/// Where the soft power is in its cycle
+///
+/// 1. On: the output is on
+/// 2. Persisting: Persist has been handed out for the color
+/// 3. Fading: FadeOut has been handed out for the color at the SYSTEM_CLOCK timestamp since_us
+/// 4. Off: the shutdown is complete, remembering the color in use when it started
+#[derive(Clone, Copy)]
+enum PowerPhase {
+    On,
+    Persisting(Hsv),
+    Fading { color: Hsv, since_us: u32 },
+    Off(Hsv),
+}
+
+/// PowerToggle struct declaration: Note all fields are private
+///
+/// 1. press: the long press detection of the power button
+/// 2. phase: where the power is in its on, shutdown, off cycle
+pub struct PowerToggle {
+    press: LongPress,
+    phase: PowerPhase,
+}
+
+/// Impl PowerToggle
+impl PowerToggle {
+    /// PUBLIC
+    /// Generate a new PowerToggle with the power on
+    pub const fn new() -> Self {
+        PowerToggle {
+            press: LongPress::new(),
+            phase: PowerPhase::On,
+        }
+    }
+
+    /// PUBLIC
+    /// return whether the power is switched off, or on its way off
+    pub const fn is_off(&self) -> bool {
+        !matches!(self.phase, PowerPhase::On)
+    }
+
+    /// PUBLIC
+    /// Advance the soft power to now_us, held being whether the power button alone is held and current the color in
+    /// use. With the power on, a hold of LONG_PRESS_MS (only once per hold) starts the shutdown with Persist, and each
+    /// later poll hands out the next step of the sequence (see the module docs). With the power off, a long press
+    /// hands back the color remembered at the start of the shutdown with On. Returns None while there is nothing to
+    /// do. Timestamps are compared with wrap-around.
+    pub const fn poll(&mut self, held: bool, now_us: u32, current: Hsv) -> Option<PowerAction> {
+        let pressed = self.press.poll(held, now_us);
+        let (phase, action) = match self.phase {
+            PowerPhase::On if pressed => (
+                PowerPhase::Persisting(current),
+                Some(PowerAction::Persist(current)),
+            ),
+            PowerPhase::Persisting(color) => (
+                PowerPhase::Fading {
+                    color,
+                    since_us: now_us,
+                },
+                Some(PowerAction::FadeOut),
+            ),
+            PowerPhase::Fading { color, since_us }
+                if now_us.wrapping_sub(since_us) >= POWER_FADE_MS * 1000 =>
+            {
+                (PowerPhase::Off(color), Some(PowerAction::Sleep))
+            }
+            PowerPhase::Off(color) if pressed => (PowerPhase::On, Some(PowerAction::On(color))),
+            phase => (phase, None),
+        };
+        self.phase = phase;
+        action
+    }
+}
+
+// a short hold does nothing; a long hold persists, then fades, then sleeps once the fade is over, each step once and in
+// that order; and the next long hold wakes with the persisted color, whatever the color has become meanwhile. The
+// clock is a ManualClock and the flash page the persist.rs blob (magic, components, CRC) that save_hsv writes: it is
+// programmed over an erased page (NOR flash only clears bits), and read back through hsv_from_bytes with the fallback
+// to the remembered color that main.rs applies to load_hsv, so a save cut short wakes with the remembered color
+const _: () = {
+    use super::clock::ManualClock;
+    use super::persist::{HSV_BLOB_SIZE, hsv_from_bytes, hsv_to_bytes};
+
+    let color = Hsv {
+        h: 0.9,
+        s: 0.6,
+        v: 0.5,
+    };
+    let other = Hsv {
+        h: 0.1,
+        s: 1.0,
+        v: 1.0,
+    };
+    let mut clock = ManualClock::new(0);
+    let mut power = PowerToggle::new();
+    assert!(power.poll(true, clock.now_ticks(), color).is_none());
+    clock.advance_ms(LONG_PRESS_MS - 1);
+    assert!(power.poll(true, clock.now_ticks(), color).is_none());
+    clock.advance_ms(1);
+    assert!(power.poll(false, clock.now_ticks(), color).is_none());
+    assert!(!power.is_off());
+
+    clock.advance_ms(10_000);
+    assert!(power.poll(true, clock.now_ticks(), color).is_none());
+    clock.advance_ms(LONG_PRESS_MS);
+    let mut flash = [0xFF; HSV_BLOB_SIZE];
+    let mut torn = flash;
+    match power.poll(true, clock.now_ticks(), color) {
+        Some(PowerAction::Persist(saved)) => {
+            let blob = hsv_to_bytes(&saved);
+            let mut i = 0;
+            while i < HSV_BLOB_SIZE {
+                flash[i] &= blob[i];
+                // power lost halfway through the write: the CRC is still erased
+                if i < HSV_BLOB_SIZE / 2 {
+                    torn[i] &= blob[i];
+                }
+                i += 1;
+            }
+        }
+        _ => panic!("the shutdown does not persist first"),
+    }
+    assert!(power.is_off(), "the pots still write while shutting down");
+    clock.advance_ms(100);
+    assert!(
+        matches!(
+            power.poll(true, clock.now_ticks(), other),
+            Some(PowerAction::FadeOut)
+        ),
+        "the shutdown does not fade once persisted"
+    );
+    clock.advance_ms(POWER_FADE_MS - 1);
+    assert!(
+        power.poll(true, clock.now_ticks(), other).is_none(),
+        "slept before the fade was over"
+    );
+    clock.advance_ms(1);
+    assert!(matches!(
+        power.poll(false, clock.now_ticks(), other),
+        Some(PowerAction::Sleep)
+    ));
+    clock.advance_ms(10_000);
+    assert!(
+        power.poll(false, clock.now_ticks(), other).is_none(),
+        "the shutdown ran twice"
+    );
+    assert!(power.is_off());
+
+    assert!(power.poll(true, clock.now_ticks(), other).is_none());
+    clock.advance_ms(LONG_PRESS_MS);
+    match power.poll(true, clock.now_ticks(), other) {
+        Some(PowerAction::On(remembered)) => {
+            let restored = match hsv_from_bytes(&flash) {
+                Some(loaded) => loaded,
+                None => panic!("the persisted color did not load"),
+            };
+            assert!(
+                restored.h == color.h && restored.s == color.s && restored.v == color.v,
+                "wake restores a different color"
+            );
+            let fallback = match hsv_from_bytes(&torn) {
+                Some(_) => panic!("a torn write loaded as a color"),
+                None => remembered,
+            };
+            assert!(
+                fallback.h == color.h && fallback.s == color.s && fallback.v == color.v,
+                "a torn write does not wake with the remembered color"
+            );
+            assert!(hsv_from_bytes(&[0xFF; HSV_BLOB_SIZE]).is_none());
+        }
+        _ => panic!("second long press does not switch back on"),
+    }
+    assert!(!power.is_off());
+};
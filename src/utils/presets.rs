@@ -0,0 +1,79 @@
+//! presets.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The presets module contains the table of named preset colors, e.g. the colors stepped through by the slideshow
+//! mode (see slideshow.rs).
+
+use super::hsv_rgb_convert::Hsv;
+
+/// A named preset color
+///
+/// 1. name: short name, as printed over RTT
+/// 2. color: the preset Hsv color
+#[derive(Clone, Copy)]
+pub struct Preset {
+    pub name: &'static str,
+    pub color: Hsv,
+}
+
+/// Constants
+pub const PRESETS: [Preset; 7] = [
+    Preset {
+        name: "red",
+        color: Hsv {
+            h: 0.0,
+            s: 1.0,
+            v: 1.0,
+        },
+    },
+    Preset {
+        name: "amber",
+        color: Hsv {
+            h: 0.0833,
+            s: 1.0,
+            v: 1.0,
+        },
+    },
+    Preset {
+        name: "green",
+        color: Hsv {
+            h: 0.3333,
+            s: 1.0,
+            v: 1.0,
+        },
+    },
+    Preset {
+        name: "teal",
+        color: Hsv {
+            h: 0.4722,
+            s: 0.8,
+            v: 0.8,
+        },
+    },
+    Preset {
+        name: "blue",
+        color: Hsv {
+            h: 0.6667,
+            s: 1.0,
+            v: 1.0,
+        },
+    },
+    Preset {
+        name: "magenta",
+        color: Hsv {
+            h: 0.9167,
+            s: 0.75,
+            v: 0.8,
+        },
+    },
+    Preset {
+        name: "warm white",
+        color: Hsv {
+            h: 0.0833,
+            s: 0.25,
+            v: 1.0,
+        },
+    },
+]; // in slideshow order
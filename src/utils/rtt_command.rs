@@ -0,0 +1,728 @@
+//! rtt_command.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The rtt_command module contains the RTT (Real-Time Transfer) command interface. The CommandReader struct
+//! buffers the raw bytes read from the RTT down channel into complete lines, and parse() converts a completed
+//! line into a Command enum which is then dispatched by main.rs. Replies are printed back on the RTT up channel.
+//! The CommandHistory struct remembers the last few commands so they can be replayed with `!` (the last command) or
+//! `!n` (the nth most recent).
+
+use core::fmt::{self, Write};
+
+use super::color_space::ColorSpace;
+use super::curve::{Curve, LUT_POINTS};
+use super::fade::HueDirection;
+use super::fmt_buf::FmtBuf;
+use super::hsv_display::{HSVPage, PageTransition};
+use super::hsv_rgb_convert::Hsv;
+use super::hue_scroll::DEFAULT_HUE_SCROLL_PERIOD_MS;
+use super::midi_cc::{DEFAULT_CC_NUMBERS, DEFAULT_MIDI_CHANNEL};
+use super::mode::Mode;
+use super::value_floor::{DEFAULT_MIN_GLOW, ValueFloor};
+
+/// Constants
+pub const LINE_CAPACITY: usize = 64; // longest accepted command line, extra bytes are dropped
+pub const HISTORY_CAPACITY: usize = 8; // number of past commands remembered for replay
+
+/// Commands understood by the RTT interface
+///
+/// 1. Export: print the current color as a copy-pasteable `Hsv { .. }` Rust literal
+/// 2. Breathe: add a breathing (Value) effect with the given period and depth
+/// 3. Drift: add a hue drift effect completing one trip around the color wheel every period
+/// 4. Strobe: add a strobe effect with the given period
+/// 5. ClearEffects: stop all running effects
+/// 6. Latency: report (and reset) the measured pot-change to render latency
+/// 7. Ambient: turn the ambient light (Value follows light level) mode on or off
+/// 8. AmbientBounds: set the light levels [0,1] mapped to min_scale (dark) and full Value (bright)
+/// 9. Random: jump to a random color from the (seedable) pseudo random sequence
+/// 10. Seed: restart the pseudo random sequence from the given seed
+/// 11. Hold: RTT-set axes ignore the pot for ms, then the pot takes them back once it is turned
+/// 12. Lock: RTT-set axes ignore the pot until released
+/// 13. Release: hand every axis back to the pot
+/// 14. Modes: print the menu of available modes
+/// 15. SetMode: switch to the given mode
+/// 16. NextMode: switch to the mode after the current one
+/// 17. PwmStats: report (and reset) the PWM interrupt rate and CPU load
+/// 18. Idle: return to the default color after timeout_ms without interaction, or never (None)
+/// 19. IdleDefault: make the current color the color returned to after inactivity
+/// 20. Css: print the current color as CSS `hsl(...)` and `#rrggbb` strings
+/// 21. Set: set one of the H, S, or V axes to value, warning if it had to be clamped into [0,1]
+/// 22. Curve: set the pot input curve of one of the H, S, or V axes (persisted to flash once left alone)
+/// 23. Pfm: set the shortest on-pulse the LED driver can show on one channel (0 = R, 1 = G, 2 = B) or, for None, on
+///     every channel, below which that dim channel is pulse frequency modulated
+/// 24. Debounce: set the A/B button debounce cooldown
+/// 25. Slideshow: set the slideshow dwell and fade times and switch to the slideshow mode
+/// 26. Contrast: switch the matrix between the standard and the high-contrast glyph set
+/// 27. History: print the remembered commands, most recent first, numbered for `!n`
+/// 28. Blank: blank the matrix after timeout_ms without a button press, or never (None)
+/// 29. Fade: fade to color over ms, the hue travelling around the color wheel in direction
+/// 30. Transition: set the animation used when the matrix changes page
+/// 31. Pages: switch the three editing pages between the H, S, and V components and the R, G, and B channels
+/// 32. Reinit: restart the PWM and ADC timers and re-show the matrix, as done when waking from sleep
+/// 33. IrqRates: report (and reset) how often each interrupt has fired per second
+/// 34. Primaries: warp the rendered hue so red, green, and blue land on the given hues, or stop warping (None)
+/// 35. Shimmer: set the saturation and value swing periods and depths and switch to the shimmer mode
+/// 36. Swatch: show a swatch mirroring the LED color on the matrix instead of the page glyph, or the page glyph again
+/// 37. CommitDelay: set how long a changed setting must be left alone before it is written to flash
+/// 38. AutoBalance/ClearBalance: calibrate the channel balance with the light sensor facing the LED, or render unbalanced
+/// 39. Readout: scroll the selected axis value (as a percentage) across the matrix
+/// 40. Overview: show H, S, and V at once as three bar graphs on the matrix instead of the page glyph, or the page
+///     glyph again
+/// 41. Detent: snap the pot to its center within ±window (0 for off)
+/// 42. Gamma: map the matrix greyscale through the perceptual gamma curve, or show it raw
+/// 43. Version: print the firmware version, git hash, and build time
+/// 44. ScanRate: scan the matrix at (at least) hz
+/// 45. Standby/StandbyLook: turn the standby shimmer on or off, or set its brightness and hue cycle period (turning it
+///     on)
+/// 46. Match: convert the 8-bit RGB color to HSV and apply it, reporting how closely the LED reproduces it
+/// 47. PwmPause: freeze the LED pins where they are by stopping the PWM, or start it again
+/// 48. HueRateCap: limit every hue animation to at most turns_per_s trips around the color wheel per second
+/// 49. WhitePoint: make the LED a white of the given color temperature in Kelvin, or drop the tint (None)
+/// 50. Midi: send the H, S, and V axes over the UART link as MIDI control changes on the given channel (1-16) and
+///     controller numbers, or go back to the daisy-chain packets (None)
+/// 51. Posterize: snap every rendered channel to the given number of levels, or render smoothly (None)
+/// 52. ValueFloor: set the policy for the bottom of the Value axis (see value_floor.rs)
+/// 53. Demo: start the demo reel cycling through the modes (see demo_reel.rs)
+/// 54. Watch: log every base color change with the code path behind it, or stop logging (see color_watch.rs)
+/// 55. LedGamma: shape the LED duty cycles by the gamma curve with the given exponent (see led_gamma.rs)
+/// 56. Blend: glide every change of the LED color linearly over ms, 0 for instant changes
+/// 57. BadAssignment: an `h=`, `s=`, or `v=` assignment whose value is not a number in [0,1], reported and ignored
+/// 58. ColorCode: show the color as a camera-readable dot pattern on the matrix instead of the page glyph, or the page
+///     glyph again
+/// 59. Smooth: low-pass filter the pot with an IIR of the given alpha, or go back to the plain block average (None)
+/// 60. AutoScroll: scroll the hue one turn every period_ms (`on` for DEFAULT_HUE_SCROLL_PERIOD_MS) while the pot is
+///     pinned at an end on the Hue page, or stop doing so (None)
+#[derive(Clone, Copy)]
+pub enum Command {
+    Export,
+    Breathe {
+        period_ms: u32,
+        depth: f32,
+    },
+    Drift {
+        period_ms: u32,
+    },
+    Strobe {
+        period_ms: u32,
+    },
+    ClearEffects,
+    Latency,
+    Ambient(bool),
+    AmbientBounds {
+        dark: f32,
+        bright: f32,
+        min_scale: f32,
+    },
+    Random,
+    Seed(u32),
+    Hold {
+        ms: u32,
+    },
+    Lock,
+    Release,
+    Modes,
+    SetMode(Mode),
+    NextMode,
+    PwmStats,
+    Idle {
+        timeout_ms: Option<u32>,
+    },
+    IdleDefault,
+    Css,
+    Set {
+        page: HSVPage,
+        value: f32,
+    },
+    Curve {
+        page: HSVPage,
+        curve: Curve,
+    },
+    Pfm {
+        channel: Option<usize>,
+        min_on_us: u32,
+    },
+    Debounce {
+        ms: u32,
+    },
+    Slideshow {
+        dwell_ms: u32,
+        fade_ms: u32,
+    },
+    Contrast(bool),
+    History,
+    Blank {
+        timeout_ms: Option<u32>,
+    },
+    Fade {
+        color: Hsv,
+        ms: u32,
+        direction: HueDirection,
+    },
+    Transition(PageTransition),
+    Pages(ColorSpace),
+    Reinit,
+    IrqRates,
+    Primaries(Option<[f32; 3]>),
+    Shimmer {
+        sat_period_ms: u32,
+        sat_depth: f32,
+        value_period_ms: u32,
+        value_depth: f32,
+    },
+    Swatch(bool),
+    CommitDelay {
+        ms: u32,
+    },
+    AutoBalance,
+    ClearBalance,
+    Readout,
+    Overview(bool),
+    Detent {
+        window: f32,
+    },
+    Gamma(bool),
+    Version,
+    ScanRate {
+        hz: u32,
+    },
+    Standby(bool),
+    StandbyLook {
+        brightness: f32,
+        period_ms: u32,
+    },
+    Match([u8; 3]),
+    PwmPause(bool),
+    HueRateCap {
+        turns_per_s: f32,
+    },
+    WhitePoint(Option<f32>),
+    Midi(Option<(u8, [u8; 3])>),
+    Posterize(Option<u8>),
+    ValueFloor(ValueFloor),
+    Demo,
+    Watch(bool),
+    LedGamma {
+        gamma: f32,
+    },
+    Blend {
+        ms: u32,
+    },
+    BadAssignment {
+        page: HSVPage,
+    },
+    ColorCode(bool),
+    Smooth {
+        alpha: Option<f32>,
+    },
+    AutoScroll {
+        period_ms: Option<u32>,
+    },
+}
+
+/// CommandReader struct declaration: Note all fields are private
+///
+/// 1. buf: statically allocated line buffer
+/// 2. len: number of valid bytes currently held in buf
+pub struct CommandReader {
+    buf: [u8; LINE_CAPACITY],
+    len: usize,
+}
+
+/// Impl CommandReader
+///
+/// Accumulates bytes from the RTT down channel until a newline is received
+impl CommandReader {
+    /// PUBLIC
+    /// Generate a new, empty CommandReader
+    pub const fn new() -> Self {
+        CommandReader {
+            buf: [0; LINE_CAPACITY],
+            len: 0,
+        }
+    }
+
+    /// PUBLIC
+    /// Push a single byte into the line buffer. Returns the completed (trimmed) line once a '\n' is received,
+    /// otherwise None. Carriage returns are ignored so both "\n" and "\r\n" terminals work, and bytes past
+    /// LINE_CAPACITY are silently dropped. A line which is not valid UTF-8 is returned as an empty line.
+    pub fn push(&mut self, byte: u8) -> Option<&str> {
+        match byte {
+            b'\r' => None,
+            b'\n' => {
+                let len = self.len;
+                self.len = 0;
+                Some(core::str::from_utf8(&self.buf[..len]).unwrap_or("").trim())
+            }
+            _ => {
+                if self.len < LINE_CAPACITY {
+                    self.buf[self.len] = byte;
+                    self.len += 1;
+                }
+                None
+            }
+        }
+    }
+}
+
+/// HistoryLine struct declaration: an owned copy of a command line. Note all fields are private
+///
+/// 1. 0: the line text
+#[derive(Clone, Copy)]
+pub struct HistoryLine(FmtBuf<LINE_CAPACITY>);
+
+/// Impl HistoryLine
+impl HistoryLine {
+    /// PUBLIC
+    /// Copy line, truncated to LINE_CAPACITY bytes (at a character boundary)
+    pub fn new(line: &str) -> Self {
+        let mut buf = FmtBuf::new();
+        buf.write_str(line).ok();
+        HistoryLine(buf)
+    }
+
+    /// PUBLIC
+    /// return the line as a str
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+/// CommandHistory struct declaration: Note all fields are private
+///
+/// 1. lines: ring buffer of the remembered command lines
+/// 2. next: index in lines the next command is recorded at (the oldest line once the buffer is full)
+/// 3. count: number of remembered lines, up to HISTORY_CAPACITY
+pub struct CommandHistory {
+    lines: [HistoryLine; HISTORY_CAPACITY],
+    next: usize,
+    count: usize,
+}
+
+/// Impl CommandHistory
+impl CommandHistory {
+    /// PUBLIC
+    /// Generate a new, empty CommandHistory
+    pub const fn new() -> Self {
+        CommandHistory {
+            lines: [HistoryLine(FmtBuf::new()); HISTORY_CAPACITY],
+            next: 0,
+            count: 0,
+        }
+    }
+
+    /// PUBLIC
+    /// Remember a command line, forgetting the oldest once HISTORY_CAPACITY lines are held
+    pub fn push(&mut self, line: HistoryLine) {
+        self.lines[self.next] = line;
+        self.next = (self.next + 1) % HISTORY_CAPACITY;
+        self.count = (self.count + 1).min(HISTORY_CAPACITY);
+    }
+
+    /// PUBLIC
+    /// return the nth most recent line (1 = the last command), None if fewer than n lines are remembered
+    pub fn get(&self, n: usize) -> Option<HistoryLine> {
+        if n == 0 || n > self.count {
+            return None;
+        }
+        Some(self.lines[(self.next + HISTORY_CAPACITY - n) % HISTORY_CAPACITY])
+    }
+
+    /// PUBLIC
+    /// return the number of remembered lines
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// PUBLIC
+    /// Resolve a typed line: `!` replays the last command and `!n` the nth most recent, any other line is returned
+    /// as-is. Returns None for a replay of a command that isn't remembered (including any replay while the history
+    /// is empty).
+    pub fn expand(&self, line: &str) -> Option<HistoryLine> {
+        match line.strip_prefix('!') {
+            Some("") => self.get(1),
+            Some(n) => self.get(n.trim().parse().ok()?),
+            None => Some(HistoryLine::new(line)),
+        }
+    }
+}
+
+/// PUBLIC
+/// Convert a completed line into a Command. The first whitespace separated word selects the command and any
+/// remaining words are its arguments. Returns None for an empty or unrecognized line, or for missing/malformed
+/// arguments.
+pub fn parse(line: &str) -> Option<Command> {
+    let mut words = line.split_whitespace();
+    let command = match words.next()? {
+        "export" => Command::Export,
+        "css" => Command::Css,
+        "set" => Command::Set {
+            page: parse_page(words.next()?)?,
+            value: parse_f32(words.next()?)?,
+        },
+        "curve" => Command::Curve {
+            page: parse_page(words.next()?)?,
+            curve: match words.next()? {
+                "linear" => Curve::Linear,
+                "exp" => Curve::Exponential,
+                "lut" => {
+                    let mut table = [0u8; LUT_POINTS];
+                    for point in table.iter_mut() {
+                        *point = words.next()?.parse().ok()?;
+                    }
+                    Curve::Lut(table)
+                }
+                _ => return None,
+            },
+        },
+        "breathe" => Command::Breathe {
+            period_ms: words.next()?.parse().ok()?,
+            depth: parse_f32(words.next()?)?,
+        },
+        "drift" => Command::Drift {
+            period_ms: words.next()?.parse().ok()?,
+        },
+        "strobe" => Command::Strobe {
+            period_ms: words.next()?.parse().ok()?,
+        },
+        "clear" => Command::ClearEffects,
+        "latency" => Command::Latency,
+        "pwm" => match words.next() {
+            None => Command::PwmStats,
+            Some("pause") => Command::PwmPause(true),
+            Some("resume") => Command::PwmPause(false),
+            Some(_) => return None,
+        },
+        "debounce" => Command::Debounce {
+            ms: words.next()?.parse().ok()?,
+        },
+        "commit" => Command::CommitDelay {
+            ms: words.next()?.parse().ok()?,
+        },
+        "pfm" => {
+            let word = words.next()?;
+            match parse_channel(word) {
+                Some(channel) => Command::Pfm {
+                    channel: Some(channel),
+                    min_on_us: words.next()?.parse().ok()?,
+                },
+                None => Command::Pfm {
+                    channel: None,
+                    min_on_us: word.parse().ok()?,
+                },
+            }
+        }
+        "idle" => match words.next()? {
+            "off" => Command::Idle { timeout_ms: None },
+            "default" => Command::IdleDefault,
+            seconds => Command::Idle {
+                timeout_ms: Some(seconds.parse::<u32>().ok()?.saturating_mul(1000)),
+            },
+        },
+        "slideshow" => Command::Slideshow {
+            dwell_ms: words.next()?.parse().ok()?,
+            fade_ms: words.next()?.parse().ok()?,
+        },
+        "shimmer" => Command::Shimmer {
+            sat_period_ms: words.next()?.parse().ok()?,
+            sat_depth: parse_f32(words.next()?)?,
+            value_period_ms: words.next()?.parse().ok()?,
+            value_depth: parse_f32(words.next()?)?,
+        },
+        "standby" => match words.next()? {
+            "on" => Command::Standby(true),
+            "off" => Command::Standby(false),
+            brightness => Command::StandbyLook {
+                brightness: parse_f32(brightness)?,
+                period_ms: words.next()?.parse().ok()?,
+            },
+        },
+        "posterize" => match words.next()? {
+            "off" => Command::Posterize(None),
+            levels => Command::Posterize(Some(levels.parse().ok()?)),
+        },
+        "vfloor" => Command::ValueFloor(match words.next()? {
+            "off" => ValueFloor::Off,
+            "never" => ValueFloor::NeverOff,
+            "glow" => ValueFloor::min_glow(match words.next() {
+                Some(glow) => parse_f32(glow)?,
+                None => DEFAULT_MIN_GLOW,
+            }),
+            _ => return None,
+        }),
+        "midi" => match words.next()? {
+            "off" => Command::Midi(None),
+            "on" => Command::Midi(Some((DEFAULT_MIDI_CHANNEL, DEFAULT_CC_NUMBERS))),
+            channel => Command::Midi(Some((
+                channel.parse().ok()?,
+                [
+                    words.next()?.parse().ok()?,
+                    words.next()?.parse().ok()?,
+                    words.next()?.parse().ok()?,
+                ],
+            ))),
+        },
+        "kelvin" => match words.next()? {
+            "off" => Command::WhitePoint(None),
+            kelvin => Command::WhitePoint(Some(parse_f32(kelvin)?)),
+        },
+        "huecap" => Command::HueRateCap {
+            turns_per_s: parse_f32(words.next()?)?,
+        },
+        "ledgamma" => Command::LedGamma {
+            gamma: parse_f32(words.next()?)?,
+        },
+        "blend" => Command::Blend {
+            ms: words.next()?.parse().ok()?,
+        },
+        "scan" => Command::ScanRate {
+            hz: words.next()?.parse().ok()?,
+        },
+        "gamma" => match words.next()? {
+            "on" => Command::Gamma(true),
+            "off" => Command::Gamma(false),
+            _ => return None,
+        },
+        "detent" => Command::Detent {
+            window: parse_f32(words.next()?)?,
+        },
+        "smooth" => Command::Smooth {
+            alpha: match words.next()? {
+                "off" => None,
+                alpha => Some(parse_f32(alpha)?),
+            },
+        },
+        "autoscroll" => Command::AutoScroll {
+            period_ms: match words.next()? {
+                "off" => None,
+                "on" => Some(DEFAULT_HUE_SCROLL_PERIOD_MS),
+                period_ms => Some(period_ms.parse().ok()?),
+            },
+        },
+        "code" => match words.next()? {
+            "on" => Command::ColorCode(true),
+            "off" => Command::ColorCode(false),
+            _ => return None,
+        },
+        "overview" => match words.next()? {
+            "on" => Command::Overview(true),
+            "off" => Command::Overview(false),
+            _ => return None,
+        },
+        "swatch" => match words.next()? {
+            "on" => Command::Swatch(true),
+            "off" => Command::Swatch(false),
+            _ => return None,
+        },
+        "contrast" => match words.next()? {
+            "high" => Command::Contrast(true),
+            "normal" => Command::Contrast(false),
+            _ => return None,
+        },
+        "history" => Command::History,
+        "version" => Command::Version,
+        "reinit" => Command::Reinit,
+        "readout" => Command::Readout,
+        "irqs" => Command::IrqRates,
+        "balance" => match words.next()? {
+            "auto" => Command::AutoBalance,
+            "off" => Command::ClearBalance,
+            _ => return None,
+        },
+        "primaries" => match words.next()? {
+            "off" => Command::Primaries(None),
+            r_hue => Command::Primaries(Some([
+                parse_f32(r_hue)?,
+                parse_f32(words.next()?)?,
+                parse_f32(words.next()?)?,
+            ])),
+        },
+        "pages" => Command::Pages(ColorSpace::from_name(words.next()?)?),
+        "transition" => Command::Transition(PageTransition::from_name(words.next()?)?),
+        "fade" => Command::Fade {
+            color: Hsv {
+                h: parse_f32(words.next()?)?,
+                s: parse_f32(words.next()?)?,
+                v: parse_f32(words.next()?)?,
+            },
+            ms: words.next()?.parse().ok()?,
+            direction: match words.next() {
+                Some(name) => HueDirection::from_name(name)?,
+                None => HueDirection::Shortest,
+            },
+        },
+        "blank" => match words.next()? {
+            "off" => Command::Blank { timeout_ms: None },
+            seconds => Command::Blank {
+                timeout_ms: Some(seconds.parse::<u32>().ok()?.saturating_mul(1000)),
+            },
+        },
+        "random" => Command::Random,
+        "match" => Command::Match([
+            words.next()?.parse().ok()?,
+            words.next()?.parse().ok()?,
+            words.next()?.parse().ok()?,
+        ]),
+        "seed" => Command::Seed(words.next()?.parse().ok()?),
+        "hold" => Command::Hold {
+            ms: words.next()?.parse().ok()?,
+        },
+        "lock" => Command::Lock,
+        "release" => Command::Release,
+        "modes" => Command::Modes,
+        "demo" => Command::Demo,
+        "watch" => match words.next()? {
+            "on" => Command::Watch(true),
+            "off" => Command::Watch(false),
+            _ => return None,
+        },
+        "mode" => match words.next()? {
+            "next" => Command::NextMode,
+            name => Command::SetMode(Mode::from_name(name)?),
+        },
+        "ambient" => match words.next()? {
+            "on" => Command::Ambient(true),
+            "off" => Command::Ambient(false),
+            dark => Command::AmbientBounds {
+                dark: parse_f32(dark)?,
+                bright: parse_f32(words.next()?)?,
+                min_scale: parse_f32(words.next()?)?,
+            },
+        },
+        assignment if assignment.contains('=') => parse_assignment(assignment)?,
+        _ => return None,
+    };
+
+    // reject trailing garbage so typos are reported rather than half-applied
+    match words.next() {
+        Some(_) => None,
+        None => Some(command),
+    }
+}
+
+/// PRIVATE
+/// Parse a number argument, rejecting NaN and the infinities (including overflowing literals such as 1e40), which
+/// would otherwise slip through every later f32::clamp and break the ranges the rendering relies on
+fn parse_f32(word: &str) -> Option<f32> {
+    word.parse::<f32>().ok().filter(|value| value.is_finite())
+}
+
+/// PRIVATE
+/// Parse an axis name (h, s, or v) into its HSVPage
+fn parse_page(word: &str) -> Option<HSVPage> {
+    match word {
+        "h" => Some(HSVPage::H),
+        "s" => Some(HSVPage::S),
+        "v" => Some(HSVPage::V),
+        _ => None,
+    }
+}
+
+/// PRIVATE
+/// Parse a quick `h=0.3` style assignment (no spaces) into a Set of that axis. A value which is not a number in [0,1]
+/// gives a BadAssignment instead, so it is reported rather than clamped. None if the name is not h, s, or v
+fn parse_assignment(assignment: &str) -> Option<Command> {
+    let (name, value) = assignment.split_once('=')?;
+    let page = parse_page(name)?;
+    Some(match parse_f32(value) {
+        Some(value) if (0.0..=1.0).contains(&value) => Command::Set { page, value },
+        _ => Command::BadAssignment { page },
+    })
+}
+
+/// PRIVATE
+/// Parse an RGB channel name (r, g, or b) into its index
+fn parse_channel(word: &str) -> Option<usize> {
+    match word {
+        "r" => Some(0),
+        "g" => Some(1),
+        "b" => Some(2),
+        _ => None,
+    }
+}
+
+/// HsvLiteral wraps an Hsv so that it Displays as a ready-to-paste Rust literal followed by its RGB hex code,
+/// e.g. `Hsv { h: 0.9167, s: 0.7500, v: 0.8000 } // #cc337f`
+pub struct HsvLiteral(pub Hsv);
+
+impl fmt::Display for HsvLiteral {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hsv = self.0;
+        let hex = hex_code(hsv.to_rgb().to_u8());
+        write!(
+            f,
+            "Hsv {{ h: {:.4}, s: {:.4}, v: {:.4} }} // {}",
+            hsv.h,
+            hsv.s,
+            hsv.v,
+            core::str::from_utf8(&hex).unwrap_or("#??????")
+        )
+    }
+}
+
+/// CssColor wraps an Hsv so that it Displays as CSS `hsl(...)` and `#rrggbb` colors, ready to paste into a stylesheet
+/// or design tool, e.g. `hsl(330, 60%, 50%) #cc337f`. The hue is in whole degrees and the percentages are rounded.
+pub struct CssColor(pub Hsv);
+
+impl fmt::Display for CssColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hsl = self.0.to_hsl();
+        let percent = |x: f32| (x.clamp(0.0, 1.0) * 100.0 + 0.5) as u32;
+        let degrees = (hsl.h.clamp(0.0, 1.0) * 360.0 + 0.5) as u32 % 360;
+        let hex = hex_code(self.0.to_rgb().to_u8());
+        write!(
+            f,
+            "hsl({}, {}%, {}%) {}",
+            degrees,
+            percent(hsl.s),
+            percent(hsl.l),
+            core::str::from_utf8(&hex).unwrap_or("#??????")
+        )
+    }
+}
+
+/// PRIVATE
+/// Format 8-bit RGB channels as the ASCII `#rrggbb` hex code (lowercase)
+const fn hex_code(rgb: [u8; 3]) -> [u8; 7] {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut hex = *b"#000000";
+    let mut c = 0;
+    while c < 3 {
+        hex[1 + 2 * c] = DIGITS[(rgb[c] >> 4) as usize];
+        hex[2 + 2 * c] = DIGITS[(rgb[c] & 0x0F) as usize];
+        c += 1;
+    }
+    hex
+}
+
+// the starting magenta of the `css` example formats as the hex code documented for it
+const _: () = {
+    let hex = hex_code(super::color_control::STARTING_HSV.to_rgb().to_u8());
+    let expected = b"#cc337f";
+    let mut i = 0;
+    while i < expected.len() {
+        assert!(hex[i] == expected[i], "the css example hex code is wrong");
+        i += 1;
+    }
+    assert!(matches!(
+        hex_code([0x0a, 0xf0, 0x5c]),
+        [b'#', b'0', b'a', b'f', b'0', b'5', b'c']
+    ));
+};
+
+// the color of the HsvLiteral example formats as the hex code documented for it
+const _: () = {
+    let literal = Hsv {
+        h: 0.9167,
+        s: 0.75,
+        v: 0.8,
+    };
+    assert!(
+        matches!(
+            hex_code(literal.to_rgb().to_u8()),
+            [b'#', b'c', b'c', b'3', b'3', b'7', b'f']
+        ),
+        "the HsvLiteral example hex code is wrong"
+    );
+};
@@ -0,0 +1,151 @@
+//! scheduler.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The scheduler module contains the Scheduler struct, a small cooperative scheduler for the main.rs event loop: each
+//! periodic housekeeping task is registered once with its period and a plain fn taking the loop context, and a single
+//! tick() per loop iteration runs every task that has come due, in registration order. This replaces a scattered
+//! timestamp comparison per task.
+//!
+//! Tasks only run as often as tick() is called, so a period shorter than the loop iteration is run once per iteration.
+//! A task that falls more than a whole period behind (e.g. while the loop was busy calibrating) runs once and resumes
+//! its cadence from then, rather than running back to back to catch up.
+
+/// Constants
+pub const SCHEDULER_CAPACITY: usize = 8; // maximum number of registered tasks
+
+/// A periodic task: called with the loop context and the SYSTEM_CLOCK timestamp of the tick running it
+pub type TaskFn<C> = fn(&mut C, u32);
+
+/// Task struct declaration: Note all fields are private
+///
+/// 1. period_us: time between two runs of the task
+/// 2. last_run_us: SYSTEM_CLOCK timestamp the task last became due at (or was registered at)
+/// 3. run: the task itself
+struct Task<C> {
+    period_us: u32,
+    last_run_us: u32,
+    run: TaskFn<C>,
+}
+
+/// Scheduler struct declaration: Note all fields are private
+///
+/// 1. tasks: statically allocated slots, filled from index 0 in registration order
+/// 2. len: number of registered tasks
+pub struct Scheduler<C> {
+    tasks: [Option<Task<C>>; SCHEDULER_CAPACITY],
+    len: usize,
+}
+
+/// Impl Scheduler
+impl<C> Scheduler<C> {
+    /// PUBLIC
+    /// Generate a new Scheduler without any tasks
+    pub const fn new() -> Self {
+        Scheduler {
+            tasks: [const { None }; SCHEDULER_CAPACITY],
+            len: 0,
+        }
+    }
+
+    /// PUBLIC
+    /// Register run to be called every period_ms (at least 1), first one period after now_us. If the scheduler is full
+    /// the task is handed back as the Err value.
+    pub const fn register(
+        &mut self,
+        period_ms: u32,
+        now_us: u32,
+        run: TaskFn<C>,
+    ) -> Result<(), TaskFn<C>> {
+        if self.len == SCHEDULER_CAPACITY {
+            return Err(run);
+        }
+        let period_ms = if period_ms == 0 { 1 } else { period_ms };
+        self.tasks[self.len] = Some(Task {
+            period_us: period_ms.saturating_mul(1000),
+            last_run_us: now_us,
+            run,
+        });
+        self.len += 1;
+        Ok(())
+    }
+
+    /// PUBLIC
+    /// Mark every task due at now_us as run, returning them as a bit mask (bit i for the ith registered task).
+    /// Timestamps are compared with wrap-around.
+    pub const fn take_due(&mut self, now_us: u32) -> u32 {
+        let mut due = 0;
+        let mut i = 0;
+        while i < self.len {
+            if let Some(task) = &mut self.tasks[i] {
+                let elapsed_us = now_us.wrapping_sub(task.last_run_us);
+                if elapsed_us >= task.period_us {
+                    due |= 1 << i;
+                    // keep the cadence unless a whole period was missed, then restart it from now
+                    task.last_run_us = if elapsed_us < 2 * task.period_us {
+                        task.last_run_us.wrapping_add(task.period_us)
+                    } else {
+                        now_us
+                    };
+                }
+            }
+            i += 1;
+        }
+        due
+    }
+
+    /// PUBLIC
+    /// Run every task due at now_us with the loop context, in registration order. Returns the number of tasks run.
+    pub fn tick(&mut self, now_us: u32, context: &mut C) -> u32 {
+        let due = self.take_due(now_us);
+        for (i, task) in self.tasks.iter().enumerate() {
+            if let Some(task) = task
+                && due & (1 << i) != 0
+            {
+                (task.run)(context, now_us);
+            }
+        }
+        due.count_ones()
+    }
+}
+
+// on a synthetic 1ms clock over one second, a 10ms task fires 100 times and a 250ms task 4 times, each exactly on its
+// period; a tick after a long stall runs a task once, not once per missed period, and a full scheduler refuses a task
+const _: () = {
+    fn noop(_: &mut (), _: u32) {}
+
+    let mut scheduler = Scheduler::<()>::new();
+    assert!(scheduler.register(10, 0, noop).is_ok());
+    assert!(scheduler.register(250, 0, noop).is_ok());
+    let mut fired = [0u32; 2];
+    let mut now_ms = 1;
+    while now_ms <= 1_000 {
+        let due = scheduler.take_due(now_ms * 1000);
+        if due & 0b01 != 0 {
+            assert!(now_ms % 10 == 0, "the 10ms task fired off its period");
+            fired[0] += 1;
+        }
+        if due & 0b10 != 0 {
+            assert!(now_ms % 250 == 0, "the 250ms task fired off its period");
+            fired[1] += 1;
+        }
+        now_ms += 1;
+    }
+    assert!(fired[0] == 100 && fired[1] == 4);
+
+    // stalled for 5 seconds: both tasks run once, then the 10ms task resumes 10ms later
+    assert!(scheduler.take_due(6_000_000) == 0b11);
+    assert!(scheduler.take_due(6_005_000) == 0);
+    assert!(scheduler.take_due(6_010_000) == 0b01);
+
+    let mut i = 2;
+    while i < SCHEDULER_CAPACITY {
+        assert!(scheduler.register(1, 0, noop).is_ok());
+        i += 1;
+    }
+    assert!(
+        scheduler.register(1, 0, noop).is_err(),
+        "a full scheduler took a task"
+    );
+};
@@ -0,0 +1,140 @@
+//! serial_control.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The serial_control module lets a host PC set the color over the MB2's USB serial port (UARTE0 on the interface
+//! chip's UART pins), e.g. from a script for automated color testing. The host sends one command per line ("\n" or
+//! "\r\n" terminated) at SERIAL_BAUDRATE, 8N1:
+//!
+//! 1. `H <hue>` / `S <sat>` / `V <value>`: set that component to a value in [0,1], answered with `OK`
+//! 2. `GET`: answered with the current color as `HSV <h> <s> <v>`
+//!
+//! Commands are case-insensitive. Anything else, including a value outside [0,1], is answered with `ERR <reason>`
+//! and leaves the color untouched. Like an RTT `set`, a component set over serial is held from the pot (see
+//! arbitration.rs).
+//!
+//! The port is polled from the main.rs event loop. The 4 byte UARTE RX FIFO plus the 1 byte DMA buffer cover ~5ms of
+//! main loop stalls at SERIAL_BAUDRATE, so a host should wait for each reply before sending the next line.
+
+use core::fmt::{self, Write as _};
+
+use embedded_io::{Read, ReadReady, Write};
+use microbit::{
+    hal::uarte::{Baudrate, Parity, Pins, Uarte, UarteRx, UarteTx},
+    pac::UARTE0,
+};
+
+use super::fmt_buf::FmtBuf;
+use super::hsv_display::HSVPage;
+use super::rtt_command::CommandReader;
+
+/// Constants
+const SERIAL_BAUDRATE: Baudrate = Baudrate::BAUD9600; // ~1ms per byte, slow enough for the polled receive
+const REPLY_CAPACITY: usize = 48; // longest reply line, without the "\r\n"
+const TX_CHUNK: usize = 16; // DMA buffer the replies are sent through, chunk by chunk
+
+/// A command received over the serial port
+///
+/// 1. Set: set the component of page to value, already checked to lie in [0,1]
+/// 2. Get: reply with the current color
+#[derive(Clone, Copy)]
+pub enum SerialCommand {
+    Set { page: HSVPage, value: f32 },
+    Get,
+}
+
+/// PUBLIC
+/// Parse one received line into a SerialCommand, or the reason it was rejected
+pub fn parse_serial(line: &str) -> Result<SerialCommand, &'static str> {
+    let mut words = line.split_whitespace();
+    let command = words.next().ok_or("empty line")?;
+    let page = if command.eq_ignore_ascii_case("get") {
+        if words.next().is_some() {
+            return Err("GET takes no value");
+        }
+        return Ok(SerialCommand::Get);
+    } else if command.eq_ignore_ascii_case("h") {
+        HSVPage::H
+    } else if command.eq_ignore_ascii_case("s") {
+        HSVPage::S
+    } else if command.eq_ignore_ascii_case("v") {
+        HSVPage::V
+    } else {
+        return Err("unknown command");
+    };
+
+    let value: f32 = words
+        .next()
+        .ok_or("missing value")?
+        .parse()
+        .map_err(|_| "value is not a number")?;
+    if words.next().is_some() {
+        return Err("more than one value");
+    }
+    if !(0.0..=1.0).contains(&value) {
+        return Err("value outside [0,1]");
+    }
+    Ok(SerialCommand::Set { page, value })
+}
+
+/// SerialControl struct declaration: Note all fields are private
+///
+/// 1. tx/rx: UARTE0 split into its transmit and receive halves
+/// 2. reader: assembles the received bytes into lines
+pub struct SerialControl {
+    tx: UarteTx<UARTE0>,
+    rx: UarteRx<UARTE0>,
+    reader: CommandReader,
+}
+
+/// Impl SerialControl
+impl SerialControl {
+    /// PUBLIC
+    /// Generate a new SerialControl, taking ownership of UARTE0 on the given pins. Must only be called once, since it
+    /// allocates the static DMA buffers.
+    pub fn new(uarte: UARTE0, pins: Pins) -> Self {
+        let uarte = Uarte::new(uarte, pins, Parity::EXCLUDED, SERIAL_BAUDRATE);
+        let tx_buf = cortex_m::singleton!(: [u8; TX_CHUNK] = [0; TX_CHUNK]).unwrap();
+        let rx_buf = cortex_m::singleton!(: [u8; 1] = [0; 1]).unwrap();
+        let (tx, rx) = uarte.split(tx_buf, rx_buf).unwrap();
+        SerialControl {
+            tx,
+            rx,
+            reader: CommandReader::new(),
+        }
+    }
+
+    /// PUBLIC
+    /// Drain the bytes received so far, returning the command of the first complete, valid line among them (the
+    /// bytes after it wait for the next poll). A malformed line is answered with `ERR <reason>` and skipped, and an
+    /// empty one is ignored.
+    pub fn poll(&mut self) -> Option<SerialCommand> {
+        while let Ok(true) = self.rx.read_ready() {
+            let mut byte = [0u8; 1];
+            if !matches!(self.rx.read(&mut byte), Ok(1)) {
+                continue;
+            }
+            let parsed = match self.reader.push(byte[0]) {
+                Some("") | None => continue,
+                Some(line) => parse_serial(line),
+            };
+            match parsed {
+                Ok(command) => return Some(command),
+                Err(reason) => self.reply(format_args!("ERR {}", reason)),
+            }
+        }
+        None
+    }
+
+    /// PUBLIC
+    /// Send one reply line, truncated to REPLY_CAPACITY and terminated with "\r\n". Blocks until it is sent
+    pub fn reply(&mut self, args: fmt::Arguments) {
+        let mut line = FmtBuf::<REPLY_CAPACITY>::new();
+        line.write_fmt(args).ok();
+        // a failed reply is dropped: the host times out waiting for it, and the next line starts afresh
+        self.tx.write_all(line.as_str().as_bytes()).ok();
+        self.tx.write_all(b"\r\n").ok();
+        self.tx.flush().ok();
+    }
+}
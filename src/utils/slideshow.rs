@@ -0,0 +1,127 @@
+//! slideshow.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The slideshow module contains the Slideshow struct, the state machine behind the slideshow mode: the color slowly
+//! fades to each of the PRESETS in turn (see presets.rs), dwells on it, then fades on to the next, cycling through the
+//! table. The main.rs event loop polls it and starts each fade on the ColorControler.
+
+use super::app_config::DEFAULT_APP_CONFIG;
+use super::presets::{PRESETS, Preset};
+
+/// Constants
+pub const DEFAULT_DWELL_MS: u32 = DEFAULT_APP_CONFIG.slideshow_dwell_ms; // time shown on each preset
+pub const DEFAULT_FADE_MS: u32 = DEFAULT_APP_CONFIG.slideshow_fade_ms; // time fading to the next preset
+pub const MIN_DWELL_MS: u32 = 1_000; // shortest dwell selectable with the pot
+pub const MAX_DWELL_MS: u32 = 60_000; // longest dwell selectable with the pot
+const MAX_PHASE_MS: u32 = 60 * 60 * 1000; // 1 hour, safely below the ~71 minute SYSTEM_CLOCK wrap
+
+/// PUBLIC
+/// The dwell time selected by the pot percentage [0,1], from MIN_DWELL_MS to MAX_DWELL_MS
+pub fn dwell_from_pot(pot: f32) -> u32 {
+    MIN_DWELL_MS + (pot.clamp(0.0, 1.0) * (MAX_DWELL_MS - MIN_DWELL_MS) as f32) as u32
+}
+
+/// The phase of the slideshow
+///
+/// 1. Starting: enabled, the fade to the current preset has not been started yet
+/// 2. Fading: fading to the current preset
+/// 3. Dwelling: showing the current preset
+#[derive(Clone, Copy, PartialEq)]
+pub enum SlideshowPhase {
+    Starting,
+    Fading,
+    Dwelling,
+}
+
+/// Slideshow struct declaration: Note all fields are private
+///
+/// 1. enabled: whether the slideshow is running
+/// 2. dwell_ms: time spent showing each preset once its fade has completed
+/// 3. fade_ms: time spent fading from one preset to the next
+/// 4. index: index into PRESETS of the current preset
+/// 5. phase: the current SlideshowPhase
+/// 6. phase_start_us: SYSTEM_CLOCK timestamp at which the current phase began
+pub struct Slideshow {
+    enabled: bool,
+    dwell_ms: u32,
+    fade_ms: u32,
+    index: usize,
+    phase: SlideshowPhase,
+    phase_start_us: u32,
+}
+
+/// Impl Slideshow
+impl Slideshow {
+    /// PUBLIC
+    /// Generate a new, disabled, Slideshow with the default dwell and fade times, starting at the first preset
+    pub const fn new() -> Self {
+        Slideshow {
+            enabled: false,
+            dwell_ms: DEFAULT_DWELL_MS,
+            fade_ms: DEFAULT_FADE_MS,
+            index: 0,
+            phase: SlideshowPhase::Starting,
+            phase_start_us: 0,
+        }
+    }
+
+    /// PUBLIC
+    /// Start (or stop) the slideshow with the given dwell and fade times, each clamped to 1 hour. Starting restarts the
+    /// fade to the current preset on the next poll().
+    pub fn set_slideshow(&mut self, enabled: bool, dwell_ms: u32, fade_ms: u32) {
+        self.enabled = enabled;
+        self.dwell_ms = dwell_ms.min(MAX_PHASE_MS);
+        self.fade_ms = fade_ms.min(MAX_PHASE_MS);
+        self.phase = SlideshowPhase::Starting;
+    }
+
+    /// PUBLIC
+    /// Change the dwell time without restarting the current phase (e.g. from the pot)
+    pub fn set_dwell_ms(&mut self, dwell_ms: u32) {
+        self.dwell_ms = dwell_ms.min(MAX_PHASE_MS);
+    }
+
+    /// PUBLIC
+    /// return the dwell time
+    pub fn dwell_ms(&self) -> u32 {
+        self.dwell_ms
+    }
+
+    /// PUBLIC
+    /// return the fade time
+    pub fn fade_ms(&self) -> u32 {
+        self.fade_ms
+    }
+
+    /// PUBLIC
+    /// Advance the state machine to now_us. Returns the preset to start fading to (over fade_ms) when a fade begins,
+    /// otherwise None. Timestamps are compared with wrap-around.
+    pub fn poll(&mut self, now_us: u32) -> Option<Preset> {
+        if !self.enabled {
+            return None;
+        }
+
+        let elapsed_us = now_us.wrapping_sub(self.phase_start_us);
+        match self.phase {
+            SlideshowPhase::Starting => {
+                self.phase = SlideshowPhase::Fading;
+                self.phase_start_us = now_us;
+                Some(PRESETS[self.index])
+            }
+            SlideshowPhase::Fading if elapsed_us >= self.fade_ms * 1000 => {
+                self.phase = SlideshowPhase::Dwelling;
+                self.phase_start_us = now_us;
+                None
+            }
+            SlideshowPhase::Dwelling if elapsed_us >= self.dwell_ms * 1000 => {
+                self.index = (self.index + 1) % PRESETS.len();
+                self.phase = SlideshowPhase::Fading;
+                self.phase_start_us = now_us;
+                Some(PRESETS[self.index])
+            }
+            SlideshowPhase::Fading | SlideshowPhase::Dwelling => None,
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! standby.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The standby module contains the Standby struct which implements the optional standby shimmer: with the Value at (or
+//! near) 0 the LED looks exactly like a board which is switched off, so once the Value has been that low with no
+//! interaction for STANDBY_DELAY_MS the main.rs event loop drives a barely visible, slow rainbow hue cycle instead.
+//! Any interaction (pot movement, button press, or RTT command, see inactivity.rs) ends the standby and the output
+//! returns to normal.
+//!
+//! The shimmer is rendered through ColorControler::set_drive_override, so it leaves the stored color untouched, and its
+//! hue cycle is limited by the hue rate cap like every other hue animation (see hue_rate.rs).
+
+use super::hsv_rgb_convert::Hsv;
+use super::hue_rate::limit_hue_period_ms;
+
+/// Constants
+pub const STANDBY_VALUE_THRESHOLD: f32 = 0.02; // a Value at or below this counts as the output being off
+pub const STANDBY_DELAY_MS: u32 = 30_000; // the output has to be off and untouched for 30 seconds
+pub const DEFAULT_STANDBY_BRIGHTNESS: f32 = 0.03; // Value of the shimmer, just visible in a dim room
+pub const MAX_STANDBY_BRIGHTNESS: f32 = 0.1; // brighter would no longer read as a standby hint
+pub const DEFAULT_STANDBY_PERIOD_MS: u32 = 60_000; // one trip around the color wheel per minute
+const MIN_STANDBY_PERIOD_MS: u32 = 1_000; // faster cycles look like the rainbow mode rather than a standby hint
+
+/// PUBLIC
+/// The standby entry decision: is the Value at or below STANDBY_VALUE_THRESHOLD with no interaction for at least
+/// STANDBY_DELAY_MS between last_activity_us and now_us? Timestamps are compared with wrap-around.
+pub const fn standby_due(value: f32, now_us: u32, last_activity_us: u32) -> bool {
+    value <= STANDBY_VALUE_THRESHOLD
+        && now_us.wrapping_sub(last_activity_us) >= STANDBY_DELAY_MS * 1000
+}
+
+/// PUBLIC
+/// The shimmer color elapsed_ms into the standby: fully saturated at brightness, its hue travelling once around the
+/// color wheel every period_ms
+pub const fn standby_color(brightness: f32, period_ms: u32, elapsed_ms: u32) -> Hsv {
+    Hsv {
+        h: (elapsed_ms % period_ms) as f32 / period_ms as f32,
+        s: 1.0,
+        v: brightness,
+    }
+}
+
+/// What the output should do for the standby
+///
+/// 1. Show: drive the given shimmer color
+/// 2. Exit: the standby has ended, return the output to normal
+#[derive(Clone, Copy)]
+pub enum StandbyUpdate {
+    Show(Hsv),
+    Exit,
+}
+
+/// Standby struct declaration: Note all fields are private
+///
+/// 1. enabled: whether the standby shimmer is used at all
+/// 2. brightness: Value [0,MAX_STANDBY_BRIGHTNESS] of the shimmer
+/// 3. period_ms: time the shimmer takes to cycle once around the color wheel
+/// 4. since_us: SYSTEM_CLOCK timestamp at which the current standby began, None while not in standby
+pub struct Standby {
+    enabled: bool,
+    brightness: f32,
+    period_ms: u32,
+    since_us: Option<u32>,
+}
+
+/// Impl Standby
+impl Standby {
+    /// PUBLIC
+    /// Generate a new, disabled, Standby with the default brightness and period
+    pub const fn new() -> Self {
+        Standby {
+            enabled: false,
+            brightness: DEFAULT_STANDBY_BRIGHTNESS,
+            period_ms: DEFAULT_STANDBY_PERIOD_MS,
+            since_us: None,
+        }
+    }
+
+    /// PUBLIC
+    /// Enable or disable the standby shimmer. A running standby ends at the next poll() once disabled
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// PUBLIC
+    /// Set the shimmer brightness (clamped to [0,MAX_STANDBY_BRIGHTNESS], a non-finite one keeping the current
+    /// brightness) and hue cycle period (at least MIN_STANDBY_PERIOD_MS)
+    pub fn set_look(&mut self, brightness: f32, period_ms: u32) {
+        if brightness.is_finite() {
+            self.brightness = brightness.clamp(0.0, MAX_STANDBY_BRIGHTNESS);
+        }
+        self.period_ms = period_ms.max(MIN_STANDBY_PERIOD_MS);
+    }
+
+    /// PUBLIC
+    /// return the shimmer brightness and hue cycle period
+    pub fn look(&self) -> (f32, u32) {
+        (self.brightness, self.period_ms)
+    }
+
+    /// PUBLIC
+    /// Advance the standby to now_us, due being the standby_due() decision. Returns the shimmer color to show while in
+    /// standby (its hue cycling no faster than hue_rate_cap turns per second), Exit once when the standby ends, and
+    /// None otherwise.
+    pub const fn poll(
+        &mut self,
+        due: bool,
+        now_us: u32,
+        hue_rate_cap: f32,
+    ) -> Option<StandbyUpdate> {
+        if !(self.enabled && due) {
+            return match self.since_us.take() {
+                Some(_) => Some(StandbyUpdate::Exit),
+                None => None,
+            };
+        }
+        let since_us = match self.since_us {
+            Some(since_us) => since_us,
+            None => now_us,
+        };
+        self.since_us = Some(since_us);
+        let elapsed_ms = now_us.wrapping_sub(since_us) / 1000;
+        Some(StandbyUpdate::Show(standby_color(
+            self.brightness,
+            limit_hue_period_ms(self.period_ms, hue_rate_cap),
+            elapsed_ms,
+        )))
+    }
+}
+
+// a dark, untouched output enters standby and shimmers, while a lit output or any activity keeps (or takes) it out
+const _: () = {
+    let cap = super::hue_rate::DEFAULT_HUE_RATE_CAP;
+    let idle_us = STANDBY_DELAY_MS * 1000;
+    assert!(standby_due(0.0, idle_us, 0));
+    assert!(standby_due(STANDBY_VALUE_THRESHOLD, idle_us, 0));
+    assert!(
+        !standby_due(0.5, idle_us, 0),
+        "a lit output entered standby"
+    );
+    assert!(!standby_due(0.0, idle_us - 1, 0), "standby entered early");
+
+    let mut standby = Standby {
+        enabled: true,
+        ..Standby::new()
+    };
+    assert!(matches!(
+        standby.poll(standby_due(0.0, idle_us, 0), idle_us, cap),
+        Some(StandbyUpdate::Show(Hsv { s: 1.0, .. }))
+    ));
+    let quarter_us = idle_us + DEFAULT_STANDBY_PERIOD_MS / 4 * 1000;
+    match standby.poll(standby_due(0.0, quarter_us, 0), quarter_us, cap) {
+        Some(StandbyUpdate::Show(color)) => {
+            assert!(color.h == 0.25 && color.v == DEFAULT_STANDBY_BRIGHTNESS)
+        }
+        _ => panic!("standby left without activity"),
+    }
+    // activity at quarter_us ends the standby once
+    assert!(matches!(
+        standby.poll(
+            standby_due(0.0, quarter_us + 1, quarter_us),
+            quarter_us + 1,
+            cap
+        ),
+        Some(StandbyUpdate::Exit)
+    ));
+    assert!(
+        standby
+            .poll(
+                standby_due(0.0, quarter_us + 2, quarter_us),
+                quarter_us + 2,
+                cap
+            )
+            .is_none()
+    );
+    assert!(
+        Standby::new().poll(true, 0, cap).is_none(),
+        "a disabled standby shimmered"
+    );
+};
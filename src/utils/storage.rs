@@ -0,0 +1,254 @@
+//! storage.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The storage module contains the ConfigStore struct which persists the Config (see config.rs) in the last 4KB page
+//! of the nRF52833's 512KB internal flash, and the last color (see persist.rs) in the page below it, using the NVMC
+//! (Non-Volatile Memory Controller). The firmware is far smaller than the 504KB of flash below those pages, so they
+//! are never shared with code. Each blob has a page of its own, so saving the color never erases the Config.
+//!
+//! Every flash access first polls the NVMC ready flag for a bounded time (the HAL's own wait spins forever) and a
+//! save reads the page back to verify it. A failed attempt is retried up to FLASH_ATTEMPTS times with a doubling
+//! backoff (see retry_step), after which the error is returned rather than hanging or leaving the failure unnoticed.
+
+use core::fmt;
+
+use embedded_storage::nor_flash::{NorFlash, NorFlashError, NorFlashErrorKind, ReadNorFlash};
+use microbit::{hal::nvmc::Nvmc, pac::NVMC};
+
+use super::config::{CONFIG_SIZE, Config, ConfigError};
+use super::hsv_rgb_convert::Hsv;
+use super::persist::{HSV_BLOB_SIZE, hsv_from_bytes, hsv_to_bytes};
+
+/// Constants
+const STORAGE_ADDR: usize = 0x0007_E000; // last two flash pages: 512KB - 8KB
+const PAGE_SIZE: usize = 4 * 1024; // nRF52833 flash page (erase unit) size
+const COLOR_PAGE_OFFSET: u32 = 0; // the color page, 0x7E000
+const CONFIG_PAGE_OFFSET: u32 = PAGE_SIZE as u32; // the config page, the last flash page at 0x7F000
+pub const FLASH_ATTEMPTS: u32 = 3; // attempts at a flash operation before giving up
+pub const BACKOFF_BASE_CYCLES: u32 = 64_000; // 1ms at 64MHz before the first retry, doubling for each later one
+const READY_POLL_CYCLES: u32 = 640; // 10us at 64MHz between polls of the NVMC ready flag
+const READY_POLL_LIMIT: u32 = 20_000; // 200ms of polling, over twice the 85ms worst case page erase
+
+/// Reason a Config could not be loaded from flash
+///
+/// 1. Flash: the NVMC read itself failed
+/// 2. Config: the page was read but is blank or corrupt
+#[derive(Clone, Copy, PartialEq)]
+pub enum LoadError {
+    Flash,
+    Config(ConfigError),
+}
+
+/// Reason a Config could not be saved to flash. Displays as a descriptive message.
+///
+/// 1. NotReady: the NVMC ready flag stayed clear for the whole READY_POLL_LIMIT
+/// 2. Flash: the NVMC erase or write itself failed
+/// 3. Verify: the page read back does not hold the Config written
+#[derive(Clone, Copy, PartialEq)]
+pub enum SaveError {
+    NotReady,
+    Flash(NorFlashErrorKind),
+    Verify,
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            SaveError::NotReady => write!(f, "NVMC never became ready"),
+            SaveError::Flash(kind) => write!(f, "NVMC operation failed ({:?})", kind),
+            SaveError::Verify => write!(f, "page read back does not match"),
+        }
+    }
+}
+
+/// The outcome of one attempt at a flash operation
+///
+/// 1. Done: the attempt succeeded
+/// 2. Retry: the attempt failed, wait backoff_cycles and try again
+/// 3. GiveUp: the attempt failed and was the last of FLASH_ATTEMPTS
+#[derive(Clone, Copy, PartialEq)]
+pub enum RetryStep {
+    Done,
+    Retry { backoff_cycles: u32 },
+    GiveUp,
+}
+
+/// PUBLIC
+/// The retry state machine: what follows attempt number attempt (from 1) having succeeded or failed. The backoff
+/// starts at BACKOFF_BASE_CYCLES and doubles with each failed attempt.
+pub const fn retry_step(attempt: u32, succeeded: bool) -> RetryStep {
+    if succeeded {
+        RetryStep::Done
+    } else if attempt >= FLASH_ATTEMPTS {
+        RetryStep::GiveUp
+    } else {
+        RetryStep::Retry {
+            backoff_cycles: BACKOFF_BASE_CYCLES << (attempt - 1),
+        }
+    }
+}
+
+// success after a retry stops retrying, a failure backs off for longer each time, and the last failure gives up
+const _: () = {
+    assert!(matches!(retry_step(1, true), RetryStep::Done));
+    assert!(matches!(retry_step(2, true), RetryStep::Done));
+    assert!(matches!(
+        retry_step(1, false),
+        RetryStep::Retry {
+            backoff_cycles: BACKOFF_BASE_CYCLES
+        }
+    ));
+    assert!(matches!(
+        retry_step(2, false),
+        RetryStep::Retry { backoff_cycles } if backoff_cycles == 2 * BACKOFF_BASE_CYCLES
+    ));
+    assert!(matches!(
+        retry_step(FLASH_ATTEMPTS, false),
+        RetryStep::GiveUp
+    ));
+    assert!(
+        FLASH_ATTEMPTS >= 1,
+        "a flash operation needs at least one attempt"
+    );
+};
+
+/// PUBLIC
+/// Run op until it succeeds, retrying failures as retry_step directs. Returns the first success or the last error.
+/// op is any fallible closure, so the retry can be driven by a mock flash as well as the NVMC.
+pub fn with_retry<T, E>(mut op: impl FnMut() -> Result<T, E>) -> Result<T, E> {
+    let mut attempt = 1;
+    loop {
+        let result = op();
+        match retry_step(attempt, result.is_ok()) {
+            RetryStep::Retry { backoff_cycles } => cortex_m::asm::delay(backoff_cycles),
+            RetryStep::Done | RetryStep::GiveUp => return result,
+        }
+        attempt += 1;
+    }
+}
+
+/// PUBLIC
+/// Poll ready until it returns true, at most READY_POLL_LIMIT times READY_POLL_CYCLES apart. Returns whether it did.
+pub fn wait_ready(mut ready: impl FnMut() -> bool) -> bool {
+    for _ in 0..READY_POLL_LIMIT {
+        if ready() {
+            return true;
+        }
+        cortex_m::asm::delay(READY_POLL_CYCLES);
+    }
+    false
+}
+
+/// PRIVATE
+/// Whether the NVMC ready flag is set
+fn nvmc_ready() -> bool {
+    // SAFETY: a read of the NVMC READY register has no side effects, so it cannot disturb the Nvmc driver owning the
+    // peripheral
+    let nvmc = unsafe { &*NVMC::ptr() };
+    nvmc.ready.read().ready().is_ready()
+}
+
+/// PRIVATE
+/// The SaveError of a failed NVMC operation
+fn flash_error(e: impl NorFlashError) -> SaveError {
+    SaveError::Flash(e.kind())
+}
+
+/// ConfigStore struct declaration: Note all fields are private
+///
+/// 1. nvmc: NVMC driver owning the color and config flash pages
+pub struct ConfigStore {
+    nvmc: Nvmc<NVMC>,
+}
+
+/// Impl ConfigStore
+impl ConfigStore {
+    /// PUBLIC
+    /// Generate a new ConfigStore, taking ownership of the NVMC peripheral
+    pub fn new(nvmc: NVMC) -> Self {
+        // SAFETY: the two pages lie within the internal flash and are only ever accessed through this ConfigStore
+        // (which owns the only NVMC), so handing them out as a unique &'static mut is sound
+        let pages =
+            unsafe { core::slice::from_raw_parts_mut(STORAGE_ADDR as *mut u8, 2 * PAGE_SIZE) };
+        ConfigStore {
+            nvmc: Nvmc::new(nvmc, pages),
+        }
+    }
+
+    /// PUBLIC
+    /// Read the Config stored in flash, retrying a failed read
+    pub fn load(&mut self) -> Result<Config, LoadError> {
+        let bytes = self.read_page::<CONFIG_SIZE>(CONFIG_PAGE_OFFSET)?;
+        Config::from_bytes(&bytes).map_err(LoadError::Config)
+    }
+
+    /// PUBLIC
+    /// Erase the config page and write config to it, retrying a failed or unverified attempt
+    pub fn save(&mut self, config: &Config) -> Result<(), SaveError> {
+        let bytes = config.to_bytes();
+        with_retry(|| self.try_save(CONFIG_PAGE_OFFSET, &bytes))
+    }
+
+    /// PUBLIC
+    /// Read the color saved by save_hsv(), None if the read failed or the page holds no valid color (see persist.rs)
+    pub fn load_hsv(&mut self) -> Option<Hsv> {
+        let bytes = self.read_page::<HSV_BLOB_SIZE>(COLOR_PAGE_OFFSET).ok()?;
+        hsv_from_bytes(&bytes)
+    }
+
+    /// PUBLIC
+    /// Save hsv to the color page, retrying a failed or unverified attempt. A color already saved is not written
+    /// again, sparing the page an erase
+    pub fn save_hsv(&mut self, hsv: &Hsv) -> Result<(), SaveError> {
+        let bytes = hsv_to_bytes(hsv);
+        if self.read_page(COLOR_PAGE_OFFSET) == Ok(bytes) {
+            return Ok(());
+        }
+        with_retry(|| self.try_save(COLOR_PAGE_OFFSET, &bytes))
+    }
+
+    /// PRIVATE
+    /// Read the first N bytes of the page at offset, retrying a failed read
+    fn read_page<const N: usize>(&mut self, offset: u32) -> Result<[u8; N], LoadError> {
+        let mut bytes = [0u8; N];
+        with_retry(|| {
+            if !wait_ready(nvmc_ready) {
+                return Err(LoadError::Flash);
+            }
+            self.nvmc
+                .read(offset, &mut bytes)
+                .map_err(|_| LoadError::Flash)
+        })?;
+        Ok(bytes)
+    }
+
+    /// PRIVATE
+    /// One attempt at erasing the page at offset, writing bytes to it, and reading them back
+    fn try_save<const N: usize>(&mut self, offset: u32, bytes: &[u8; N]) -> Result<(), SaveError> {
+        let ready = || {
+            if wait_ready(nvmc_ready) {
+                Ok(())
+            } else {
+                Err(SaveError::NotReady)
+            }
+        };
+
+        ready()?;
+        self.nvmc
+            .erase(offset, offset + PAGE_SIZE as u32)
+            .map_err(flash_error)?;
+        ready()?;
+        self.nvmc.write(offset, bytes).map_err(flash_error)?;
+        ready()?;
+        let mut read_back = [0u8; N];
+        self.nvmc
+            .read(offset, &mut read_back)
+            .map_err(flash_error)?;
+        if read_back != *bytes {
+            return Err(SaveError::Verify);
+        }
+        Ok(())
+    }
+}
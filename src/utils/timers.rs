@@ -0,0 +1,64 @@
+//! timers.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The timers module is the one place stating what every TIMER peripheral runs at. Each TIMER counts the 16MHz
+//! TIMER_BASE_CLOCK_HZ divided by 2^prescaler:
+//!
+//! 1. TIMER0: the matrix row scan of HSVDisplay (`display` feature), 125kHz by default and 62.5kHz-1MHz via `scan`
+//! 2. TIMER1: the Debouncer's press captures (a TimerClock, see clock.rs), compared against the cooldown, 1MHz
+//! 3. TIMER2: the ColorControler's software PWM steps (or the ppi-pwm frame, unused with hw-pwm), 1MHz
+//! 4. TIMER3: the end of each ADC averaging (refresh) period, 1MHz
+//! 5. TIMER4: the free running SYSTEM_CLOCK (a TimerClock), read as usecs by now_us() in main.rs, 1MHz
+//!
+//! TIMER1-4 are set up through the HAL Timer, which always uses HAL_TIMER_PRESCALER, so their tick math shares
+//! TIMER_TICKS_PER_MS and TIMER_TICKS_PER_US. TIMER0 is set up by the microbit Display driver with its own prescaler,
+//! which the `scan` RTT command changes at run time; only the matrix scan depends on it (see hsv_display.rs), so no
+//! timestamp, period, or PWM duty is ever computed in TIMER0 ticks and its rate may differ freely from TIMER2's.
+//!
+//! The HAL's TICKS_PER_SECOND for each of TIMER1-4 is checked against TIMER_TICKS_PER_SECOND at compile time, so a
+//! changed prescaler fails the build rather than silently breaking the timing.
+
+use microbit::hal::timer::Timer;
+use microbit::pac::{TIMER1, TIMER2, TIMER3, TIMER4};
+
+/// Constants
+pub const TIMER_BASE_CLOCK_HZ: u32 = 16_000_000; // the TIMER peripherals' clock, divided by 2^prescaler
+pub const HAL_TIMER_PRESCALER: u32 = 4; // the prescaler the HAL Timer configures
+pub const TIMER_TICKS_PER_SECOND: u32 = timer_ticks_per_second(HAL_TIMER_PRESCALER); // TIMER1-4 tick rate, 1MHz
+pub const TIMER_TICKS_PER_MS: u32 = TIMER_TICKS_PER_SECOND / 1000; // TIMER1-4 ticks per msec
+pub const TIMER_TICKS_PER_US: u32 = TIMER_TICKS_PER_SECOND / 1000 / 1000; // TIMER1-4 ticks per usec, exactly 1
+
+/// PUBLIC
+/// The tick rate (in Hz) of a TIMER running at prescaler
+pub const fn timer_ticks_per_second(prescaler: u32) -> u32 {
+    TIMER_BASE_CLOCK_HZ >> prescaler
+}
+
+// TIMER1-4 tick at the rate the timing math here assumes, and one tick is one usec: SYSTEM_CLOCK timestamps are read
+// as usecs and the PWM step and frame durations are given in usecs
+const _: () = {
+    assert!(TIMER_TICKS_PER_SECOND == 1_000_000);
+    assert!(
+        Timer::<TIMER1>::TICKS_PER_SECOND == TIMER_TICKS_PER_SECOND,
+        "TIMER1 (debounce) rate changed"
+    );
+    assert!(
+        Timer::<TIMER2>::TICKS_PER_SECOND == TIMER_TICKS_PER_SECOND,
+        "TIMER2 (PWM) rate changed"
+    );
+    assert!(
+        Timer::<TIMER3>::TICKS_PER_SECOND == TIMER_TICKS_PER_SECOND,
+        "TIMER3 (ADC refresh) rate changed"
+    );
+    assert!(
+        Timer::<TIMER4>::TICKS_PER_SECOND == TIMER_TICKS_PER_SECOND,
+        "TIMER4 (system clock) rate changed"
+    );
+    assert!(
+        TIMER_TICKS_PER_US == 1,
+        "SYSTEM_CLOCK ticks are no longer usecs"
+    );
+    assert!(TIMER_TICKS_PER_MS * 1000 == TIMER_TICKS_PER_SECOND);
+};
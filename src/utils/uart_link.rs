@@ -0,0 +1,262 @@
+//! uart_link.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The uart_link module daisy-chains several MB2s so they all show the same color. Every board transmits its color
+//! over UARTE1 whenever it changes, and with the `follower` cargo feature a board also receives the color sent by the
+//! board before it in the chain and applies it. Since a follower then transmits the received color in turn, any number
+//! of followers can be chained TX -> RX behind one master.
+//!
+//! Packet layout (PACKET_SIZE bytes):
+//! 1. byte 0: PACKET_SYNC
+//! 2. bytes 1-3: the H, S, and V components, each quantized to a byte (0-255 spanning [0,1])
+//! 3. bytes 4-5: Fletcher-16 checksum (see config.rs) of bytes 1-3, little endian
+//!
+//! The sync byte can also occur inside a color, so the receiver only trusts a packet whose checksum matches and
+//! otherwise resynchronizes on the next sync byte it has already buffered.
+//!
+//! In the MIDI mode the link sends MIDI control change messages (see midi_cc.rs) at MIDI_BAUDRATE instead of packets,
+//! so it can't drive followers meanwhile. A follower build refuses the MIDI mode: its receive DMA runs on UARTE1 at
+//! all times, and the baud rate can't be switched under a running reception.
+
+#[cfg(feature = "follower")]
+use embedded_io::{Read, ReadReady, Write};
+#[cfg(not(feature = "follower"))]
+use microbit::hal::uarte::Uarte;
+#[cfg(feature = "follower")]
+use microbit::hal::uarte::{Uarte, UarteRx, UarteTx};
+use microbit::{
+    hal::uarte::{Baudrate, Parity, Pins},
+    pac::UARTE1,
+};
+
+use super::config::fletcher16;
+use super::hsv_rgb_convert::Hsv;
+#[cfg(feature = "follower")]
+use super::hsv_rgb_convert::normalize_hue;
+use super::midi_cc::MidiCc;
+
+/// Constants
+pub const PACKET_SIZE: usize = 6; // sync byte, 3 color bytes, 2 checksum bytes
+const PACKET_SYNC: u8 = 0xA5; // first byte of every packet
+const LINK_BAUDRATE: Baudrate = Baudrate::BAUD38400; // a packet takes ~1.6ms, and the 4 byte UARTE RX FIFO covers ~1ms of main loop stalls
+#[cfg(not(feature = "follower"))]
+const MIDI_BAUDRATE: Baudrate = Baudrate::BAUD31250; // the MIDI standard rate, ~1ms per control change message
+
+/// PRIVATE
+/// Quantize a color component in [0,1] to a byte, rounding to nearest
+fn quantize(component: f32) -> u8 {
+    (component.clamp(0.0, 1.0) * 255.0 + 0.5) as u8
+}
+
+/// PUBLIC
+/// The 3 byte serialized color sent in a packet: H, S, and V each quantized to a byte
+pub fn color_bytes(hsv: Hsv) -> [u8; 3] {
+    [quantize(hsv.h), quantize(hsv.s), quantize(hsv.v)]
+}
+
+/// PUBLIC
+/// Frame the 3 color bytes into a complete packet
+pub fn encode_packet(color: [u8; 3]) -> [u8; PACKET_SIZE] {
+    let checksum = fletcher16(&color).to_le_bytes();
+    [
+        PACKET_SYNC,
+        color[0],
+        color[1],
+        color[2],
+        checksum[0],
+        checksum[1],
+    ]
+}
+
+/// PUBLIC
+/// Unframe a complete packet, returning its color or None if the sync byte or checksum is wrong. A hue of 255 wraps
+/// back to 0 like any hue of 1.0.
+#[cfg(feature = "follower")]
+pub fn decode_packet(packet: &[u8; PACKET_SIZE]) -> Option<Hsv> {
+    let color = [packet[1], packet[2], packet[3]];
+    if packet[0] != PACKET_SYNC || fletcher16(&color).to_le_bytes() != [packet[4], packet[5]] {
+        return None;
+    }
+    let component = |byte: u8| byte as f32 / 255.0;
+    Some(Hsv {
+        h: normalize_hue(component(color[0])),
+        s: component(color[1]),
+        v: component(color[2]),
+    })
+}
+
+/// PacketParser struct declaration: Note all fields are private
+///
+/// 1. buf: the bytes received so far of the packet being assembled, always starting with PACKET_SYNC
+/// 2. len: number of bytes in buf
+#[cfg(feature = "follower")]
+pub struct PacketParser {
+    buf: [u8; PACKET_SIZE],
+    len: usize,
+}
+
+/// Impl PacketParser
+///
+/// Assembles received bytes into packets
+#[cfg(feature = "follower")]
+impl PacketParser {
+    /// PUBLIC
+    /// Generate a new, empty PacketParser
+    pub const fn new() -> Self {
+        PacketParser {
+            buf: [0; PACKET_SIZE],
+            len: 0,
+        }
+    }
+
+    /// PUBLIC
+    /// Add one received byte, returning the color once it completes a valid packet. Bytes before a sync byte are
+    /// dropped, and after a bad packet the parser restarts from the next sync byte within it (if any).
+    pub fn push(&mut self, byte: u8) -> Option<Hsv> {
+        if self.len == 0 && byte != PACKET_SYNC {
+            return None;
+        }
+        self.buf[self.len] = byte;
+        self.len += 1;
+        if self.len < PACKET_SIZE {
+            return None;
+        }
+
+        let color = decode_packet(&self.buf);
+        self.len = 0;
+        if color.is_none()
+            && let Some(start) = self.buf[1..].iter().position(|&b| b == PACKET_SYNC)
+        {
+            let start = start + 1;
+            self.buf.copy_within(start.., 0);
+            self.len = PACKET_SIZE - start;
+        }
+        color
+    }
+}
+
+/// UartLink struct declaration: Note all fields are private
+///
+/// 1. uarte: UARTE1 driver, transmit only (without the `follower` feature)
+/// 2. tx/rx: UARTE1 split into its transmit and receive halves (with the `follower` feature)
+/// 3. parser: assembles the received bytes into packets (with the `follower` feature)
+/// 4. last_sent: color bytes of the last packet transmitted, None before the first
+/// 5. midi: the MIDI control change settings while in the MIDI mode, None while sending packets
+pub struct UartLink {
+    #[cfg(not(feature = "follower"))]
+    uarte: Uarte<UARTE1>,
+    #[cfg(feature = "follower")]
+    tx: UarteTx<UARTE1>,
+    #[cfg(feature = "follower")]
+    rx: UarteRx<UARTE1>,
+    #[cfg(feature = "follower")]
+    parser: PacketParser,
+    last_sent: Option<[u8; 3]>,
+    midi: Option<MidiCc>,
+}
+
+/// Impl UartLink
+impl UartLink {
+    /// PUBLIC
+    /// Generate a new UartLink, taking ownership of UARTE1 on the given pins. Must only be called once, since the
+    /// follower build allocates the static DMA buffers here.
+    pub fn new(uarte: UARTE1, pins: Pins) -> Self {
+        let uarte = Uarte::new(uarte, pins, Parity::EXCLUDED, LINK_BAUDRATE);
+        #[cfg(not(feature = "follower"))]
+        let link = UartLink {
+            uarte,
+            last_sent: None,
+            midi: None,
+        };
+        #[cfg(feature = "follower")]
+        let link = {
+            let tx_buf = cortex_m::singleton!(: [u8; PACKET_SIZE] = [0; PACKET_SIZE]).unwrap();
+            let rx_buf = cortex_m::singleton!(: [u8; 1] = [0; 1]).unwrap();
+            let (tx, rx) = uarte.split(tx_buf, rx_buf).unwrap();
+            UartLink {
+                tx,
+                rx,
+                parser: PacketParser::new(),
+                last_sent: None,
+                midi: None,
+            }
+        };
+        link
+    }
+
+    /// PUBLIC
+    /// Switch to the MIDI mode with the given settings, or back to sending daisy-chain packets (None). Either way the
+    /// next send() transmits the whole color afresh. Returns false, leaving the link as it was, for the MIDI mode in a
+    /// follower build (see the module docs)
+    pub fn set_midi(&mut self, midi: Option<MidiCc>) -> bool {
+        #[cfg(feature = "follower")]
+        if midi.is_some() {
+            return false;
+        }
+        #[cfg(not(feature = "follower"))]
+        {
+            let baudrate = if midi.is_some() {
+                MIDI_BAUDRATE
+            } else {
+                LINK_BAUDRATE
+            };
+            // SAFETY: only the baud rate register is written, between two blocking transfers of this transmit-only
+            // UARTE1 owner, so no transfer is running
+            let uarte = unsafe { &*UARTE1::ptr() };
+            uarte.baudrate.write(|w| w.baudrate().variant(baudrate));
+        }
+        self.midi = midi;
+        self.last_sent = None;
+        true
+    }
+
+    /// PRIVATE
+    /// Transmit bytes, blocking until they are sent. Returns whether the transfer succeeded
+    fn write(&mut self, bytes: &[u8]) -> bool {
+        #[cfg(not(feature = "follower"))]
+        let sent = self.uarte.write(bytes).is_ok();
+        #[cfg(feature = "follower")]
+        let sent = self.tx.write_all(bytes).is_ok() && self.tx.flush().is_ok();
+        sent
+    }
+
+    /// PUBLIC
+    /// Transmit hsv if its quantized color differs from the last one sent. Blocks for the ~1.6ms packet, or in the
+    /// MIDI mode for ~1ms per changed axis.
+    pub fn send(&mut self, hsv: Hsv) {
+        if let Some(mut midi) = self.midi {
+            let (messages, count) = midi.messages(hsv);
+            if !messages[..count].iter().all(|message| self.write(message)) {
+                midi.mark_unsent();
+            }
+            self.midi = Some(midi);
+            return;
+        }
+
+        let color = color_bytes(hsv);
+        if self.last_sent == Some(color) {
+            return;
+        }
+        if self.write(&encode_packet(color)) {
+            self.last_sent = Some(color);
+        }
+    }
+
+    /// PUBLIC
+    /// Drain the bytes received so far, returning the color of the last valid packet among them (if any)
+    #[cfg(feature = "follower")]
+    pub fn poll(&mut self) -> Option<Hsv> {
+        let mut color = None;
+        while let Ok(true) = self.rx.read_ready() {
+            let mut byte = [0u8; 1];
+            if let Ok(1) = self.rx.read(&mut byte)
+                && let Some(hsv) = self.parser.push(byte[0])
+            {
+                color = Some(hsv);
+            }
+        }
+        color
+    }
+}
@@ -0,0 +1,87 @@
+//! value_floor.rs
+//! Copyright © 2026 Sean Springer
+//! [This program is licensed under the "MIT License"]
+//! Please see the file LICENSE in the source distribution of this software for license terms.
+//!
+//! The value_floor module contains the ValueFloor policy deciding what the bottom of the Value axis means. By default
+//! a Value of 0 turns the LED completely off, which can look like a broken board, so the policy can instead keep a
+//! minimum glow at every Value, or keep the Value pot from ever reaching off while an explicit off (RTT `set v 0`, the
+//! soft power toggle) still switches the LED off.
+//!
+//! The minimum glow is applied by ColorControler at render time (before the effects, so e.g. the strobe still goes
+//! dark), and the pot floor when the Value pot is applied (see ColorControler::apply_pot).
+
+/// Constants
+pub const MIN_VISIBLE_VALUE: f32 = 0.01; // the dimmest Value the PWM can show, one BRIGHTNESS_STEPS step
+pub const DEFAULT_MIN_GLOW: f32 = 0.05; // a faint but clearly lit glow
+pub const MAX_MIN_GLOW: f32 = 0.25; // brighter would eat a quarter of the Value pot travel
+
+/// The policy for the bottom of the Value axis
+///
+/// 1. Off: a Value of 0 turns the LED off, from the pot or anywhere else (default)
+/// 2. MinGlow: the LED is rendered at least at this Value [MIN_VISIBLE_VALUE,MAX_MIN_GLOW] whatever the Value is set
+///    to, only the soft power switching it off
+/// 3. NeverOff: the Value pot bottoms out at MIN_VISIBLE_VALUE rather than off, while an explicit Value of 0 (e.g.
+///    RTT `set v 0`) still turns the LED off
+#[derive(Clone, Copy, PartialEq)]
+pub enum ValueFloor {
+    Off,
+    MinGlow(f32),
+    NeverOff,
+}
+
+/// Impl ValueFloor
+impl ValueFloor {
+    /// PUBLIC
+    /// A MinGlow policy with glow clamped to [MIN_VISIBLE_VALUE,MAX_MIN_GLOW]
+    pub const fn min_glow(glow: f32) -> Self {
+        ValueFloor::MinGlow(glow.clamp(MIN_VISIBLE_VALUE, MAX_MIN_GLOW))
+    }
+
+    /// PUBLIC
+    /// The Value to store for a Value pot reading of value [0,1]
+    pub const fn pot_value(self, value: f32) -> f32 {
+        match self {
+            ValueFloor::NeverOff => value.max(MIN_VISIBLE_VALUE),
+            ValueFloor::Off | ValueFloor::MinGlow(_) => value,
+        }
+    }
+
+    /// PUBLIC
+    /// The Value to render for a stored Value of value [0,1]
+    pub const fn rendered_value(self, value: f32) -> f32 {
+        match self {
+            ValueFloor::MinGlow(glow) => value.max(glow),
+            ValueFloor::Off | ValueFloor::NeverOff => value,
+        }
+    }
+}
+
+// at the bottom of the pot travel: Off stores and renders 0, MinGlow stores 0 but renders the glow, and NeverOff stores
+// (and renders) the dimmest visible Value while an explicit 0 stays off. Higher Values pass through every policy
+const _: () = {
+    let off = ValueFloor::Off;
+    assert!(off.pot_value(0.0) == 0.0 && off.rendered_value(0.0) == 0.0);
+
+    let glow = ValueFloor::min_glow(DEFAULT_MIN_GLOW);
+    assert!(glow.pot_value(0.0) == 0.0);
+    assert!(glow.rendered_value(glow.pot_value(0.0)) == DEFAULT_MIN_GLOW);
+    assert!(matches!(
+        ValueFloor::min_glow(1.0),
+        ValueFloor::MinGlow(MAX_MIN_GLOW)
+    ));
+
+    let never_off = ValueFloor::NeverOff;
+    assert!(never_off.rendered_value(never_off.pot_value(0.0)) == MIN_VISIBLE_VALUE);
+    assert!(
+        never_off.rendered_value(0.0) == 0.0,
+        "NeverOff kept an explicit off lit"
+    );
+
+    let policies = [off, glow, never_off];
+    let mut i = 0;
+    while i < policies.len() {
+        assert!(policies[i].rendered_value(policies[i].pot_value(0.5)) == 0.5);
+        i += 1;
+    }
+};